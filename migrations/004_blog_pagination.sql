@@ -0,0 +1,20 @@
+-- Keyset pagination for the blog listing.
+--
+-- Pages are ordered by (published_at DESC, id DESC) and continue from the
+-- last row of the previous page, so this index covers both the ordering and
+-- the cursor comparison.
+CREATE INDEX IF NOT EXISTS idx_blog_posts_published_keyset
+    ON blog_posts (published_at DESC, id DESC)
+    WHERE is_published;
+
+-- Estimated reading time in minutes at 200 words per minute.
+--
+-- PostgREST exposes functions taking a table row as computed columns, so the
+-- listing can `select=...,reading_time` without downloading `content`.
+CREATE OR REPLACE FUNCTION reading_time(post blog_posts)
+RETURNS INTEGER AS $$
+    SELECT GREATEST(
+        1,
+        CEIL(COALESCE(array_length(regexp_split_to_array(btrim(post.content), '\s+'), 1), 0) / 200.0)
+    )::INTEGER;
+$$ LANGUAGE sql IMMUTABLE;
@@ -0,0 +1,44 @@
+-- Tag archives for the blog.
+--
+-- Tags are stored as written ("3D Printing"), but archive URLs use a slug
+-- ("3d-printing") so that spelling variants land on the same page. The slug
+-- rule here must match `tag_slug` in web/src/api/tags.rs.
+CREATE OR REPLACE FUNCTION tag_slug(tag TEXT)
+RETURNS TEXT AS $$
+    SELECT btrim(regexp_replace(lower(tag), '[^a-z0-9]+', '-', 'g'), '-');
+$$ LANGUAGE sql IMMUTABLE STRICT;
+
+CREATE OR REPLACE FUNCTION blog_tag_slugs(tags JSONB)
+RETURNS TEXT[] AS $$
+    SELECT COALESCE(array_agg(tag_slug(tag)), '{}')
+    FROM jsonb_array_elements_text(COALESCE(tags, '[]'::jsonb)) AS tag;
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Containment lookups on the normalized slugs (`blog_tag_slugs(tags) @> ...`).
+CREATE INDEX IF NOT EXISTS idx_blog_posts_tag_slugs
+    ON blog_posts USING GIN (blog_tag_slugs(tags))
+    WHERE is_published;
+
+-- Published posts carrying a tag, by slug. Returns whole rows so PostgREST
+-- can still apply `select`, `order` and computed columns such as
+-- `reading_time`.
+CREATE OR REPLACE FUNCTION blog_posts_by_tag(tag TEXT)
+RETURNS SETOF blog_posts AS $$
+    SELECT *
+    FROM blog_posts
+    WHERE is_published AND blog_tag_slugs(tags) @> ARRAY[tag];
+$$ LANGUAGE sql STABLE;
+
+-- Every tag on a published post with the number of posts using it. Spelling
+-- variants are merged under the most common spelling.
+CREATE OR REPLACE FUNCTION blog_tag_counts()
+RETURNS TABLE (name TEXT, slug TEXT, count INTEGER) AS $$
+    SELECT
+        mode() WITHIN GROUP (ORDER BY tag) AS name,
+        tag_slug(tag) AS slug,
+        COUNT(DISTINCT post.id)::INTEGER AS count
+    FROM blog_posts post, jsonb_array_elements_text(post.tags) AS tag
+    WHERE post.is_published AND tag_slug(tag) <> ''
+    GROUP BY tag_slug(tag)
+    ORDER BY count DESC, slug;
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,59 @@
+-- Full-text search across blog posts and projects.
+--
+-- Each table gets a stored, weighted `search_vector`: titles and tags rank
+-- highest (A), then excerpts and descriptions (B), then post bodies (C).
+-- `search_site`, defined in 007 once posts can be scheduled, is the single
+-- entry point used by the `search` server function (web/src/api/search.rs).
+
+-- array_to_string is only STABLE, which generated columns don't accept.
+-- For TEXT[] it is immutable in practice.
+CREATE OR REPLACE FUNCTION immutable_array_to_string(items TEXT[])
+RETURNS TEXT AS $$
+    SELECT array_to_string(items, ' ');
+$$ LANGUAGE sql IMMUTABLE;
+
+ALTER TABLE blog_posts
+    ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A')
+        || setweight(jsonb_to_tsvector('english', coalesce(tags, '[]'::jsonb), '["string"]'), 'A')
+        || setweight(to_tsvector('english', coalesce(excerpt, '')), 'B')
+        || setweight(to_tsvector('english', coalesce(content, '')), 'C')
+    ) STORED;
+
+ALTER TABLE projects
+    ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A')
+        || setweight(to_tsvector('english', coalesce(immutable_array_to_string(technologies), '')), 'A')
+        || setweight(to_tsvector('english', coalesce(description, '')), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_blog_posts_search ON blog_posts USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_projects_search ON projects USING GIN (search_vector);
+
+-- Turn what the visitor typed into a prefix query: "rust fin" becomes
+-- 'rust':* & 'fin':*, so results show up while the last word is still being
+-- typed. Returns NULL when nothing searchable is left.
+CREATE OR REPLACE FUNCTION search_prefix_query(query TEXT)
+RETURNS tsquery AS $$
+    SELECT to_tsquery('english', string_agg(quote_literal(word) || ':*', ' & '))
+    FROM regexp_split_to_table(
+        btrim(regexp_replace(lower(coalesce(query, '')), '[^[:alnum:]]+', ' ', 'g')),
+        ' '
+    ) AS word
+    WHERE word <> '';
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Markdown reduced to roughly what a reader sees, for snippets: code blocks,
+-- images and HTML tags go, links keep their text, and emphasis, code, heading,
+-- quote and list markers are dropped.
+CREATE OR REPLACE FUNCTION markdown_text(source TEXT)
+RETURNS TEXT AS $$
+    SELECT regexp_replace(regexp_replace(regexp_replace(regexp_replace(regexp_replace(regexp_replace(
+        coalesce(source, ''),
+        '(```|~~~).*?(```|~~~)', ' ', 'g'),
+        '!\[[^]]*\]\([^)]*\)', ' ', 'g'),
+        '\[([^]]*)\]\([^)]*\)', '\1', 'g'),
+        '<[^>]+>', ' ', 'g'),
+        '^[ \t]*(#{1,6}|>|[-*+]|[0-9]+\.)[ \t]+', '', 'gn'),
+        '\*{1,3}|_{2,3}|~~|`', '', 'g');
+$$ LANGUAGE sql IMMUTABLE;
@@ -0,0 +1,72 @@
+-- Scheduled publishing.
+--
+-- A post is public once it is published *and* its `published_at` has
+-- passed, so authors can publish ahead of time by picking a future date.
+-- Every public read applies this rule: the row level security policy below,
+-- the functions from 005, `search_site` below, and `only_public` /
+-- `is_public` in web/src/api/blog.rs. Drafts are reachable only through
+-- signed preview links, which the server reads with the service role key.
+
+DROP POLICY IF EXISTS "Public blog posts are viewable by everyone" ON blog_posts;
+CREATE POLICY "Public blog posts are viewable by everyone" ON blog_posts
+    FOR SELECT USING (is_published = true AND published_at <= NOW());
+
+CREATE OR REPLACE FUNCTION blog_posts_by_tag(tag TEXT)
+RETURNS SETOF blog_posts AS $$
+    SELECT *
+    FROM blog_posts
+    WHERE is_published AND published_at <= NOW() AND blog_tag_slugs(tags) @> ARRAY[tag];
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION blog_tag_counts()
+RETURNS TABLE (name TEXT, slug TEXT, count INTEGER) AS $$
+    SELECT
+        mode() WITHIN GROUP (ORDER BY tag) AS name,
+        tag_slug(tag) AS slug,
+        COUNT(DISTINCT post.id)::INTEGER AS count
+    FROM blog_posts post, jsonb_array_elements_text(post.tags) AS tag
+    WHERE post.is_published AND post.published_at <= NOW() AND tag_slug(tag) <> ''
+    GROUP BY tag_slug(tag)
+    ORDER BY count DESC, slug;
+$$ LANGUAGE sql STABLE;
+
+-- Ranked hits from blog posts and projects (see 006). `snippet` marks
+-- matches with <mark></mark>, in the text of the post rather than its
+-- Markdown.
+CREATE OR REPLACE FUNCTION search_site(query TEXT, max_results INTEGER DEFAULT 20)
+RETURNS TABLE (kind TEXT, id BIGINT, title TEXT, slug TEXT, snippet TEXT, rank REAL) AS $$
+    WITH q AS (SELECT search_prefix_query(query) AS tsq)
+    SELECT * FROM (
+        SELECT
+            'post' AS kind,
+            post.id::BIGINT,
+            post.title::TEXT,
+            post.slug::TEXT,
+            ts_headline(
+                'english',
+                markdown_text(coalesce(post.excerpt, '') || E'\n\n' || post.content),
+                q.tsq,
+                'StartSel=<mark>, StopSel=</mark>, MaxWords=30, MinWords=12, MaxFragments=2'
+            ) AS snippet,
+            ts_rank(post.search_vector, q.tsq) AS rank
+        FROM blog_posts post, q
+        WHERE post.is_published AND post.published_at <= NOW() AND post.search_vector @@ q.tsq
+        UNION ALL
+        SELECT
+            'project',
+            project.id,
+            project.title,
+            NULL,
+            ts_headline(
+                'english',
+                project.description,
+                q.tsq,
+                'StartSel=<mark>, StopSel=</mark>, MaxWords=30, MinWords=12, MaxFragments=2'
+            ),
+            ts_rank(project.search_vector, q.tsq)
+        FROM projects project, q
+        WHERE project.search_vector @@ q.tsq
+    ) hits
+    ORDER BY rank DESC, title
+    LIMIT max_results;
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,70 @@
+-- Revision history for blog posts.
+--
+-- Every insert and update of `blog_posts` stores the resulting version in
+-- `blog_post_revisions`, so no edit ever loses text. The newest revision of
+-- a post is always its current state.
+--
+-- The change note travels with the write itself: set `revision_note` in the
+-- same insert or update and the trigger moves it onto the revision, leaving
+-- the column empty again. The author is the email in the caller's JWT, or
+-- the database role for writes made outside the API.
+
+ALTER TABLE blog_posts ADD COLUMN IF NOT EXISTS revision_note TEXT;
+
+CREATE TABLE IF NOT EXISTS blog_post_revisions (
+    id SERIAL PRIMARY KEY,
+    -- Deferred, because the revision of a new post is written before the
+    -- post row itself.
+    post_id INTEGER NOT NULL REFERENCES blog_posts(id) ON DELETE CASCADE DEFERRABLE INITIALLY DEFERRED,
+    title VARCHAR(255) NOT NULL,
+    slug VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    excerpt TEXT,
+    tags JSONB,
+    cover_image TEXT,
+    is_published BOOLEAN,
+    published_at TIMESTAMP WITH TIME ZONE,
+    author TEXT,
+    change_note TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_blog_post_revisions_post ON blog_post_revisions (post_id, id DESC);
+
+CREATE OR REPLACE FUNCTION record_blog_post_revision()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO blog_post_revisions (
+        post_id, title, slug, content, excerpt, tags, cover_image, is_published, published_at, author, change_note
+    ) VALUES (
+        NEW.id, NEW.title, NEW.slug, NEW.content, NEW.excerpt, NEW.tags, NEW.cover_image, NEW.is_published,
+        NEW.published_at,
+        COALESCE(NULLIF(current_setting('request.jwt.claims', true), '')::json ->> 'email', current_user),
+        NULLIF(btrim(NEW.revision_note), '')
+    );
+    NEW.revision_note := NULL;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS record_blog_post_revision ON blog_posts;
+CREATE TRIGGER record_blog_post_revision
+    BEFORE INSERT OR UPDATE ON blog_posts
+    FOR EACH ROW
+    EXECUTE FUNCTION record_blog_post_revision();
+
+-- Posts written before this migration start their history here.
+INSERT INTO blog_post_revisions (
+    post_id, title, slug, content, excerpt, tags, cover_image, is_published, published_at, change_note, created_at
+)
+SELECT id, title, slug, content, excerpt, tags, cover_image, is_published, published_at,
+       'Version from before revision history', COALESCE(updated_at, created_at, NOW())
+FROM blog_posts post
+WHERE NOT EXISTS (SELECT 1 FROM blog_post_revisions revision WHERE revision.post_id = post.id);
+
+-- History is read only by the dashboard's server functions, with the service
+-- role key after they have checked the author, so no signed-in user gets a
+-- policy. The trigger writes it during those same service role writes.
+ALTER TABLE blog_post_revisions ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Authenticated users can manage blog post revisions" ON blog_post_revisions;
@@ -0,0 +1,35 @@
+-- Responsive image variants for uploaded images.
+--
+-- Uploaded images are stored as several widths in AVIF, WebP and JPEG plus a
+-- blur placeholder (see `web/src/api/images.rs`). The variant set is kept as
+-- JSON next to the image URL it belongs to; pages only use it while that URL
+-- is still the one in `cover_image` / `image_url`.
+
+ALTER TABLE blog_posts ADD COLUMN IF NOT EXISTS cover_image_variants JSONB;
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS image_variants JSONB;
+
+-- Revisions keep the variants, so restoring an old cover restores its
+-- variants too.
+ALTER TABLE blog_post_revisions ADD COLUMN IF NOT EXISTS cover_image_variants JSONB;
+
+CREATE OR REPLACE FUNCTION record_blog_post_revision()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO blog_post_revisions (
+        post_id, title, slug, content, excerpt, tags, cover_image, cover_image_variants, is_published,
+        published_at, author, change_note
+    ) VALUES (
+        NEW.id, NEW.title, NEW.slug, NEW.content, NEW.excerpt, NEW.tags, NEW.cover_image,
+        NEW.cover_image_variants, NEW.is_published, NEW.published_at,
+        COALESCE(NULLIF(current_setting('request.jwt.claims', true), '')::json ->> 'email', current_user),
+        NULLIF(btrim(NEW.revision_note), '')
+    );
+    NEW.revision_note := NULL;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Uploads are written by the server with the service role key, after it has
+-- checked the author, so signed-in users get no write access to the bucket.
+DROP POLICY IF EXISTS "Authenticated users can upload blog images" ON storage.objects;
+DROP POLICY IF EXISTS "Authenticated users can replace blog images" ON storage.objects;
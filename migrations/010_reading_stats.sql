@@ -0,0 +1,38 @@
+-- Stored word count and reading time for blog posts.
+--
+-- Both are computed by the app from the post's Markdown (see
+-- `web/src/content/reading.rs`), which leaves code blocks out and counts
+-- images at a fixed cost, and are written with every save from the
+-- dashboard. Listings read these columns instead of downloading `content`.
+--
+-- They replace the `reading_time(blog_posts)` computed column from
+-- `004_blog_pagination.sql`, which counted every whitespace-separated token,
+-- code included.
+
+DROP FUNCTION IF EXISTS reading_time(blog_posts);
+
+ALTER TABLE blog_posts ADD COLUMN IF NOT EXISTS word_count INTEGER;
+ALTER TABLE blog_posts ADD COLUMN IF NOT EXISTS reading_time INTEGER;
+-- SHA-256 (hex) of the `content` the counts were made from.
+ALTER TABLE blog_posts ADD COLUMN IF NOT EXISTS reading_stats_sha256 TEXT;
+
+-- Content written outside the dashboard (SQL, the table editor) comes without
+-- matching counts. Clear them so the app counts the post again when it is
+-- listed, rather than showing figures for an older text.
+CREATE OR REPLACE FUNCTION clear_stale_reading_stats()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.reading_stats_sha256 IS DISTINCT FROM encode(sha256(convert_to(NEW.content, 'UTF8')), 'hex') THEN
+        NEW.word_count := NULL;
+        NEW.reading_time := NULL;
+        NEW.reading_stats_sha256 := NULL;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS clear_stale_reading_stats ON blog_posts;
+CREATE TRIGGER clear_stale_reading_stats
+    BEFORE INSERT OR UPDATE ON blog_posts
+    FOR EACH ROW
+    EXECUTE FUNCTION clear_stale_reading_stats();
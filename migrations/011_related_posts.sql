@@ -0,0 +1,33 @@
+-- Related posts for the end of a blog post.
+--
+-- Posts are ranked by the tags they share with the given post (one point
+-- per tag, compared by slug) plus how well their `search_vector` (from 006)
+-- matches the words of its title, tags and excerpt. Posts with nothing in
+-- common are left out. Used by the `get_post_links` server function
+-- (web/src/api/related.rs), which selects the summary columns from it.
+
+CREATE OR REPLACE FUNCTION related_blog_posts(post_id INTEGER, max_results INTEGER DEFAULT 3)
+RETURNS SETOF blog_posts AS $$
+    WITH source AS (
+        SELECT
+            blog_tag_slugs(tags) AS tag_slugs,
+            -- Any of the title, tag and excerpt lexemes (weights A and B).
+            (
+                SELECT to_tsquery('simple', string_agg(quote_literal(lexeme), ' | '))
+                FROM unnest(tsvector_to_array(ts_filter(search_vector, '{a,b}'))) AS lexeme
+            ) AS terms
+        FROM blog_posts
+        WHERE id = post_id
+    )
+    SELECT post.*
+    FROM blog_posts post
+    CROSS JOIN source
+    CROSS JOIN LATERAL (
+        SELECT (
+            SELECT COUNT(*) FROM unnest(blog_tag_slugs(post.tags)) AS tag WHERE tag = ANY(source.tag_slugs)
+        ) + COALESCE(ts_rank(post.search_vector, source.terms), 0) AS score
+    ) ranked
+    WHERE post.id <> post_id AND post.is_published AND post.published_at <= NOW() AND ranked.score > 0
+    ORDER BY ranked.score DESC, post.published_at DESC, post.id DESC
+    LIMIT max_results;
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,38 @@
+-- Multi-part blog series.
+--
+-- A series is a named, ordered run of posts with its own page at
+-- `/blog/series/<slug>`. A post belongs to at most one series; `series_part`
+-- orders the posts within it (ties go to the earlier publish date). Parts
+-- are numbered on the site by position among the public posts, so drafts
+-- and gaps in `series_part` don't leave holes.
+
+CREATE TABLE IF NOT EXISTS blog_series (
+    id SERIAL PRIMARY KEY,
+    title VARCHAR(255) NOT NULL,
+    slug VARCHAR(255) UNIQUE NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+DROP TRIGGER IF EXISTS update_blog_series_updated_at ON blog_series;
+CREATE TRIGGER update_blog_series_updated_at
+    BEFORE UPDATE ON blog_series
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Deleting a series leaves its posts in place, outside any series.
+ALTER TABLE blog_posts ADD COLUMN IF NOT EXISTS series_id INTEGER REFERENCES blog_series(id) ON DELETE SET NULL;
+ALTER TABLE blog_posts ADD COLUMN IF NOT EXISTS series_part INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_blog_posts_series ON blog_posts(series_id, series_part);
+
+ALTER TABLE blog_series ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Blog series are viewable by everyone" ON blog_series;
+CREATE POLICY "Blog series are viewable by everyone" ON blog_series
+    FOR SELECT USING (true);
+
+-- Series are written only by the dashboard, with the service role key after
+-- it has checked the author.
+DROP POLICY IF EXISTS "Authenticated users can modify blog series" ON blog_series;
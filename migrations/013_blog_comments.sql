@@ -0,0 +1,41 @@
+-- Reader comments on blog posts.
+--
+-- Comments are threaded through `parent_id` and wait in the moderation queue
+-- (`status = 'pending'`) until an author approves them or marks them as spam.
+-- Readers only ever see approved comments. New comments are written by the
+-- `submit_comment` server function (web/src/api/comments.rs) with the service
+-- role key, after its rate limit and checks, so there is no insert policy for
+-- anonymous visitors. Moderation goes through the server with the same key,
+-- once it has checked that the caller is an author (`ADMIN_EMAILS`); no policy
+-- opens the queue to signed-in users, who can sign themselves up.
+
+CREATE TABLE IF NOT EXISTS blog_comments (
+    id SERIAL PRIMARY KEY,
+    post_id INTEGER NOT NULL REFERENCES blog_posts(id) ON DELETE CASCADE,
+    -- The comment this one replies to; NULL for a top-level comment.
+    parent_id INTEGER REFERENCES blog_comments(id) ON DELETE CASCADE,
+    author_name VARCHAR(80) NOT NULL,
+    -- The Supabase user who wrote it, when they were signed in.
+    author_id UUID,
+    body TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'spam')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+DROP TRIGGER IF EXISTS update_blog_comments_updated_at ON blog_comments;
+CREATE TRIGGER update_blog_comments_updated_at
+    BEFORE UPDATE ON blog_comments
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_blog_comments_post ON blog_comments(post_id, status, created_at);
+CREATE INDEX IF NOT EXISTS idx_blog_comments_status ON blog_comments(status, created_at DESC);
+
+ALTER TABLE blog_comments ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Approved comments are viewable by everyone" ON blog_comments;
+CREATE POLICY "Approved comments are viewable by everyone" ON blog_comments
+    FOR SELECT USING (status = 'approved');
+
+DROP POLICY IF EXISTS "Authenticated users can moderate comments" ON blog_comments;
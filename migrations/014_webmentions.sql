@@ -0,0 +1,63 @@
+-- Webmentions (https://www.w3.org/TR/webmention/) in both directions.
+--
+-- `webmentions` holds mentions other sites send to `/webmention`. They stay
+-- `pending` until the server has fetched the source and found the link to
+-- the post; only `verified` ones are shown under the post. A source that
+-- stops linking to us, or is gone, has its mention removed.
+--
+-- `webmentions_sent` remembers which links in each public post have been
+-- notified, and for which version of the post, so every link is notified
+-- once per edit. Links removed by an edit are notified too, so the other site
+-- can drop the mention.
+--
+-- Both tables are written by the server with the service role key
+-- (web/src/server/webmention.rs), so neither has a write policy.
+
+CREATE TABLE IF NOT EXISTS webmentions (
+    id SERIAL PRIMARY KEY,
+    post_id INTEGER NOT NULL REFERENCES blog_posts(id) ON DELETE CASCADE,
+    source TEXT NOT NULL,
+    target TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'verified')),
+    kind TEXT NOT NULL DEFAULT 'mention' CHECK (kind IN ('mention', 'reply', 'like', 'repost')),
+    -- From the source's h-entry and its author's h-card, when it has them.
+    author_name TEXT,
+    author_url TEXT,
+    author_photo TEXT,
+    content TEXT,
+    url TEXT,
+    published_at TIMESTAMP WITH TIME ZONE,
+    verified_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE (source, target)
+);
+
+DROP TRIGGER IF EXISTS update_webmentions_updated_at ON webmentions;
+CREATE TRIGGER update_webmentions_updated_at
+    BEFORE UPDATE ON webmentions
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_webmentions_post ON webmentions(post_id, status);
+
+CREATE TABLE IF NOT EXISTS webmentions_sent (
+    id SERIAL PRIMARY KEY,
+    post_id INTEGER NOT NULL REFERENCES blog_posts(id) ON DELETE CASCADE,
+    target TEXT NOT NULL,
+    -- `blog_posts.updated_at` of the version that was notified.
+    post_updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- The target's endpoint and its answer; NULL when it has no endpoint.
+    endpoint TEXT,
+    status_code INTEGER,
+    error TEXT,
+    sent_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE (post_id, target)
+);
+
+ALTER TABLE webmentions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webmentions_sent ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Verified webmentions are viewable by everyone" ON webmentions;
+CREATE POLICY "Verified webmentions are viewable by everyone" ON webmentions
+    FOR SELECT USING (status = 'verified');
@@ -0,0 +1,6 @@
+-- Contact messages are saved only by the `submit_contact_form` server
+-- function (web/src/api/contact.rs), which validates them and records the
+-- sender's address, using the service role key. Visitors can no longer insert
+-- rows directly with the anon key.
+
+DROP POLICY IF EXISTS "Anyone can submit contact forms" ON contact_submissions;
@@ -0,0 +1,59 @@
+-- Shared rate limit counters, used instead of the in-memory ones when the
+-- server runs with `RATE_LIMIT_STORE=postgres` (web/src/server/rate_limit.rs),
+-- so limits hold across restarts and several server instances.
+--
+-- Each row is one counted request. Keys are SHA-256 hashes of what is being
+-- limited (an IP address, an email address), so no addresses are kept here.
+-- Only the server calls `hit_rate_limits`, with the service role key.
+
+CREATE TABLE IF NOT EXISTS rate_limit_hits (
+    id BIGSERIAL PRIMARY KEY,
+    bucket TEXT NOT NULL,
+    key TEXT NOT NULL,
+    hit_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_hits_key ON rate_limit_hits(bucket, key, hit_at);
+
+ALTER TABLE rate_limit_hits ENABLE ROW LEVEL SECURITY;
+
+-- Count one request against several limits at once and say whether it is
+-- allowed: true while, for every `{bucket, key, max_hits, window_seconds}` in
+-- `hits`, fewer than `max_hits` were counted for the key in the last
+-- `window_seconds`. A refused request isn't counted against any of them. Old
+-- rows for the keys are cleared as it goes.
+DROP FUNCTION IF EXISTS hit_rate_limit(TEXT, TEXT, INTEGER, INTEGER);
+CREATE OR REPLACE FUNCTION hit_rate_limits(hits JSONB)
+RETURNS BOOLEAN AS $$
+DECLARE
+    hit JSONB;
+    recent INTEGER;
+BEGIN
+    -- One request per key at a time, so two at once can't both squeeze in.
+    -- Keys are locked in a fixed order so two requests can't wait on each other.
+    FOR hit IN SELECT value FROM jsonb_array_elements(hits) ORDER BY value->>'bucket', value->>'key' LOOP
+        PERFORM pg_advisory_xact_lock(hashtext((hit->>'bucket') || ':' || (hit->>'key')));
+
+        DELETE FROM rate_limit_hits
+        WHERE bucket = hit->>'bucket' AND key = hit->>'key'
+            AND hit_at <= NOW() - make_interval(secs => (hit->>'window_seconds')::INTEGER);
+
+        SELECT COUNT(*) INTO recent FROM rate_limit_hits WHERE bucket = hit->>'bucket' AND key = hit->>'key';
+        IF recent >= (hit->>'max_hits')::INTEGER THEN
+            RETURN FALSE;
+        END IF;
+    END LOOP;
+
+    INSERT INTO rate_limit_hits (bucket, key)
+    SELECT value->>'bucket', value->>'key' FROM jsonb_array_elements(hits);
+    RETURN TRUE;
+END;
+$$ LANGUAGE plpgsql;
+
+REVOKE EXECUTE ON FUNCTION hit_rate_limits(JSONB) FROM PUBLIC;
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'anon') THEN
+        REVOKE EXECUTE ON FUNCTION hit_rate_limits(JSONB) FROM anon, authenticated;
+    END IF;
+END $$;
@@ -0,0 +1,39 @@
+-- Outgoing email, queued.
+--
+-- The server renders each message when something happens (a new contact
+-- message, for now) and stores it here; a background task
+-- (web/src/server/mail) sends what is due. A failed send is tried again later,
+-- waiting longer each time, until `attempts` reaches the limit and the
+-- message is marked `failed`. So a mail outage delays mail but never fails
+-- whatever queued it.
+--
+-- Only the server reads and writes this table, with the service role key.
+
+CREATE TABLE IF NOT EXISTS mail_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    -- What the message is, e.g. 'contact_notification'.
+    kind TEXT NOT NULL,
+    to_address TEXT NOT NULL,
+    reply_to TEXT,
+    subject TEXT NOT NULL,
+    text_body TEXT NOT NULL,
+    html_body TEXT NOT NULL,
+    contact_submission_id UUID REFERENCES contact_submissions(id) ON DELETE SET NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'sent', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+DROP TRIGGER IF EXISTS update_mail_outbox_updated_at ON mail_outbox;
+CREATE TRIGGER update_mail_outbox_updated_at
+    BEFORE UPDATE ON mail_outbox
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_mail_outbox_due ON mail_outbox(status, next_attempt_at);
+
+ALTER TABLE mail_outbox ENABLE ROW LEVEL SECURITY;
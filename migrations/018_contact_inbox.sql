@@ -0,0 +1,29 @@
+-- The contact message inbox (web/src/views/admin_inbox.rs).
+--
+-- Each message moves through `status`: new messages arrive 'unread' (or
+-- 'spam', when the spam checks flag them), become 'read' once opened and
+-- 'replied' once answered, and end up 'archived'. The transitions allowed are
+-- in `ContactStatus::next` (web/src/api/inbox.rs); the check here only keeps
+-- the column to the known values.
+--
+-- Only the server reads and writes messages, with the service role key: the
+-- inbox functions check that the caller is an author (`ADMIN_EMAILS`) first.
+-- No policy opens the table to signed-in users, who can sign themselves up.
+
+UPDATE contact_submissions
+    SET status = 'unread'
+    WHERE status IS NULL OR status NOT IN ('unread', 'read', 'replied', 'archived', 'spam');
+
+ALTER TABLE contact_submissions ALTER COLUMN status SET DEFAULT 'unread';
+ALTER TABLE contact_submissions ALTER COLUMN status SET NOT NULL;
+
+ALTER TABLE contact_submissions DROP CONSTRAINT IF EXISTS contact_submissions_status_check;
+ALTER TABLE contact_submissions ADD CONSTRAINT contact_submissions_status_check
+    CHECK (status IN ('unread', 'read', 'replied', 'archived', 'spam'));
+
+-- The inbox lists one status at a time, newest first.
+CREATE INDEX IF NOT EXISTS idx_contact_submissions_status_created
+    ON contact_submissions(status, created_at DESC);
+
+DROP POLICY IF EXISTS "Authenticated users can read contact messages" ON contact_submissions;
+DROP POLICY IF EXISTS "Authenticated users can triage contact messages" ON contact_submissions;
@@ -0,0 +1,40 @@
+-- Replies to contact messages, sent from the inbox (web/src/views/admin_inbox.rs).
+--
+-- Each reply an author sends is queued in `mail_outbox` and recorded here,
+-- linked to the message it answers, so the inbox can show the whole
+-- conversation. Messages carry their own Message-ID, and each reply refers to
+-- the one before it, so mail clients thread them. Rows are 'outgoing' for now;
+-- 'incoming' is for answers brought in from a mailbox later, matched through
+-- `in_reply_to`.
+
+ALTER TABLE mail_outbox ADD COLUMN IF NOT EXISTS message_id TEXT;
+ALTER TABLE mail_outbox ADD COLUMN IF NOT EXISTS in_reply_to TEXT;
+
+CREATE TABLE IF NOT EXISTS contact_replies (
+    id BIGSERIAL PRIMARY KEY,
+    contact_submission_id UUID NOT NULL REFERENCES contact_submissions(id) ON DELETE CASCADE,
+    direction TEXT NOT NULL DEFAULT 'outgoing' CHECK (direction IN ('outgoing', 'incoming')),
+    from_address TEXT NOT NULL,
+    to_address TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    body TEXT NOT NULL,
+    -- The email's Message-ID, and the one it answers.
+    message_id TEXT UNIQUE,
+    in_reply_to TEXT,
+    -- How an outgoing reply is getting on.
+    mail_outbox_id BIGINT REFERENCES mail_outbox(id) ON DELETE SET NULL,
+    -- The author who wrote it.
+    sent_by TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contact_replies_submission ON contact_replies(contact_submission_id, created_at);
+
+-- Like the messages themselves, replies and the mail queue are read and
+-- written only by the server, with the service role key, after checking the
+-- caller is an author. Row level security is on with no policies.
+ALTER TABLE contact_replies ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "Authenticated users can read contact replies" ON contact_replies;
+DROP POLICY IF EXISTS "Authenticated users can send contact replies" ON contact_replies;
+DROP POLICY IF EXISTS "Authenticated users can see queued mail" ON mail_outbox;
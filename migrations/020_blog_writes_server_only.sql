@@ -0,0 +1,35 @@
+-- Blog posts are written only by the dashboard's server functions
+-- (web/src/api/blog_admin.rs), with the service role key, once they have
+-- checked that the caller is an author (`ADMIN_EMAILS`). Anyone can sign up,
+-- so signed-in users can no longer write posts, or read drafts, with their
+-- own key; readers keep the published posts.
+--
+-- Writes made with the service role key carry no author in their JWT, so the
+-- server names the author in `revision_author`, which travels with the write
+-- like `revision_note` and is moved onto the revision by the trigger.
+
+ALTER TABLE blog_posts ADD COLUMN IF NOT EXISTS revision_author TEXT;
+
+CREATE OR REPLACE FUNCTION record_blog_post_revision()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO blog_post_revisions (
+        post_id, title, slug, content, excerpt, tags, cover_image, cover_image_variants, is_published,
+        published_at, author, change_note
+    ) VALUES (
+        NEW.id, NEW.title, NEW.slug, NEW.content, NEW.excerpt, NEW.tags, NEW.cover_image,
+        NEW.cover_image_variants, NEW.is_published, NEW.published_at,
+        COALESCE(
+            NULLIF(btrim(NEW.revision_author), ''),
+            NULLIF(current_setting('request.jwt.claims', true), '')::json ->> 'email',
+            current_user
+        ),
+        NULLIF(btrim(NEW.revision_note), '')
+    );
+    NEW.revision_note := NULL;
+    NEW.revision_author := NULL;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP POLICY IF EXISTS "Authenticated users can modify blog posts" ON blog_posts;
@@ -0,0 +1,28 @@
+-- Stored reading stats for posts written outside the dashboard.
+--
+-- Such posts come without counts (see 010), so the app counts them the first
+-- time they are listed and stores the result with the service role key,
+-- writing only `word_count`, `reading_time` and `reading_stats_sha256`.
+-- That write is not an edit: the triggers below only fire for updates that
+-- set the post's own columns, so it neither bumps `updated_at` nor records a
+-- revision.
+
+DROP TRIGGER IF EXISTS update_blog_posts_updated_at ON blog_posts;
+CREATE TRIGGER update_blog_posts_updated_at
+    BEFORE UPDATE OF
+        title, slug, content, excerpt, tags, cover_image, cover_image_variants, is_published, published_at,
+        author, series_id, series_part
+    ON blog_posts
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Revisions keep the columns listed here; a note or author on its own still
+-- records one, as a dashboard save always does.
+DROP TRIGGER IF EXISTS record_blog_post_revision ON blog_posts;
+CREATE TRIGGER record_blog_post_revision
+    BEFORE INSERT OR UPDATE OF
+        title, slug, content, excerpt, tags, cover_image, cover_image_variants, is_published, published_at,
+        revision_note, revision_author
+    ON blog_posts
+    FOR EACH ROW
+    EXECUTE FUNCTION record_blog_post_revision();
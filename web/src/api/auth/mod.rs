@@ -38,3 +38,8 @@ pub use wasm_impl::*;
 mod native_impl;
 #[cfg(not(target_arch = "wasm32"))]
 pub use native_impl::*;
+
+#[cfg(feature = "server")]
+pub mod session;
+#[cfg(feature = "server")]
+pub use session::{may_author, require_author, service_client, signed_in_user};
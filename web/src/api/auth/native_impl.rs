@@ -5,6 +5,11 @@ use crate::api::env;
 
 use super::{User, Credentials};
 
+/// Sessions only live in the browser.
+pub fn access_token() -> Option<String> {
+    None
+}
+
 pub async fn get_user() -> Option<User> {
     // For native builds, return None
     None
@@ -40,9 +45,24 @@ impl ServerSupabaseClient {
         Self { client }
     }
 
+    /// A client that acts as a signed-in user, so row level security sees
+    /// the `authenticated` role. Verify the token first.
+    pub fn as_user(url: &str, api_key: &str, access_token: &str) -> Self {
+        let client = postgrest::Postgrest::new(format!("{}/rest/v1", url))
+            .insert_header("apikey", api_key)
+            .insert_header("Authorization", format!("Bearer {}", access_token));
+
+        Self { client }
+    }
+
     pub fn table(&self, table_name: &str) -> postgrest::Builder {
         self.client.from(table_name)
     }
+
+    /// Call a database function; `params` is its JSON arguments object.
+    pub fn rpc(&self, function: &str, params: impl Into<String>) -> postgrest::Builder {
+        self.client.rpc(function, params)
+    }
 }
 
 pub fn create_server_client() -> ServerSupabaseClient {
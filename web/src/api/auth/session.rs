@@ -0,0 +1,69 @@
+//! Server-side checks that a request comes from a signed-in author.
+//!
+//! The browser keeps the Supabase session (see `access_token`) and passes the
+//! access token to server functions that write. Supabase Auth is the judge of
+//! whether it's valid; `ADMIN_EMAILS`, when set, further limits which
+//! accounts may author.
+
+use dioxus::prelude::ServerFnError;
+
+use super::{ServerSupabaseClient, User};
+use crate::api::env;
+
+/// The user behind `access_token`, or an error if the token is missing,
+/// expired, or belongs to someone who isn't an author.
+pub async fn require_author(access_token: &str) -> Result<User, ServerFnError> {
+    let user = signed_in_user(access_token).await?;
+    if !may_author(&user) {
+        return Err(ServerFnError::new(format!("{} is not allowed to edit the blog", user.email)));
+    }
+    Ok(user)
+}
+
+/// The user behind `access_token`, author or not, or an error if the token is
+/// missing or expired.
+pub async fn signed_in_user(access_token: &str) -> Result<User, ServerFnError> {
+    if access_token.is_empty() {
+        return Err(ServerFnError::new("Not signed in".to_string()));
+    }
+
+    let config = env::get_env_config();
+    let response = reqwest::Client::new()
+        .get(format!("{}/auth/v1/user", config.supabase_url))
+        .header("apikey", &config.supabase_anon_key)
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to verify session: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(ServerFnError::new("Session expired or invalid, please sign in again".to_string()));
+    }
+    response
+        .json::<User>()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to parse user: {}", e)))
+}
+
+/// Whether `ADMIN_EMAILS` lets `user` author. Nobody may while it is unset,
+/// since anyone can sign up.
+pub fn may_author(user: &User) -> bool {
+    static UNSET_WARNING: std::sync::Once = std::sync::Once::new();
+
+    match env::get_env_config().admin_emails.as_deref().filter(|allowed| !allowed.trim().is_empty()) {
+        Some(allowed) => allowed.split(',').map(str::trim).any(|email| email.eq_ignore_ascii_case(&user.email)),
+        None => {
+            UNSET_WARNING.call_once(|| tracing::warn!("ADMIN_EMAILS is unset, so nobody may author"));
+            false
+        }
+    }
+}
+
+/// A database client that bypasses row level security, for reads and writes
+/// the server makes on a visitor's behalf. `None` while
+/// `SUPABASE_SERVICE_ROLE_KEY` is unset.
+pub fn service_client() -> Option<ServerSupabaseClient> {
+    let config = env::get_env_config();
+    let service_key = config.supabase_service_role_key.as_deref()?;
+    Some(ServerSupabaseClient::as_user(&config.supabase_url, &config.supabase_anon_key, service_key))
+}
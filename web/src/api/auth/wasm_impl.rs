@@ -2,33 +2,69 @@
 
 use dioxus::prelude::ServerFnError;
 use gloo::storage::Storage;
-use supabase_js_rs::*;
-use dioxus::signals::{GlobalSignal, Signal};
 use tracing::debug;
 use crate::api::env;
-use dioxus::prelude::Readable;
 use super::{User, Credentials};
 
-pub static CLIENT: GlobalSignal<SupabaseClient> = Signal::global(create_client);
+/// LocalStorage key holding the signed-in session.
+const SESSION_KEY: &str = "supabase.session";
 
-fn create_client() -> SupabaseClient {
-    let config = env::get_env_config();
-    supabase_js_rs::create_client(&config.supabase_url, &config.supabase_anon_key)
+/// Tokens from Supabase Auth, kept in LocalStorage between visits.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Session {
+    access_token: String,
+    refresh_token: String,
+}
+
+fn auth_url(path: &str) -> String {
+    format!("{}/auth/v1/{}", env::get_env_config().supabase_url, path)
+}
+
+/// The current access token, if anyone is signed in. Server functions that
+/// write take it as an argument and verify it themselves.
+pub fn access_token() -> Option<String> {
+    gloo::storage::LocalStorage::get::<Session>(SESSION_KEY)
+        .ok()
+        .map(|session| session.access_token)
 }
 
 pub async fn get_user() -> Option<User> {
-    // Get user from Supabase client
-    let client = CLIENT.cloned();
-    
-    // For now, return None - implement actual user retrieval logic here
-    None
+    let access_token = access_token()?;
+    let response = reqwest::Client::new()
+        .get(auth_url("user"))
+        .header("apikey", &env::get_env_config().supabase_anon_key)
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .ok()?;
+
+    if !response.status().is_success() {
+        debug!("Stored session was rejected: {}", response.status());
+        gloo::storage::LocalStorage::delete(SESSION_KEY);
+        return None;
+    }
+    response.json::<User>().await.ok()
 }
 
 pub async fn signin_with_password(credentials: Credentials) -> Result<String, ServerFnError> {
     debug!("Signing in with password for email: {}", credentials.email);
-    
-    // Placeholder implementation - implement actual Supabase auth here
-    Ok("WASM sign-in not fully implemented".to_string())
+
+    let response = reqwest::Client::new()
+        .post(auth_url("token?grant_type=password"))
+        .header("apikey", &env::get_env_config().supabase_anon_key)
+        .json(&credentials)
+        .send()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Sign-in request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(ServerFnError::new(format!("Sign-in failed: {}", response.status())));
+    }
+    let session = response
+        .json::<Session>()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to parse session: {}", e)))?;
+    set_session(session.access_token, session.refresh_token).await
 }
 
 pub async fn signin_with_google() -> Result<String, ServerFnError> {
@@ -40,16 +76,26 @@ pub async fn signin_with_google() -> Result<String, ServerFnError> {
 
 pub async fn signout() -> Result<String, ServerFnError> {
     debug!("Signing out");
-    
-    // Placeholder implementation - implement actual sign-out logic here
-    Ok("Sign-out not fully implemented".to_string())
+
+    if let Some(access_token) = access_token() {
+        // Revoke the refresh token too; the local session goes either way.
+        let _ = reqwest::Client::new()
+            .post(auth_url("logout"))
+            .header("apikey", &env::get_env_config().supabase_anon_key)
+            .bearer_auth(access_token)
+            .send()
+            .await;
+    }
+    gloo::storage::LocalStorage::delete(SESSION_KEY);
+    Ok("Signed out".to_string())
 }
 
 pub async fn set_session(access_token: String, refresh_token: String) -> Result<String, ServerFnError> {
     debug!("Setting session with tokens");
-    
-    // Placeholder implementation - implement actual session management here
-    Ok("Session management not fully implemented".to_string())
+
+    gloo::storage::LocalStorage::set(SESSION_KEY, Session { access_token, refresh_token })
+        .map_err(|e| ServerFnError::new(format!("Failed to store session: {}", e)))?;
+    Ok("Session stored".to_string())
 }
 
 // Server client for making API calls
@@ -1,6 +1,19 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use dioxus::prelude::*;
 
+use crate::api::images::ImageVariants;
+use crate::api::series::SeriesRef;
+use crate::content::reading_stats;
+
+/// Read a `null` column as the type's default.
+pub(crate) fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 
 
 // Define your blog post structure
@@ -16,7 +29,26 @@ pub struct BlogPost {
     pub created_at: Option<String>,
     pub tags: Vec<String>,
     pub cover_image: Option<String>,
+    /// Responsive versions of `cover_image`, when it was uploaded through the
+    /// dashboard.
+    #[serde(default)]
+    pub cover_image_variants: Option<ImageVariants>,
     pub author: String,
+    /// Words in the body, leaving out code blocks. See `content::reading`.
+    #[serde(default)]
+    pub word_count: u32,
+    /// Estimated minutes to read, images included.
+    #[serde(default)]
+    pub reading_time: u32,
+    /// The series the post is part of, and its place there. See `api::series`.
+    #[serde(default)]
+    pub series: Option<SeriesRef>,
+    #[serde(default)]
+    pub series_part: Option<i32>,
+    /// Rendered, highlighted and sanitized body. Only filled in for single
+    /// post fetches; listings leave it empty.
+    #[serde(default)]
+    pub content_html: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -30,10 +62,15 @@ pub struct SupabaseBlogPost {
     pub updated_at: Option<String>,
     pub tags: Option<serde_json::Value>, // JSON array from Supabase
     pub cover_image: Option<String>,
+    #[serde(default)]
+    pub cover_image_variants: Option<ImageVariants>,
     pub author: String,
     pub is_published: Option<bool>,
     pub created_at: Option<String>,
-
+    #[serde(default)]
+    pub series: Option<SeriesRef>,
+    #[serde(default)]
+    pub series_part: Option<i32>,
 }
 
 impl From<SupabaseBlogPost> for BlogPost {
@@ -48,6 +85,7 @@ impl From<SupabaseBlogPost> for BlogPost {
             vec![]
         };
 
+        let stats = reading_stats(&supabase_post.content);
         BlogPost {
             id: supabase_post.id,
             title: supabase_post.title,
@@ -58,86 +96,124 @@ impl From<SupabaseBlogPost> for BlogPost {
             updated_at: supabase_post.updated_at,
             tags,
             cover_image: supabase_post.cover_image,
+            cover_image_variants: supabase_post.cover_image_variants,
             author: supabase_post.author,
+            word_count: stats.word_count,
+            reading_time: stats.reading_time,
+            series: supabase_post.series,
+            series_part: supabase_post.series_part,
             created_at: supabase_post.created_at,
+            content_html: String::new(),
         }
     }
 }
 
 
 
+/// Whether a post with these fields is visible to the public: published, and
+/// not scheduled for later. Drafts and scheduled posts are only reachable
+/// through a preview link (see `api::preview`).
+#[cfg(feature = "server")]
+pub(crate) fn is_public(is_published: bool, published_at: &str, now: chrono::DateTime<chrono::Utc>) -> bool {
+    is_published && crate::server::parse_timestamp(published_at).is_some_and(|at| at <= now)
+}
+
+/// Restrict a `blog_posts` query to public posts, the same rule as
+/// `is_public` and the row level security policy in
+/// `migrations/007_scheduled_publishing.sql`.
+#[cfg(feature = "server")]
+pub(crate) fn only_public(query: postgrest::Builder) -> postgrest::Builder {
+    let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    query.eq("is_published", "true").lte("published_at", now)
+}
+
 #[server(name = GetBlog)]
 pub async fn get_blog() -> Result<Vec<BlogPost>, ServerFnError> {
     #[cfg(not(target_arch = "wasm32"))]
     {
-        use crate::api::auth::create_server_client;
-        use tracing::info;
+        fetch_blog_posts(true).await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
 
-        info!("Fetching blog posts from Supabase...");
-        
-        let client = create_server_client();
+/// The post query behind `get_blog`, shared with the feeds so both always
+/// agree on which posts exist and in what order. `public_only` leaves out
+/// drafts and posts scheduled for later.
+#[cfg(feature = "server")]
+pub async fn fetch_blog_posts(public_only: bool) -> Result<Vec<BlogPost>, ServerFnError> {
+    use crate::api::auth::create_server_client;
+    use tracing::info;
 
-        // Query blog posts with specific fields and ordering - remove any potential limits
-        let resp = client
-            .table("blog_posts")
-            .select("id,title,content,author,created_at,slug, excerpt,tags,cover_image,published_at")
-            .order("created_at.desc")
-            .limit(1000) // Explicitly set a high limit to ensure we get all blog posts
-            .execute()
-            .await;
-            
-        match resp {
-            Ok(response) => {
-                let status = response.status();
-                
-                if status.is_success() {
-                    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
-                    info!("Raw Supabase response: {}", text);
-                    info!("Response length: {} characters", text.len());
-
-                    // Try to parse the JSON response into our BlogPost struct
-                    match serde_json::from_str::<Vec<BlogPost>>(&text) {
-                        Ok(blog_posts) => {
-                            info!("Successfully parsed {} blog posts", blog_posts.len());
-                            for (i, blog_post) in blog_posts.iter().enumerate() {
-                                info!("Blog Post {}: '{}' (author: '{}')", i + 1, blog_post.title, blog_post.author);
-                            }
-                            Ok(blog_posts)
+    if let Some(posts) = crate::api::source::current().blog_posts(public_only).await {
+        return posts;
+    }
+
+    info!("Fetching blog posts from Supabase...");
+
+    let client = create_server_client();
+
+    // Query blog posts with specific fields and ordering - remove any potential limits
+    let mut query = client
+        .table("blog_posts")
+        .select("id,title,content,author,created_at,slug, excerpt,tags,cover_image,cover_image_variants,published_at,updated_at,series_part,series:blog_series(slug,title)")
+        .order("created_at.desc")
+        .limit(1000); // Explicitly set a high limit to ensure we get all blog posts
+    if public_only {
+        query = only_public(query);
+    }
+    let resp = query.execute().await;
+
+    match resp {
+        Ok(response) => {
+            let status = response.status();
+
+            if status.is_success() {
+                let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+                info!("Raw Supabase response: {}", text);
+                info!("Response length: {} characters", text.len());
+
+                // Parse into SupabaseBlogPost first, which counts words and
+                // reading time from the content, like a single post fetch
+                match serde_json::from_str::<Vec<SupabaseBlogPost>>(&text) {
+                    Ok(supabase_posts) => {
+                        info!("Successfully parsed {} blog posts", supabase_posts.len());
+                        for (i, blog_post) in supabase_posts.iter().enumerate() {
+                            info!("Blog Post {}: '{}' (author: '{}')", i + 1, blog_post.title, blog_post.author);
                         }
-                        Err(parse_error) => {
-                            info!("JSON parsing failed: {}", parse_error);
-                            info!("Attempting to parse as serde_json::Value for debugging...");
-                            
-                            match serde_json::from_str::<serde_json::Value>(&text) {
-                                Ok(value) => {
-                                    info!("Raw JSON structure: {:#}", value);
-                                    if let Some(array) = value.as_array() {
-                                        info!("Found {} items in JSON array", array.len());
-                                    }
+                        Ok(supabase_posts.into_iter().map(BlogPost::from).collect())
+                    }
+                    Err(parse_error) => {
+                        info!("JSON parsing failed: {}", parse_error);
+                        info!("Attempting to parse as serde_json::Value for debugging...");
+
+                        match serde_json::from_str::<serde_json::Value>(&text) {
+                            Ok(value) => {
+                                info!("Raw JSON structure: {:#}", value);
+                                if let Some(array) = value.as_array() {
+                                    info!("Found {} items in JSON array", array.len());
                                 }
-                                Err(_) => info!("Response is not valid JSON at all")
                             }
-
-                            Err(ServerFnError::new(format!("Failed to parse blog posts JSON: {}", parse_error)))
+                            Err(_) => info!("Response is not valid JSON at all")
                         }
+
+                        Err(ServerFnError::new(format!("Failed to parse blog posts JSON: {}", parse_error)))
                     }
-                } else {
-                    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
-                    let error_msg = format!("Failed to fetch blog posts. Status: {}, Response: {}", status, text);
-                    info!("{}", error_msg);
-                    Err(ServerFnError::new(error_msg))
                 }
-            }
-            Err(e) => {
-                let error_msg = format!("Request failed: {}", e);
+            } else {
+                let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+                let error_msg = format!("Failed to fetch blog posts. Status: {}, Response: {}", status, text);
                 info!("{}", error_msg);
                 Err(ServerFnError::new(error_msg))
             }
         }
-    }
-    #[cfg(target_arch = "wasm32")]
-    {
-        Err(ServerFnError::new("Server function called on client side".to_string()))
+        Err(e) => {
+            let error_msg = format!("Request failed: {}", e);
+            info!("{}", error_msg);
+            Err(ServerFnError::new(error_msg))
+        }
     }
 }
 
@@ -150,17 +226,22 @@ pub async fn get_blog_with_slug(slug: String) -> Result<BlogPost, ServerFnError>
         use crate::api::auth::create_server_client;
         use tracing::info;
 
+        if let Some(post) = crate::api::source::current().blog_post(&slug).await {
+            return post;
+        }
+
         info!("Fetching blog post with slug '{}' from Supabase...", slug);
         
         let client = create_server_client();
 
         // Query specific blog post by slug
-        let resp = client
-            .table("blog_posts")
-            .select("id,title,content,author,created_at,slug,excerpt,tags,cover_image,published_at,updated_at")
-            .eq("slug", &slug)
-            .eq("is_published", "true") // Only fetch published posts
-            .single() // Use single() to get one result instead of an array
+        let resp = only_public(
+            client
+                .table("blog_posts")
+                .select("id,title,content,author,created_at,slug,excerpt,tags,cover_image,cover_image_variants,published_at,updated_at,series_part,series:blog_series(slug,title)")
+                .eq("slug", &slug),
+        )
+        .single() // Use single() to get one result instead of an array
             .execute()
             .await;
             
@@ -176,7 +257,8 @@ pub async fn get_blog_with_slug(slug: String) -> Result<BlogPost, ServerFnError>
                     match serde_json::from_str::<SupabaseBlogPost>(&text) {
                         Ok(supabase_post) => {
                             info!("Successfully parsed blog post: '{}'", supabase_post.title);
-                            let blog_post: BlogPost = supabase_post.into();
+                            let mut blog_post: BlogPost = supabase_post.into();
+                            blog_post.content_html = crate::content::rendered_post_html(blog_post.id, &blog_post.content);
                             Ok(blog_post)
                         }
                         Err(parse_error) => {
@@ -215,4 +297,349 @@ pub async fn get_blog_with_slug(slug: String) -> Result<BlogPost, ServerFnError>
     {
         Err(ServerFnError::new("Server function called on client side".to_string()))
     }
-}
\ No newline at end of file
+}
+
+/// Posts per page on the blog listing.
+pub const BLOG_PAGE_SIZE: usize = 9;
+
+/// Columns behind `BlogPostSummary`. The counts are stored with each save
+/// (see `migrations/010_reading_stats.sql`), so `content` never leaves the
+/// database for a listing. The series is embedded through `series_id`.
+#[cfg(feature = "server")]
+pub(crate) const SUMMARY_COLUMNS: &str = "id,title,author,slug,excerpt,tags,cover_image,cover_image_variants,published_at,word_count,reading_time,series_part,series:blog_series(slug,title)";
+
+/// What a listing card needs: everything but the body.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlogPostSummary {
+    pub id: i32,
+    pub title: String,
+    pub author: String,
+    pub slug: String,
+    pub excerpt: String,
+    pub tags: Vec<String>,
+    pub cover_image: Option<String>,
+    #[serde(default)]
+    pub cover_image_variants: Option<ImageVariants>,
+    pub published_at: String,
+    /// As on `BlogPost`. Both are 0 for posts the database has no counts for
+    /// yet, until `fill_reading_stats` counts and stores them.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub word_count: u32,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub reading_time: u32,
+    #[serde(default)]
+    pub series: Option<SeriesRef>,
+    #[serde(default)]
+    pub series_part: Option<i32>,
+}
+
+impl From<&BlogPost> for BlogPostSummary {
+    fn from(post: &BlogPost) -> Self {
+        BlogPostSummary {
+            id: post.id,
+            title: post.title.clone(),
+            author: post.author.clone(),
+            slug: post.slug.clone(),
+            excerpt: post.excerpt.clone(),
+            tags: post.tags.clone(),
+            cover_image: post.cover_image.clone(),
+            cover_image_variants: post.cover_image_variants.clone(),
+            published_at: post.published_at.clone(),
+            word_count: post.word_count,
+            reading_time: post.reading_time,
+            series: post.series.clone(),
+            series_part: post.series_part,
+        }
+    }
+}
+
+/// Count the posts whose counts aren't stored, because they were written
+/// outside the dashboard, from their content, and store the counts so the
+/// next listing reads them (see `migrations/021_reading_stats_backfill.sql`).
+#[cfg(feature = "server")]
+pub(crate) async fn fill_reading_stats(posts: &mut [BlogPostSummary]) -> Result<(), ServerFnError> {
+    use crate::api::auth::{create_server_client, service_client};
+    use crate::api::blog_admin::add_reading_stats;
+    use tracing::{info, warn};
+
+    #[derive(Deserialize)]
+    struct Content {
+        id: i32,
+        content: String,
+    }
+
+    let missing: Vec<String> = posts
+        .iter()
+        .filter(|post| post.reading_time == 0)
+        .map(|post| post.id.to_string())
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    info!("Counting words of {} posts without stored counts", missing.len());
+    let response = create_server_client()
+        .table("blog_posts")
+        .select("id,content")
+        .in_("id", missing)
+        .execute()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Request failed: {}", e)))?;
+    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+    let contents: Vec<Content> = serde_json::from_str(&text)
+        .map_err(|e| ServerFnError::new(format!("Failed to parse blog post contents JSON: {}", e)))?;
+
+    let store = service_client();
+    if store.is_none() {
+        warn!("SUPABASE_SERVICE_ROLE_KEY is unset, so reading stats are counted again on every listing");
+    }
+    for post in posts.iter_mut() {
+        let Some(found) = contents.iter().find(|found| found.id == post.id) else {
+            continue;
+        };
+        let stats = reading_stats(&found.content);
+        post.word_count = stats.word_count;
+        post.reading_time = stats.reading_time;
+
+        if let Some(client) = &store {
+            let mut body = serde_json::json!({});
+            add_reading_stats(&mut body, &found.content);
+            let stored = client
+                .table("blog_posts")
+                .eq("id", post.id.to_string())
+                .update(body.to_string())
+                .execute()
+                .await;
+            match stored {
+                Ok(response) if response.status().is_success() => {}
+                Ok(response) => warn!("Storing reading stats of post {} failed: {}", post.id, response.status()),
+                Err(e) => warn!("Storing reading stats of post {} failed: {}", post.id, e),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One page of the listing, newest first.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlogPage {
+    pub posts: Vec<BlogPostSummary>,
+    /// Pass to `get_blog_page_after` for the posts following this page.
+    pub next_cursor: Option<String>,
+    pub total_pages: u32,
+}
+
+/// Keyset position in the listing: the last post already shown. Posts are
+/// ordered by `(published_at DESC, id DESC)`, so the id breaks ties between
+/// posts published at the same instant.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct BlogCursor {
+    pub published_at: String,
+    pub id: i32,
+}
+
+impl BlogCursor {
+    pub fn after(post: &BlogPostSummary) -> Self {
+        BlogCursor { published_at: post.published_at.clone(), id: post.id }
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{}|{}", self.published_at, self.id)
+    }
+
+    /// Cursors come from the client, so the timestamp is parsed and written
+    /// back out before it goes anywhere near a PostgREST filter.
+    #[cfg(feature = "server")]
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let (published_at, id) = cursor.rsplit_once('|')?;
+        let published_at = crate::server::parse_timestamp(published_at)?.to_rfc3339();
+        Some(BlogCursor { published_at, id: id.parse().ok()? })
+    }
+
+    /// Whether `post` comes after this position in listing order.
+    pub fn is_before(&self, post: &BlogPostSummary) -> bool {
+        (post.published_at.as_str(), post.id) < (self.published_at.as_str(), self.id)
+    }
+
+    /// The same comparison as a PostgREST `or` filter.
+    #[cfg(feature = "server")]
+    pub(crate) fn filter(&self) -> String {
+        format!(
+            "published_at.lt.\"{ts}\",and(published_at.eq.\"{ts}\",id.lt.{id})",
+            ts = self.published_at,
+            id = self.id
+        )
+    }
+
+    /// The opposite of `filter`: posts that come before this position.
+    #[cfg(feature = "server")]
+    pub(crate) fn preceding_filter(&self) -> String {
+        format!(
+            "published_at.gt.\"{ts}\",and(published_at.eq.\"{ts}\",id.gt.{id})",
+            ts = self.published_at,
+            id = self.id
+        )
+    }
+}
+
+pub(crate) fn total_pages(total_posts: usize) -> u32 {
+    total_posts.div_ceil(BLOG_PAGE_SIZE).max(1) as u32
+}
+
+/// Page `page` (1-based) of the listing.
+#[server(name = GetBlogPage)]
+pub async fn get_blog_page(page: u32) -> Result<BlogPage, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        fetch_blog_page(page.max(1), None).await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// The page following `cursor`, for infinite scrolling.
+#[server(name = GetBlogPageAfter)]
+pub async fn get_blog_page_after(cursor: String) -> Result<BlogPage, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let cursor = BlogCursor::decode(&cursor)
+            .ok_or_else(|| ServerFnError::new(format!("Invalid blog cursor '{}'", cursor)))?;
+        fetch_blog_page(1, Some(cursor)).await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Page through summaries already in memory, for the non-Supabase sources.
+#[cfg(feature = "server")]
+fn paginate(mut posts: Vec<BlogPostSummary>, page: u32, after: Option<BlogCursor>) -> BlogPage {
+    posts.sort_by(|a, b| (b.published_at.as_str(), b.id).cmp(&(a.published_at.as_str(), a.id)));
+    let total_pages = total_pages(posts.len());
+
+    let start = match &after {
+        Some(cursor) => posts.iter().position(|post| cursor.is_before(post)).unwrap_or(posts.len()),
+        None => (page.max(1) as usize - 1) * BLOG_PAGE_SIZE,
+    };
+    let mut rest: Vec<BlogPostSummary> = posts.into_iter().skip(start).take(BLOG_PAGE_SIZE + 1).collect();
+    let has_more = rest.len() > BLOG_PAGE_SIZE;
+    rest.truncate(BLOG_PAGE_SIZE);
+
+    BlogPage {
+        next_cursor: has_more.then(|| rest.last().map(|post| BlogCursor::after(post).encode())).flatten(),
+        posts: rest,
+        total_pages,
+    }
+}
+
+/// Total from a PostgREST `Content-Range: 0-8/42` header.
+#[cfg(feature = "server")]
+pub(crate) fn content_range_total(content_range: Option<&str>) -> Option<usize> {
+    content_range?.rsplit_once('/')?.1.parse().ok()
+}
+
+#[cfg(feature = "server")]
+async fn fetch_blog_page(page: u32, after: Option<BlogCursor>) -> Result<BlogPage, ServerFnError> {
+    use crate::api::auth::create_server_client;
+    use tracing::info;
+
+    if let Some(posts) = crate::api::source::current().blog_posts(true).await {
+        return posts.map(|posts| paginate(posts.iter().map(BlogPostSummary::from).collect(), page, after));
+    }
+
+    info!("Fetching blog page {} (after {:?}) from Supabase...", page, after);
+    let client = create_server_client();
+    // postgrest brings its own reqwest, so its error type isn't nameable here.
+    fn request_error(e: impl std::fmt::Display) -> ServerFnError {
+        ServerFnError::new(format!("Request failed: {}", e))
+    }
+
+    let count = only_public(client.table("blog_posts").select("id"))
+        .exact_count()
+        .execute()
+        .await
+        .map_err(request_error)?;
+    let total_pages = total_pages(content_range_total(count.headers().get("content-range").and_then(|v| v.to_str().ok())).unwrap_or(0));
+
+    // A page number is turned into a cursor by looking up the last post of
+    // the previous page, which only needs its key columns.
+    let after = match after {
+        Some(cursor) => Some(cursor),
+        None if page > 1 => {
+            let offset = (page as usize - 1) * BLOG_PAGE_SIZE - 1;
+            let response = only_public(client.table("blog_posts").select("id,published_at"))
+                .order("published_at.desc,id.desc")
+                .range(offset, offset)
+                .execute()
+                .await
+                .map_err(request_error)?;
+            let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+            let keys: Vec<serde_json::Value> = serde_json::from_str(&text)
+                .map_err(|e| ServerFnError::new(format!("Failed to parse blog page keys: {}", e)))?;
+            match keys.first() {
+                Some(key) => Some(BlogCursor {
+                    published_at: key["published_at"].as_str().unwrap_or_default().to_string(),
+                    id: key["id"].as_i64().unwrap_or_default() as i32,
+                }),
+                // Past the last page.
+                None => return Ok(BlogPage { total_pages, ..Default::default() }),
+            }
+        }
+        None => None,
+    };
+
+    let mut query = only_public(client.table("blog_posts").select(SUMMARY_COLUMNS))
+        .order("published_at.desc,id.desc")
+        .limit(BLOG_PAGE_SIZE + 1);
+    if let Some(cursor) = &after {
+        query = query.or(cursor.filter());
+    }
+    let response = query.execute().await.map_err(request_error)?;
+
+    let status = response.status();
+    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+    if !status.is_success() {
+        let error_msg = format!("Failed to fetch blog page. Status: {}, Response: {}", status, text);
+        info!("{}", error_msg);
+        return Err(ServerFnError::new(error_msg));
+    }
+
+    let mut posts: Vec<BlogPostSummary> = serde_json::from_str(&text)
+        .map_err(|e| ServerFnError::new(format!("Failed to parse blog summaries JSON: {}", e)))?;
+    let has_more = posts.len() > BLOG_PAGE_SIZE;
+    posts.truncate(BLOG_PAGE_SIZE);
+    fill_reading_stats(&mut posts).await?;
+
+    Ok(BlogPage {
+        next_cursor: has_more.then(|| posts.last().map(|post| BlogCursor::after(post).encode())).flatten(),
+        posts,
+        total_pages,
+    })
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursors_only_carry_timestamps() {
+        let cursor = BlogCursor::decode("2024-05-01T12:34:56.789+00:00|42").unwrap();
+        assert_eq!(cursor, BlogCursor { published_at: "2024-05-01T12:34:56.789+00:00".to_string(), id: 42 });
+        assert_eq!(BlogCursor::decode("2024-05-01|7").unwrap().published_at, "2024-05-01T00:00:00+00:00");
+
+        assert_eq!(BlogCursor::decode("2024-05-01\"),id.gt.0,or(id.gt.0|1"), None);
+        assert_eq!(BlogCursor::decode("2024-05-01T12:34:56+00:00|x"), None);
+    }
+
+    #[test]
+    fn reads_totals_from_content_range() {
+        assert_eq!(content_range_total(Some("0-8/42")), Some(42));
+        // What a `limit=0` count answers with.
+        assert_eq!(content_range_total(Some("*/7")), Some(7));
+        assert_eq!(content_range_total(Some("0-8/*")), None);
+        assert_eq!(content_range_total(None), None);
+    }
+}
@@ -0,0 +1,436 @@
+//! Server functions behind the `/admin/blog` dashboard.
+//!
+//! Every function takes the caller's access token and checks it with
+//! `require_author` before touching the database. Reads and writes then use
+//! the service role key (`admin_client`): row level security keeps signed-in
+//! users, who can sign themselves up, out of unpublished and unsaved posts.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::blog::null_as_default;
+use crate::api::images::ImageVariants;
+use crate::api::preview::PreviewLink;
+use crate::api::series::BlogSeries;
+
+/// Columns behind `PostDraft`.
+#[cfg(feature = "server")]
+pub(crate) const DRAFT_COLUMNS: &str = "id,title,slug,excerpt,tags,cover_image,cover_image_variants,content,is_published,published_at,series_id,series_part";
+
+/// Where a post stands with readers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PublicationStatus {
+    Draft,
+    /// Published, but `published_at` is still in the future.
+    Scheduled,
+    Published,
+}
+
+impl PublicationStatus {
+    pub fn of(is_published: bool, published_at: Option<&str>) -> Self {
+        let due = published_at
+            .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+            .is_none_or(|at| at <= chrono::Utc::now());
+        match (is_published, due) {
+            (false, _) => PublicationStatus::Draft,
+            (true, false) => PublicationStatus::Scheduled,
+            (true, true) => PublicationStatus::Published,
+        }
+    }
+}
+
+/// A row in the dashboard's post list.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AdminPostSummary {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub is_published: bool,
+    pub published_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl AdminPostSummary {
+    pub fn status(&self) -> PublicationStatus {
+        PublicationStatus::of(self.is_published, self.published_at.as_deref())
+    }
+}
+
+/// The editable fields of a post. `id` is `None` until the first save.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PostDraft {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    pub title: String,
+    pub slug: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub excerpt: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub tags: Vec<String>,
+    pub cover_image: Option<String>,
+    /// Set by uploading the cover; dropped on save once `cover_image` no
+    /// longer points at it.
+    #[serde(default)]
+    pub cover_image_variants: Option<ImageVariants>,
+    pub content: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub is_published: bool,
+    /// When the post goes public. A future date schedules it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
+    /// The series the post is part of.
+    #[serde(default)]
+    pub series_id: Option<i32>,
+    /// Position in the series; saving without one puts the post last.
+    #[serde(default)]
+    pub series_part: Option<i32>,
+}
+
+impl PostDraft {
+    pub fn status(&self) -> PublicationStatus {
+        PublicationStatus::of(self.is_published, self.published_at.as_deref())
+    }
+
+    /// Problems that would stop the post from saving, in form order.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.title.trim().is_empty() {
+            return Err("Title is required".to_string());
+        }
+        if self.slug.is_empty() {
+            return Err("Slug is required".to_string());
+        }
+        if !self.slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+            return Err("Slug may only contain lowercase letters, digits and dashes".to_string());
+        }
+        if self.content.trim().is_empty() {
+            return Err("Content is required".to_string());
+        }
+        if self.series_part.is_some_and(|part| part < 1) {
+            return Err("Series part must be 1 or more".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// All posts, drafts included, most recently created first.
+#[server(name = AdminListPosts)]
+pub async fn admin_list_posts(access_token: String) -> Result<Vec<AdminPostSummary>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+
+        require_author(&access_token).await?;
+        let query = admin_client()?
+            .table("blog_posts")
+            .select("id,title,slug,is_published,published_at,updated_at")
+            .order("created_at.desc");
+        run(query, "list posts").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+#[server(name = AdminGetPost)]
+pub async fn admin_get_post(access_token: String, id: i32) -> Result<PostDraft, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+
+        require_author(&access_token).await?;
+        let query = admin_client()?
+            .table("blog_posts")
+            .select(DRAFT_COLUMNS)
+            .eq("id", id.to_string())
+            .single();
+        run(query, "load post").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Create the post if it has no id yet, otherwise update it. Returns the
+/// post as stored. `change_note` describes the edit in the post's history.
+#[server(name = SavePost)]
+pub async fn save_post(access_token: String, draft: PostDraft, change_note: String) -> Result<PostDraft, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        draft.validate().map_err(ServerFnError::new)?;
+
+        let mut draft = draft;
+        if draft.is_published && draft.published_at.is_none() {
+            draft.published_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        draft.cover_image = draft.cover_image.filter(|image| !image.trim().is_empty());
+        draft.cover_image_variants =
+            ImageVariants::for_src(draft.cover_image_variants.as_ref(), draft.cover_image.as_deref().unwrap_or("")).cloned();
+        match (draft.series_id, draft.series_part) {
+            (None, _) => draft.series_part = None,
+            (Some(series_id), None) => draft.series_part = Some(next_series_part(series_id).await?),
+            (Some(_), Some(_)) => {}
+        }
+
+        let mut body = serde_json::to_value(PostDraft { id: None, ..draft.clone() })
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        body["revision_note"] = serde_json::json!(change_note);
+        body["revision_author"] = serde_json::json!(user.email);
+        add_reading_stats(&mut body, &draft.content);
+        let body = body.to_string();
+        let table = admin_client()?.table("blog_posts");
+        let query = match draft.id {
+            Some(id) => table.eq("id", id.to_string()).update(body),
+            None => table.insert(body),
+        };
+        let saved: PostDraft = run(query.select(DRAFT_COLUMNS).single(), "save post").await?;
+        info!("{} saved blog post {:?} '{}'", user.email, saved.id, saved.slug);
+        if saved.status() == PublicationStatus::Published {
+            crate::server::webmention::send_soon();
+        }
+        Ok(saved)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Every series, for the editor's series picker.
+#[server(name = AdminListSeries)]
+pub async fn admin_list_series(access_token: String) -> Result<Vec<BlogSeries>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+
+        require_author(&access_token).await?;
+        let query = admin_client()?
+            .table("blog_series")
+            .select("id,title,slug,description")
+            .order("title.asc");
+        run(query, "list series").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Create the series if it has no id yet, otherwise update it.
+#[server(name = SaveSeries)]
+pub async fn save_series(access_token: String, series: BlogSeries) -> Result<BlogSeries, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        series.validate().map_err(ServerFnError::new)?;
+
+        let body = serde_json::to_string(&BlogSeries { id: None, ..series.clone() })
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        let table = admin_client()?.table("blog_series");
+        let query = match series.id {
+            Some(id) => table.eq("id", id.to_string()).update(body),
+            None => table.insert(body),
+        };
+        let saved: BlogSeries = run(query.select("id,title,slug,description").single(), "save series").await?;
+        info!("{} saved blog series {:?} '{}'", user.email, saved.id, saved.slug);
+        Ok(saved)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Publish or unpublish a post. Publishing stamps `published_at` if the post
+/// never had one.
+#[server(name = SetPostPublished)]
+pub async fn set_post_published(access_token: String, id: i32, published: bool) -> Result<PostDraft, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+
+        let user = require_author(&access_token).await?;
+        let client = admin_client()?;
+        let current: PostDraft = run(
+            client.table("blog_posts").select(DRAFT_COLUMNS).eq("id", id.to_string()).single(),
+            "load post",
+        )
+        .await?;
+
+        let note = if published { "Published" } else { "Unpublished" };
+        let mut body = serde_json::json!({
+            "is_published": published,
+            "revision_note": note,
+            "revision_author": user.email,
+        });
+        if published && current.published_at.is_none() {
+            body["published_at"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
+        }
+        let query = client
+            .table("blog_posts")
+            .eq("id", id.to_string())
+            .update(body.to_string())
+            .select(DRAFT_COLUMNS)
+            .single();
+        let updated: PostDraft = run(query, "update post").await?;
+        if updated.status() == PublicationStatus::Published {
+            crate::server::webmention::send_soon();
+        }
+        Ok(updated)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// A link that shows the post as it is now, published or not, to anyone
+/// holding it until it expires.
+#[server(name = CreatePreviewLink)]
+pub async fn create_preview_link(access_token: String, id: i32) -> Result<PreviewLink, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::env::absolute_url;
+        use crate::api::preview::{sign_token, PREVIEW_LINK_HOURS};
+        use crate::Route;
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        let post: PostDraft = run(
+            admin_client()?.table("blog_posts").select(DRAFT_COLUMNS).eq("id", id.to_string()).single(),
+            "load post",
+        )
+        .await?;
+
+        let expires = chrono::Utc::now() + chrono::Duration::hours(PREVIEW_LINK_HOURS);
+        let token = sign_token(id, expires)?;
+        let path = format!("{}?preview={}", Route::BlogPostDetail { slug: post.slug.clone() }, token);
+        info!("{} created a preview link for blog post {} '{}'", user.email, id, post.slug);
+        Ok(PreviewLink { url: absolute_url(&path), expires_at: expires.to_rfc3339() })
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+#[server(name = DeletePost)]
+pub async fn delete_post(access_token: String, id: i32) -> Result<(), ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        let query = admin_client()?.table("blog_posts").eq("id", id.to_string()).delete();
+        let _: serde_json::Value = run(query, "delete post").await?;
+        info!("{} deleted blog post {}", user.email, id);
+        Ok(())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// The part number after the last post in the series, drafts included.
+#[cfg(feature = "server")]
+async fn next_series_part(series_id: i32) -> Result<i32, ServerFnError> {
+    #[derive(Deserialize)]
+    struct Part {
+        series_part: Option<i32>,
+    }
+
+    let query = admin_client()?
+        .table("blog_posts")
+        .select("series_part")
+        .eq("series_id", series_id.to_string())
+        .order("series_part.desc.nullslast")
+        .limit(1);
+    let last: Vec<Part> = run(query, "number series part").await?;
+    Ok(last.first().and_then(|part| part.series_part).unwrap_or(0) + 1)
+}
+
+/// Posts are closed to every signed-in user by row level security, so the
+/// dashboard reads and writes them with the service role key, once
+/// `require_author` has let the caller in.
+#[cfg(feature = "server")]
+pub(crate) fn admin_client() -> Result<crate::api::auth::ServerSupabaseClient, ServerFnError> {
+    crate::api::auth::service_client()
+        .ok_or_else(|| ServerFnError::new("The dashboard needs SUPABASE_SERVICE_ROLE_KEY".to_string()))
+}
+
+/// Add the stored word count and reading time for `content` to a
+/// `blog_posts` write, stamped with the hash the database checks them
+/// against (see `migrations/010_reading_stats.sql`).
+#[cfg(feature = "server")]
+pub(crate) fn add_reading_stats(body: &mut serde_json::Value, content: &str) {
+    use crate::content::reading_stats;
+    use sha2::{Digest, Sha256};
+
+    let stats = reading_stats(content);
+    let hash: String = Sha256::digest(content.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect();
+    body["word_count"] = serde_json::json!(stats.word_count);
+    body["reading_time"] = serde_json::json!(stats.reading_time);
+    body["reading_stats_sha256"] = serde_json::json!(hash);
+}
+
+/// How many rows `query` matches, from the exact count PostgREST puts in
+/// `Content-Range`, without downloading any of them. `limit=0` goes on the
+/// request itself, since the builder's `limit` can't ask for no rows.
+#[cfg(feature = "server")]
+pub(crate) async fn count(query: postgrest::Builder, action: &str) -> Result<usize, ServerFnError> {
+    use crate::api::blog::content_range_total;
+
+    let response = query
+        .exact_count()
+        .build()
+        .query(&[("limit", "0")])
+        .send()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Request failed: {}", e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(ServerFnError::new(format!("Failed to {}. Status: {}, Response: {}", action, status, text)));
+    }
+    content_range_total(response.headers().get("content-range").and_then(|v| v.to_str().ok()))
+        .ok_or_else(|| ServerFnError::new(format!("Failed to {}: the response has no count", action)))
+}
+
+/// Send a PostgREST request and parse the JSON it returns.
+#[cfg(feature = "server")]
+pub(crate) async fn run<T: serde::de::DeserializeOwned>(query: postgrest::Builder, action: &str) -> Result<T, ServerFnError> {
+    use tracing::info;
+
+    let response = query
+        .execute()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Request failed: {}", e)))?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    if !status.is_success() {
+        let error_msg = if status.as_u16() == 409 {
+            "Another post already uses that slug".to_string()
+        } else {
+            format!("Failed to {}. Status: {}, Response: {}", action, status, text)
+        };
+        info!("{}", error_msg);
+        return Err(ServerFnError::new(error_msg));
+    }
+
+    // Deletes answer with an empty body.
+    let text = if text.is_empty() { "null" } else { &text };
+    serde_json::from_str(text).map_err(|e| ServerFnError::new(format!("Failed to parse response for {}: {}", action, e)))
+}
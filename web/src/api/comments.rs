@@ -0,0 +1,309 @@
+//! Reader comments and their moderation queue (see
+//! `migrations/013_blog_comments.sql`).
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Longest comment accepted, in characters.
+pub const MAX_COMMENT_CHARS: usize = 5000;
+/// Longest name accepted, in characters. Matches the column.
+pub const MAX_NAME_CHARS: usize = 80;
+
+/// Comments one visitor may send in ten minutes.
+#[cfg(feature = "server")]
+const COMMENT_LIMIT: crate::server::rate_limit::Limit = crate::server::rate_limit::Limit {
+    bucket: "comments",
+    max: 3,
+    window: std::time::Duration::from_secs(10 * 60),
+};
+
+#[cfg(feature = "server")]
+const ADMIN_COMMENT_COLUMNS: &str = "id,post_id,parent_id,author_name,body,status,created_at,post:blog_posts(slug,title)";
+
+/// Where a comment is in the moderation queue. Only approved comments are
+/// shown to readers.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentStatus {
+    #[default]
+    Pending,
+    Approved,
+    Spam,
+}
+
+impl CommentStatus {
+    /// The value stored in `blog_comments.status`.
+    #[cfg(feature = "server")]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommentStatus::Pending => "pending",
+            CommentStatus::Approved => "approved",
+            CommentStatus::Spam => "spam",
+        }
+    }
+}
+
+/// An approved comment as readers see it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Comment {
+    pub id: i32,
+    /// The comment this one replies to.
+    pub parent_id: Option<i32>,
+    pub author_name: String,
+    /// Markdown-lite; render with `render_comment`.
+    pub body: String,
+    pub created_at: String,
+}
+
+/// What a reader sends from the comment form.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NewComment {
+    pub post_id: i32,
+    pub parent_id: Option<i32>,
+    pub author_name: String,
+    pub body: String,
+}
+
+impl NewComment {
+    /// Problems that would stop the comment from being sent, in form order.
+    pub fn validate(&self) -> Result<(), String> {
+        let name = self.author_name.trim();
+        if name.is_empty() {
+            return Err("Please enter your name".to_string());
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            return Err(format!("Names are limited to {} characters", MAX_NAME_CHARS));
+        }
+        if self.body.trim().is_empty() {
+            return Err("Please write a comment".to_string());
+        }
+        if self.body.chars().count() > MAX_COMMENT_CHARS {
+            return Err(format!("Comments are limited to {} characters", MAX_COMMENT_CHARS));
+        }
+        Ok(())
+    }
+}
+
+/// The post a comment was left on, for the moderation queue.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommentPost {
+    pub slug: String,
+    pub title: String,
+}
+
+/// A comment in the moderation queue, whatever its status.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AdminComment {
+    pub id: i32,
+    pub post_id: i32,
+    pub parent_id: Option<i32>,
+    pub author_name: String,
+    pub body: String,
+    pub status: CommentStatus,
+    pub created_at: String,
+    pub post: Option<CommentPost>,
+}
+
+/// Approved comments on post `post_id`, oldest first.
+#[server(name = GetComments)]
+pub async fn get_comments(post_id: i32) -> Result<Vec<Comment>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::create_server_client;
+        use crate::api::blog_admin::run;
+        use crate::api::source::{self, ContentSource};
+
+        // Static copies of the site are built without comments.
+        if !matches!(source::current(), ContentSource::Supabase) {
+            return Ok(Vec::new());
+        }
+
+        let query = create_server_client()
+            .table("blog_comments")
+            .select("id,parent_id,author_name,body,created_at")
+            .eq("post_id", post_id.to_string())
+            .eq("status", CommentStatus::Approved.as_str())
+            .order("created_at.asc,id.asc");
+        run(query, "fetch comments").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Leave a comment. `access_token` is empty for visitors who aren't signed
+/// in. Comments wait for moderation unless an author wrote them; the status
+/// they were saved with is returned.
+#[server(name = SubmitComment)]
+pub async fn submit_comment(access_token: String, comment: NewComment) -> Result<CommentStatus, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::{may_author, service_client, signed_in_user};
+        use crate::api::blog::only_public;
+        use crate::api::blog_admin::run;
+        use tracing::info;
+
+        comment.validate().map_err(ServerFnError::new)?;
+        let user = if access_token.is_empty() { None } else { Some(signed_in_user(&access_token).await?) };
+        // Anonymous comments are limited per address; without one there is
+        // nothing to limit by, so they are refused rather than pooled.
+        let visitor = match (&user, client_ip()) {
+            (Some(user), _) => user.id.clone(),
+            (None, Some(ip)) => ip,
+            (None, None) => {
+                tracing::warn!("Refused an anonymous comment with no client address");
+                return Err(ServerFnError::new(
+                    "Comments can't be accepted right now. Please sign in or try again later.".to_string(),
+                ));
+            }
+        };
+        if !crate::server::rate_limit::allow(&COMMENT_LIMIT, &visitor).await {
+            return Err(ServerFnError::new(
+                "You've sent a few comments in a row. Please wait a few minutes and try again.".to_string(),
+            ));
+        }
+
+        let client = service_client()
+            .ok_or_else(|| ServerFnError::new("Comments need SUPABASE_SERVICE_ROLE_KEY".to_string()))?;
+        let post = only_public(client.table("blog_posts").select("id")).eq("id", comment.post_id.to_string());
+        let posts: Vec<serde_json::Value> = run(post, "look up blog post").await?;
+        if posts.is_empty() {
+            return Err(ServerFnError::new("Comments are closed on this post".to_string()));
+        }
+        if let Some(parent_id) = comment.parent_id {
+            let parent = client
+                .table("blog_comments")
+                .select("id")
+                .eq("id", parent_id.to_string())
+                .eq("post_id", comment.post_id.to_string())
+                .eq("status", CommentStatus::Approved.as_str());
+            let parents: Vec<serde_json::Value> = run(parent, "look up parent comment").await?;
+            if parents.is_empty() {
+                return Err(ServerFnError::new("The comment you're replying to is no longer available".to_string()));
+            }
+        }
+
+        let status = match &user {
+            Some(user) if may_author(user) => CommentStatus::Approved,
+            _ => CommentStatus::Pending,
+        };
+        let body = serde_json::json!({
+            "post_id": comment.post_id,
+            "parent_id": comment.parent_id,
+            "author_name": comment.author_name.trim(),
+            "author_id": user.as_ref().map(|user| &user.id),
+            "body": comment.body.trim(),
+            "status": status,
+        });
+        let _: serde_json::Value = run(client.table("blog_comments").insert(body.to_string()), "save comment").await?;
+        info!("New {} comment on blog post {} from {}", status.as_str(), comment.post_id, visitor);
+        Ok(status)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// The moderation queue for `status`, newest first.
+#[server(name = AdminListComments)]
+pub async fn admin_list_comments(access_token: String, status: CommentStatus) -> Result<Vec<AdminComment>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::run;
+
+        require_author(&access_token).await?;
+        let query = moderation_client()?
+            .table("blog_comments")
+            .select(ADMIN_COMMENT_COLUMNS)
+            .eq("status", status.as_str())
+            .order("created_at.desc,id.desc")
+            .limit(200);
+        run(query, "list comments").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// How many comments are waiting for moderation, for the dashboard.
+#[server(name = PendingCommentCount)]
+pub async fn pending_comment_count(access_token: String) -> Result<usize, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::count;
+
+        require_author(&access_token).await?;
+        let query = moderation_client()?
+            .table("blog_comments")
+            .select("id")
+            .eq("status", CommentStatus::Pending.as_str());
+        count(query, "count pending comments").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Approve a comment, or move it to or out of spam.
+#[server(name = SetCommentStatus)]
+pub async fn set_comment_status(access_token: String, id: i32, status: CommentStatus) -> Result<(), ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::run;
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        let body = serde_json::json!({ "status": status });
+        let query = moderation_client()?.table("blog_comments").eq("id", id.to_string()).update(body.to_string());
+        let _: serde_json::Value = run(query, "moderate comment").await?;
+        info!("{} marked comment {} as {}", user.email, id, status.as_str());
+        Ok(())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Delete a comment and every reply to it.
+#[server(name = DeleteComment)]
+pub async fn delete_comment(access_token: String, id: i32) -> Result<(), ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::run;
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        let query = moderation_client()?.table("blog_comments").eq("id", id.to_string()).delete();
+        let _: serde_json::Value = run(query, "delete comment").await?;
+        info!("{} deleted comment {}", user.email, id);
+        Ok(())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Comments other than approved ones are closed to every signed-in user by
+/// row level security, so moderation reads and writes them with the service
+/// role key, once `require_author` has let the caller in.
+#[cfg(feature = "server")]
+fn moderation_client() -> Result<crate::api::auth::ServerSupabaseClient, ServerFnError> {
+    crate::api::auth::service_client()
+        .ok_or_else(|| ServerFnError::new("Moderation needs SUPABASE_SERVICE_ROLE_KEY".to_string()))
+}
+
+/// The visitor's address, looking through trusted proxies.
+#[cfg(feature = "server")]
+fn client_ip() -> Option<String> {
+    crate::server::client_ip::client_ip(&server_context().request_parts()).map(|ip| ip.to_string())
+}
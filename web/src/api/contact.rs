@@ -1,132 +1,305 @@
+//! The contact form (see `migrations/002_contact_submissions.sql`). Messages
+//! are only ever saved by `submit_contact_form`, once `ContactForm::validate`
+//! has accepted them; the form runs the same checks before sending.
+//!
+//! Against spam, the page first fetches a signed `ContactChallenge` and
+//! solves its proof of work while the visitor types. The server then rate
+//! limits senders and scores each message (`server::spam`); messages that
+//! look like spam are still saved, with `status = 'spam'`.
+
+use dioxus::prelude::*;
+use email_address::{EmailAddress, Options};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
-use serde_json::Value;
+use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
+
+/// Longest name accepted, in characters.
+pub const MAX_NAME_CHARS: usize = 100;
+/// Longest email address accepted, in characters (RFC 5321 allows 254).
+pub const MAX_EMAIL_CHARS: usize = 254;
+/// Longest subject accepted, in characters.
+pub const MAX_SUBJECT_CHARS: usize = 150;
+/// Longest message accepted, in characters.
+pub const MAX_MESSAGE_CHARS: usize = 5000;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ContactSubmission {
-    pub id: Option<String>, // Always use String for cross-platform compatibility
+/// Messages one IP address may send in an hour.
+#[cfg(feature = "server")]
+const IP_LIMIT: crate::server::rate_limit::Limit = crate::server::rate_limit::Limit {
+    bucket: "contact-ip",
+    max: 5,
+    window: std::time::Duration::from_secs(60 * 60),
+};
+/// Messages one email address may send in an hour.
+#[cfg(feature = "server")]
+const EMAIL_LIMIT: crate::server::rate_limit::Limit = crate::server::rate_limit::Limit {
+    bucket: "contact-email",
+    max: 3,
+    window: std::time::Duration::from_secs(60 * 60),
+};
+
+/// What a visitor fills in on the contact page.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContactForm {
     pub name: String,
     pub email: String,
+    /// Optional.
+    pub subject: String,
     pub message: String,
-    pub created_at: Option<DateTime<Utc>>,
-    pub ip_address: Option<String>,
-    pub user_agent: Option<String>,
-    pub subject: Option<String>,
-    pub status: Option<String>,
-    pub metadata: Option<Value>,
+    /// The hidden honeypot field. People never see it, so it stays empty.
+    #[serde(default)]
+    pub website: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct NewContactSubmission {
-    pub name: String,
-    pub email: String,
-    pub message: String,
-    pub ip_address: Option<String>,
-    pub user_agent: Option<String>,
+/// What's wrong with each field of a `ContactForm`, if anything.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContactErrors {
+    pub name: Option<String>,
+    pub email: Option<String>,
     pub subject: Option<String>,
-    pub metadata: Option<Value>,
+    pub message: Option<String>,
+}
+
+impl ContactErrors {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.email.is_none() && self.subject.is_none() && self.message.is_none()
+    }
+}
+
+/// How the server answered a submission.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ContactReply {
+    Sent,
+    /// Nothing was saved; show these next to the fields.
+    Invalid(ContactErrors),
+    /// Nothing was saved: this address has sent too many messages lately.
+    TooMany,
+}
+
+/// A form token from the server and the proof of work it asks for.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContactChallenge {
+    pub token: String,
+    /// Leading zero bits the proof's hash needs.
+    pub difficulty: u32,
+}
+
+/// What the form sends back with a message: its token and the counter that
+/// solves the token's proof of work.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContactProof {
+    pub token: String,
+    pub counter: u64,
 }
 
-impl From<ContactSubmission> for NewContactSubmission {
-    fn from(submission: ContactSubmission) -> Self {
-        Self {
-            name: submission.name,
-            email: submission.email,
-            message: submission.message,
-            ip_address: submission.ip_address,
-            user_agent: submission.user_agent,
-            subject: submission.subject,
-            metadata: submission.metadata,
+impl ContactChallenge {
+    /// Find the first counter that solves the proof of work, which takes
+    /// about 2^`difficulty` hashes.
+    pub fn solve(&self) -> ContactProof {
+        let counter = (0..).find(|&counter| proof_holds(&self.token, counter, self.difficulty)).unwrap_or_default();
+        ContactProof { token: self.token.clone(), counter }
+    }
+}
+
+/// Whether SHA-256 of `<token>:<counter>` starts with `difficulty` zero bits.
+pub fn proof_holds(token: &str, counter: u64, difficulty: u32) -> bool {
+    let hash = Sha256::digest(format!("{}:{}", token, counter).as_bytes());
+    let mut zeros = 0;
+    for byte in hash {
+        zeros += byte.leading_zeros();
+        if byte != 0 {
+            break;
         }
     }
+    zeros >= difficulty
 }
 
-#[cfg(target_arch = "wasm32")]
-pub async fn submit_contact_form(submission: NewContactSubmission) -> Result<ContactSubmission, String> {
-    use wasm_bindgen::prelude::*;
-    use wasm_bindgen_futures::JsFuture;
-    use web_sys::{Request, RequestInit, RequestMode, Response};
-    use crate::api::env::{APP_PUBLIC_SUPABASE_URL, APP_PUBLIC_SUPABASE_ANON_KEY};
-
-    let url = format!("{}/rest/v1/contact_submissions", *APP_PUBLIC_SUPABASE_URL);
-    
-    let mut opts = RequestInit::new();
-    opts.method("POST");
-    opts.mode(RequestMode::Cors);
-    
-    let headers = web_sys::Headers::new().map_err(|e| format!("Failed to create headers: {:?}", e))?;
-    headers.set("Content-Type", "application/json").map_err(|e| format!("Failed to set content type: {:?}", e))?;
-    headers.set("apikey", &APP_PUBLIC_SUPABASE_ANON_KEY).map_err(|e| format!("Failed to set API key: {:?}", e))?;
-    headers.set("Authorization", &format!("Bearer {}", *APP_PUBLIC_SUPABASE_ANON_KEY)).map_err(|e| format!("Failed to set authorization: {:?}", e))?;
-    headers.set("Prefer", "return=representation").map_err(|e| format!("Failed to set prefer header: {:?}", e))?;
-    
-    opts.headers(&headers);
-
-    let body = serde_json::to_string(&submission).map_err(|e| format!("Failed to serialize submission: {}", e))?;
-    opts.body(Some(&wasm_bindgen::JsValue::from_str(&body)));
-
-    let request = Request::new_with_str_and_init(&url, &opts).map_err(|e| format!("Failed to create request: {:?}", e))?;
-
-    let window = web_sys::window().ok_or("No window object")?;
-    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await.map_err(|e| format!("Fetch failed: {:?}", e))?;
-    let resp: Response = resp_value.dyn_into().map_err(|_| "Response is not a Response object")?;
-
-    if !resp.ok() {
-        let status = resp.status();
-        let text = JsFuture::from(resp.text().map_err(|e| format!("Failed to get response text: {:?}", e))?)
-            .await
-            .map_err(|e| format!("Failed to read response text: {:?}", e))?;
-        let text_str = text.as_string().unwrap_or_else(|| "Unknown error".to_string());
-        return Err(format!("HTTP {}: {}", status, text_str));
+impl ContactForm {
+    /// The form as it should be saved, or what's wrong with it. Text is
+    /// normalized to NFC with control characters removed; the name and
+    /// subject become a single trimmed line, and the message keeps its line
+    /// breaks as `\n`.
+    pub fn validate(&self) -> Result<ContactForm, ContactErrors> {
+        let form = ContactForm {
+            name: single_line(&self.name),
+            email: single_line(&self.email),
+            subject: single_line(&self.subject),
+            message: multi_line(&self.message),
+            website: single_line(&self.website),
+        };
+        let mut errors = ContactErrors::default();
+
+        if form.name.is_empty() {
+            errors.name = Some("Please enter your name".to_string());
+        } else if form.name.chars().count() > MAX_NAME_CHARS {
+            errors.name = Some(format!("Names are limited to {} characters", MAX_NAME_CHARS));
+        }
+
+        let email = if form.email.is_empty() {
+            errors.email = Some("Please enter your email address".to_string());
+            String::new()
+        } else if form.email.chars().count() > MAX_EMAIL_CHARS {
+            errors.email = Some(format!("Email addresses are limited to {} characters", MAX_EMAIL_CHARS));
+            String::new()
+        } else {
+            match normalize_email(&form.email) {
+                Some(email) => email,
+                None => {
+                    errors.email = Some("Please enter a valid email address, like name@example.com".to_string());
+                    String::new()
+                }
+            }
+        };
+
+        if form.subject.chars().count() > MAX_SUBJECT_CHARS {
+            errors.subject = Some(format!("Subjects are limited to {} characters", MAX_SUBJECT_CHARS));
+        }
+
+        if form.message.is_empty() {
+            errors.message = Some("Please write a message".to_string());
+        } else if form.message.chars().count() > MAX_MESSAGE_CHARS {
+            errors.message = Some(format!("Messages are limited to {} characters", MAX_MESSAGE_CHARS));
+        }
+
+        if errors.is_empty() {
+            Ok(ContactForm { email, ..form })
+        } else {
+            Err(errors)
+        }
     }
+}
 
-    let json_value = JsFuture::from(resp.json().map_err(|e| format!("Failed to parse JSON: {:?}", e))?)
-        .await
-        .map_err(|e| format!("Failed to read JSON: {:?}", e))?;
+/// NFC, without control characters, with runs of whitespace (line breaks
+/// included) collapsed to one space.
+fn single_line(value: &str) -> String {
+    let value: String = value.nfc().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
-    let json_str = js_sys::JSON::stringify(&json_value).map_err(|e| format!("Failed to stringify JSON: {:?}", e))?;
-    let json_str = json_str.as_string().ok_or("Failed to convert JSON to string")?;
-    
-    let submissions: Vec<ContactSubmission> = serde_json::from_str(&json_str).map_err(|e| format!("Failed to deserialize response: {}", e))?;
-    
-    submissions.into_iter().next().ok_or("No submission returned".to_string())
+/// NFC, with `\n` line breaks, without other control characters except tabs,
+/// and without leading or trailing whitespace.
+fn multi_line(value: &str) -> String {
+    let value = value.replace("\r\n", "\n").replace('\r', "\n");
+    let value: String = value.nfc().filter(|&c| c == '\n' || c == '\t' || !c.is_control()).collect();
+    value.trim().to_string()
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-pub async fn submit_contact_form(submission: NewContactSubmission) -> Result<ContactSubmission, String> {
-    use crate::api::database::connection;
-    use sqlx::{query, Row};
-
-    let pool = connection().await.map_err(|e| format!("Database connection failed: {}", e))?;
-
-    let result = query(
-        r#"
-        INSERT INTO contact_submissions (name, email, message, ip_address, user_agent, subject, metadata)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
-        RETURNING id::text, name, email, message, created_at, ip_address::text, user_agent, subject, status, metadata
-        "#
-    )
-    .bind(&submission.name)
-    .bind(&submission.email)
-    .bind(&submission.message)
-    .bind(submission.ip_address.as_deref())
-    .bind(submission.user_agent.as_deref())
-    .bind(submission.subject.as_deref())
-    .bind(&submission.metadata)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| format!("Failed to insert contact submission: {}", e))?;
-
-    Ok(ContactSubmission {
-        id: result.get("id"),
-        name: result.get("name"),
-        email: result.get("email"),
-        message: result.get("message"),
-        created_at: result.get("created_at"),
-        ip_address: result.get("ip_address"),
-        user_agent: result.get("user_agent"),
-        subject: result.get("subject"),
-        status: result.get("status"),
-        metadata: result.get("metadata"),
-    })
+/// A bare RFC 5322 address (no display name) on a domain with a top-level
+/// domain, with the domain lowercased. The local part is kept as typed, since
+/// only the receiving server knows whether its case matters.
+fn normalize_email(value: &str) -> Option<String> {
+    let options = Options::default().without_display_text().with_required_tld();
+    let email = EmailAddress::parse_with_options(value, options).ok()?;
+    Some(format!("{}@{}", email.local_part(), email.domain().to_lowercase()))
+}
+
+/// Queue the owner's notification and the sender's acknowledgement for a
+/// saved message. Failures are only logged: the message is safe already.
+#[cfg(feature = "server")]
+async fn queue_contact_mail(form: &ContactForm, id: Option<&str>, ip_address: Option<&str>) {
+    use crate::server::mail::{self, templates};
+    use tracing::warn;
+
+    if !mail::enabled() {
+        return;
+    }
+    let config = crate::api::env::get_env_config();
+    let owners = config.contact_notify_to.as_deref().or(config.admin_emails.as_deref()).unwrap_or_default();
+    let owners = owners.split(',').map(str::trim).filter(|owner| !owner.is_empty());
+    let mut queued = Vec::new();
+    for owner in owners {
+        let notification = templates::contact_notification(form, ip_address);
+        queued.push(mail::queue("contact_notification", owner, Some(&form.email), notification, id, None).await);
+    }
+    let acknowledgement = templates::contact_acknowledgement(form);
+    queued.push(mail::queue("contact_acknowledgement", &form.email, None, acknowledgement, id, None).await);
+    for error in queued.into_iter().filter_map(Result::err) {
+        warn!("Failed to queue contact mail: {}", error);
+    }
+}
+
+/// A fresh form token for the contact page.
+#[server(name = GetContactChallenge)]
+pub async fn get_contact_challenge() -> Result<ContactChallenge, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::server::spam::{issue_token, PROOF_OF_WORK_BITS};
+
+        let token = issue_token()
+            .ok_or_else(|| ServerFnError::new("The contact form needs SUPABASE_SERVICE_ROLE_KEY".to_string()))?;
+        Ok(ContactChallenge { token, difficulty: PROOF_OF_WORK_BITS })
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Save a message from the contact page. Invalid forms are answered with
+/// `ContactReply::Invalid` rather than an error, so the page can point at
+/// the fields. Spam gets `ContactReply::Sent` like anything else, so its
+/// senders can't tell it was caught.
+#[server(name = SubmitContactForm)]
+pub async fn submit_contact_form(form: ContactForm, proof: ContactProof) -> Result<ContactReply, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::service_client;
+        use crate::api::blog_admin::run;
+        use crate::api::inbox::ContactStatus;
+        use crate::server::{rate_limit, spam};
+        use tracing::info;
+
+        let form = match form.validate() {
+            Ok(form) => form,
+            Err(errors) => return Ok(ContactReply::Invalid(errors)),
+        };
+        let (ip_address, user_agent) = {
+            let context = server_context();
+            let parts = context.request_parts();
+            let user_agent = parts
+                .headers
+                .get(axum::http::header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(|agent| agent.chars().take(500).collect::<String>());
+            (crate::server::client_ip::client_ip(&parts).map(|ip| ip.to_string()), user_agent)
+        };
+
+        // Visitors whose address can't be told share one allowance.
+        let ip_key = ip_address.as_deref().unwrap_or("unknown");
+        let email_key = form.email.to_lowercase();
+        if !rate_limit::allow_all(&[(&IP_LIMIT, ip_key), (&EMAIL_LIMIT, &email_key)]).await {
+            info!("Refused a contact message from {}: too many lately", ip_address.as_deref().unwrap_or("an unknown address"));
+            return Ok(ContactReply::TooMany);
+        }
+
+        let client = service_client()
+            .ok_or_else(|| ServerFnError::new("The contact form needs SUPABASE_SERVICE_ROLE_KEY".to_string()))?;
+        let verdict = spam::assess(&form, &proof);
+        let status = if verdict.is_spam() { ContactStatus::Spam } else { ContactStatus::Unread };
+        let body = serde_json::json!({
+            "name": form.name,
+            "email": form.email,
+            "subject": (!form.subject.is_empty()).then_some(&form.subject),
+            "message": form.message,
+            "ip_address": ip_address,
+            "user_agent": user_agent,
+            "status": status,
+            "metadata": {
+                "source": "website",
+                "spam": { "score": verdict.score, "signals": verdict.signals },
+            },
+        });
+        let saved: Vec<serde_json::Value> =
+            run(client.table("contact_submissions").insert(body.to_string()), "save contact message").await?;
+        info!("New {} contact message from {}", status.as_str(), ip_address.as_deref().unwrap_or("an unknown address"));
+
+        if !verdict.is_spam() {
+            let id = saved.first().and_then(|row| row["id"].as_str());
+            queue_contact_mail(&form, id, ip_address.as_deref()).await;
+        }
+        Ok(ContactReply::Sent)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
 }
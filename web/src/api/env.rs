@@ -6,6 +6,53 @@ pub struct EnvConfig {
     pub supabase_project_id: String,
     pub supabase_url: String,
     pub supabase_anon_key: String,
+    /// Public origin of the site, used for absolute links in feeds and
+    /// metadata. No trailing slash.
+    #[serde(default = "default_site_url")]
+    pub site_url: String,
+    /// Comma-separated emails allowed to author posts. Unset means nobody.
+    /// Server only.
+    #[serde(default)]
+    pub admin_emails: Option<String>,
+    /// Key that signs draft preview links. Previews are disabled while it is
+    /// unset. Server only.
+    #[serde(default)]
+    pub preview_secret: Option<String>,
+    /// Bypasses row level security, so previews can read drafts. Server only.
+    #[serde(default)]
+    pub supabase_service_role_key: Option<String>,
+    /// Keep uploaded images in this directory rather than Supabase Storage.
+    /// Server only.
+    #[serde(default)]
+    pub image_storage_dir: Option<String>,
+    /// Comma-separated proxy addresses or ranges whose `X-Forwarded-For` is
+    /// believed. Unset means loopback only. Server only.
+    #[serde(default)]
+    pub trusted_proxies: Option<String>,
+    /// `postgres` to share rate limits through the database rather than
+    /// keeping them in memory. Server only.
+    #[serde(default)]
+    pub rate_limit_store: Option<String>,
+    /// Comma-separated phrases that mark a contact message as likely spam,
+    /// on top of the built-in ones. Server only.
+    #[serde(default)]
+    pub contact_blocked_phrases: Option<String>,
+    /// Where outgoing mail goes: `smtp://`/`smtps://` for a mail server,
+    /// `file://` for a directory of `.eml` files or `maildir://` for a
+    /// maildir. Mail is off while it is unset. Server only.
+    #[serde(default)]
+    pub mail_url: Option<String>,
+    /// Sender of outgoing mail, e.g. `Site <noreply@example.com>`. Server only.
+    #[serde(default)]
+    pub mail_from: Option<String>,
+    /// Comma-separated addresses told about new contact messages. Unset
+    /// means `admin_emails`. Server only.
+    #[serde(default)]
+    pub contact_notify_to: Option<String>,
+}
+
+fn default_site_url() -> String {
+    "https://dylanrayburn.com".to_string()
 }
 
 impl EnvConfig {
@@ -24,6 +71,19 @@ impl EnvConfig {
                 supabase_project_id: env!("APP_PUBLIC_ID").to_string(),
                 supabase_url: env!("APP_PUBLIC_SUPABASE_URL").to_string(),
                 supabase_anon_key: env!("APP_PUBLIC_SUPABASE_ANON_KEY").to_string(),
+                site_url: option_env!("APP_PUBLIC_SITE_URL")
+                    .map(str::to_string)
+                    .unwrap_or_else(default_site_url),
+                admin_emails: None,
+                preview_secret: None,
+                supabase_service_role_key: None,
+                image_storage_dir: None,
+                trusted_proxies: None,
+                rate_limit_store: None,
+                contact_blocked_phrases: None,
+                mail_url: None,
+                mail_from: None,
+                contact_notify_to: None,
             }
         }
     }
@@ -34,8 +94,31 @@ lazy_static::lazy_static! {
     pub static ref APP_PUBLIC_ID: String = ENV_CONFIG.supabase_project_id.clone();
     pub static ref APP_PUBLIC_SUPABASE_URL: String = ENV_CONFIG.supabase_url.clone();
     pub static ref APP_PUBLIC_SUPABASE_ANON_KEY: String = ENV_CONFIG.supabase_anon_key.clone();
+    pub static ref SITE_URL: String = ENV_CONFIG.site_url.trim_end_matches('/').to_string();
 }
 
 pub fn get_env_config() -> &'static EnvConfig {
     &ENV_CONFIG
-}
\ No newline at end of file
+}
+
+/// Settings for tests, made before anything reads the configuration: the
+/// required ones, and a fixed `SITE_URL` so absolute links are predictable.
+#[cfg(test)]
+pub fn use_test_config() {
+    static SET: std::sync::Once = std::sync::Once::new();
+    SET.call_once(|| {
+        std::env::set_var("SUPABASE_PROJECT_ID", "test");
+        std::env::set_var("SUPABASE_URL", "http://127.0.0.1:9");
+        std::env::set_var("SUPABASE_ANON_KEY", "test");
+        std::env::set_var("SITE_URL", "https://site.test");
+    });
+}
+
+/// Absolute URL for a path on this site.
+pub fn absolute_url(path: &str) -> String {
+    if path.starts_with("http://") || path.starts_with("https://") {
+        path.to_string()
+    } else {
+        format!("{}/{}", *SITE_URL, path.trim_start_matches('/'))
+    }
+}
@@ -0,0 +1,317 @@
+//! Image uploads from the authoring UI.
+//!
+//! An upload is checked, decoded (which drops EXIF and every other kind of
+//! metadata, after its orientation has been applied), and stored as a set of
+//! resized AVIF, WebP and JPEG variants plus a tiny blurred placeholder. The
+//! resulting `ImageVariants` is saved next to the image URL so pages can emit
+//! `srcset`/`sizes` without asking the storage backend anything.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Largest upload accepted, before decoding.
+pub const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Widths the variants are generated at. Images narrower than a width get a
+/// variant at their own width instead of being scaled up.
+#[cfg(feature = "server")]
+const VARIANT_WIDTHS: [u32; 3] = [480, 960, 1600];
+
+/// Width of the blur placeholder.
+#[cfg(feature = "server")]
+const PLACEHOLDER_WIDTH: u32 = 16;
+
+/// Upload buckets, as created by `migrations/001_initial.sql`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ImageBucket {
+    Blog,
+}
+
+#[cfg(feature = "server")]
+impl ImageBucket {
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageBucket::Blog => "blog-images",
+        }
+    }
+}
+
+/// One file of a variant set.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImageFile {
+    pub url: String,
+    pub width: u32,
+}
+
+/// All widths of an image in one format.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImageSource {
+    pub mime_type: String,
+    /// Narrowest first.
+    pub files: Vec<ImageFile>,
+}
+
+impl ImageSource {
+    pub fn srcset(&self) -> String {
+        self.files
+            .iter()
+            .map(|file| format!("{} {}w", file.url, file.width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The responsive versions of an uploaded image.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImageVariants {
+    /// The URL stored as the image itself: the widest JPEG, for browsers
+    /// that skip the `picture` sources. Variants only apply while the image
+    /// field still holds this URL.
+    pub src: String,
+    /// Size of the widest variant, so the browser can reserve space.
+    pub width: u32,
+    pub height: u32,
+    /// Blurred preview as a `data:` URI, shown while the image loads.
+    pub placeholder: String,
+    /// Best format first.
+    pub sources: Vec<ImageSource>,
+}
+
+impl ImageVariants {
+    /// These variants, if they were made for the image at `src`.
+    pub fn for_src<'a>(variants: Option<&'a ImageVariants>, src: &str) -> Option<&'a ImageVariants> {
+        variants.filter(|variants| variants.src == src)
+    }
+}
+
+/// Check, convert and store an image. `data` is the file's contents,
+/// base64-encoded.
+#[server(name = UploadImage)]
+pub async fn upload_image(access_token: String, bucket: ImageBucket, data: String) -> Result<ImageVariants, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use base64::Engine;
+        use tracing::info;
+
+        use crate::api::auth::require_author;
+        use crate::api::storage::Storage;
+
+        let user = require_author(&access_token).await?;
+        let storage = Storage::configured()?;
+        // Every 4 base64 characters hold 3 bytes; refuse oversized uploads
+        // before decoding them.
+        if data.trim_end_matches('=').len() > MAX_IMAGE_BYTES.div_ceil(3) * 4 {
+            return Err(ServerFnError::new(format!(
+                "Images may be at most {} MB",
+                MAX_IMAGE_BYTES / (1024 * 1024)
+            )));
+        }
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| ServerFnError::new(format!("Upload is not valid base64: {}", e)))?;
+
+        // Decoding and encoding are CPU bound; keep them off the async workers.
+        let processed = tokio::task::spawn_blocking(move || process::process(&bytes))
+            .await
+            .map_err(|e| ServerFnError::new(format!("Image processing failed: {}", e)))?
+            .map_err(ServerFnError::new)?;
+
+        let mut sources: Vec<ImageSource> = Vec::new();
+        for encoded in processed.files {
+            let path = format!("{}/{}.{}", processed.key, encoded.width, encoded.extension);
+            let url = storage.put(bucket.name(), &path, encoded.bytes, encoded.mime_type).await?;
+            let file = ImageFile { url, width: encoded.width };
+            match sources.iter_mut().find(|source| source.mime_type == encoded.mime_type) {
+                Some(source) => source.files.push(file),
+                None => sources.push(ImageSource { mime_type: encoded.mime_type.to_string(), files: vec![file] }),
+            }
+        }
+
+        let src = sources
+            .iter()
+            .find(|source| source.mime_type == "image/jpeg")
+            .and_then(|source| source.files.last())
+            .map(|file| file.url.clone())
+            .ok_or_else(|| ServerFnError::new("No variants were generated".to_string()))?;
+        info!("{} uploaded image {} to {}", user.email, processed.key, bucket.name());
+        Ok(ImageVariants {
+            src,
+            width: processed.width,
+            height: processed.height,
+            placeholder: processed.placeholder,
+            sources,
+        })
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Decoding, resizing and encoding, independent of where the files go.
+#[cfg(feature = "server")]
+mod process {
+    use std::io::Cursor;
+
+    use image::codecs::avif::AvifEncoder;
+    use image::codecs::jpeg::JpegEncoder;
+    use image::codecs::webp::WebPEncoder;
+    use image::imageops::FilterType;
+    use image::{
+        DynamicImage, ExtendedColorType, ImageDecoder, ImageEncoder, ImageFormat, ImageReader, Limits, Rgb, RgbImage,
+        RgbaImage,
+    };
+
+    use super::{PLACEHOLDER_WIDTH, VARIANT_WIDTHS};
+
+    /// Formats accepted for upload.
+    const ACCEPTED_FORMATS: [ImageFormat; 4] = [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP, ImageFormat::Gif];
+    /// Refuse to decode anything larger, whatever the file size.
+    const MAX_DIMENSION: u32 = 12_000;
+    /// AVIF encoder speed (1 slowest to 10 fastest) and quality (1 to 100).
+    /// The author waits for the encode, and slower speeds cost several times
+    /// the time for a few percent smaller files.
+    const AVIF_SPEED: u8 = 10;
+    const AVIF_QUALITY: u8 = 70;
+    /// JPEG quality (1 to 100) for the fallback files.
+    const JPEG_QUALITY: u8 = 80;
+
+    pub struct EncodedFile {
+        pub width: u32,
+        pub extension: &'static str,
+        pub mime_type: &'static str,
+        pub bytes: Vec<u8>,
+    }
+
+    pub struct Processed {
+        /// Directory for this image's files, from a hash of the upload, so
+        /// uploading the same file twice doesn't store it twice.
+        pub key: String,
+        pub width: u32,
+        pub height: u32,
+        pub placeholder: String,
+        /// Narrowest first within each format.
+        pub files: Vec<EncodedFile>,
+    }
+
+    pub fn process(bytes: &[u8]) -> Result<Processed, String> {
+        use sha2::{Digest, Sha256};
+
+        let mut reader = ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(|e| format!("Failed to read image: {}", e))?;
+        match reader.format() {
+            Some(format) if ACCEPTED_FORMATS.contains(&format) => {}
+            _ => return Err("Only JPEG, PNG, WebP and GIF images can be uploaded".to_string()),
+        }
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(MAX_DIMENSION);
+        limits.max_image_height = Some(MAX_DIMENSION);
+        reader.limits(limits);
+
+        let mut decoder = reader.into_decoder().map_err(|e| format!("Failed to decode image: {}", e))?;
+        let orientation = decoder.orientation().map_err(|e| format!("Failed to decode image: {}", e))?;
+        let mut image = DynamicImage::from_decoder(decoder).map_err(|e| format!("Failed to decode image: {}", e))?;
+        image.apply_orientation(orientation);
+
+        let mut widths: Vec<u32> = VARIANT_WIDTHS.into_iter().filter(|width| *width < image.width()).collect();
+        widths.push(image.width().min(VARIANT_WIDTHS[VARIANT_WIDTHS.len() - 1]));
+
+        let resized: Vec<RgbaImage> = widths.iter().map(|width| resize(&image, *width)).collect();
+        let mut files = Vec::new();
+        for variant in &resized {
+            files.push(EncodedFile {
+                width: variant.width(),
+                extension: "avif",
+                mime_type: "image/avif",
+                bytes: encode_avif(variant)?,
+            });
+        }
+        for variant in &resized {
+            files.push(EncodedFile {
+                width: variant.width(),
+                extension: "webp",
+                mime_type: "image/webp",
+                bytes: encode_webp(variant)?,
+            });
+        }
+        for variant in &resized {
+            files.push(EncodedFile {
+                width: variant.width(),
+                extension: "jpg",
+                mime_type: "image/jpeg",
+                bytes: encode_jpeg(variant)?,
+            });
+        }
+
+        let widest = resized.last().expect("at least one width");
+        let placeholder = image::imageops::blur(&resize(&image, PLACEHOLDER_WIDTH), 1.0);
+        let hash = Sha256::digest(bytes);
+        Ok(Processed {
+            key: hash.iter().take(12).map(|byte| format!("{:02x}", byte)).collect(),
+            width: widest.width(),
+            height: widest.height(),
+            placeholder: format!("data:image/webp;base64,{}", base64_encode(&encode_webp(&placeholder)?)),
+            files,
+        })
+    }
+
+    fn resize(image: &DynamicImage, width: u32) -> RgbaImage {
+        let height = (u64::from(image.height()) * u64::from(width) / u64::from(image.width().max(1))).max(1) as u32;
+        image.resize_exact(width, height, FilterType::Lanczos3).to_rgba8()
+    }
+
+    fn encode_avif(image: &RgbaImage) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        AvifEncoder::new_with_speed_quality(&mut bytes, AVIF_SPEED, AVIF_QUALITY)
+            .write_image(image, image.width(), image.height(), ExtendedColorType::Rgba8)
+            .map_err(|e| format!("Failed to encode AVIF: {}", e))?;
+        Ok(bytes)
+    }
+
+    /// The `image` crate only writes lossless WebP, which is why AVIF is
+    /// offered first and JPEG is the fallback `src`.
+    fn encode_webp(image: &RgbaImage) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        WebPEncoder::new_lossless(&mut bytes)
+            .write_image(image, image.width(), image.height(), ExtendedColorType::Rgba8)
+            .map_err(|e| format!("Failed to encode WebP: {}", e))?;
+        Ok(bytes)
+    }
+
+    /// JPEG has no alpha channel, so transparent pixels are laid over white.
+    pub(super) fn encode_jpeg(image: &RgbaImage) -> Result<Vec<u8>, String> {
+        let rgb: RgbImage = RgbImage::from_fn(image.width(), image.height(), |x, y| {
+            let [r, g, b, a] = image.get_pixel(x, y).0;
+            let alpha = u16::from(a);
+            let over_white = |channel: u8| ((u16::from(channel) * alpha + 255 * (255 - alpha)) / 255) as u8;
+            Rgb([over_white(r), over_white(g), over_white(b)])
+        });
+        let mut bytes = Vec::new();
+        JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY)
+            .write_image(&rgb, rgb.width(), rgb.height(), ExtendedColorType::Rgb8)
+            .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+        Ok(bytes)
+    }
+
+    fn base64_encode(bytes: &[u8]) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn jpeg_fallback_lays_transparency_over_white() {
+        let image = RgbaImage::from_fn(32, 32, |x, _| if x < 16 { Rgba([0, 0, 255, 255]) } else { Rgba([0, 0, 0, 0]) });
+        let bytes = super::process::encode_jpeg(&image).unwrap();
+
+        let decoded = image::load_from_memory_with_format(&bytes, image::ImageFormat::Jpeg).unwrap().to_rgb8();
+        let [r, g, b] = decoded.get_pixel(4, 4).0;
+        assert!(r < 40 && g < 40 && b > 200, "opaque blue stays blue: {:?}", (r, g, b));
+        assert!(decoded.get_pixel(28, 4).0.iter().all(|channel| *channel > 240), "transparent turns white");
+    }
+}
@@ -0,0 +1,438 @@
+//! The contact message inbox for authors (see
+//! `migrations/018_contact_inbox.sql`): listing and triaging what
+//! `submit_contact_form` saved, and answering it by email
+//! (`migrations/019_contact_replies.sql`).
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Messages per inbox page.
+#[cfg(feature = "server")]
+const INBOX_PAGE_SIZE: usize = 25;
+/// Most messages one bulk action may change.
+#[cfg(feature = "server")]
+const MAX_BULK_IDS: usize = 100;
+/// Longest reply accepted, in characters.
+pub const MAX_REPLY_CHARS: usize = 20_000;
+
+#[cfg(feature = "server")]
+const INBOX_COLUMNS: &str = "id,name,email,subject,message,status,created_at,ip_address,user_agent,metadata";
+#[cfg(feature = "server")]
+const REPLY_COLUMNS: &str =
+    "id,direction,from_address,to_address,subject,body,sent_by,created_at,mail:mail_outbox(status,last_error,sent_at)";
+
+/// Where a contact message is in the inbox.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContactStatus {
+    #[default]
+    Unread,
+    Read,
+    Replied,
+    Archived,
+    Spam,
+}
+
+impl ContactStatus {
+    pub const ALL: [ContactStatus; 5] = [
+        ContactStatus::Unread,
+        ContactStatus::Read,
+        ContactStatus::Replied,
+        ContactStatus::Archived,
+        ContactStatus::Spam,
+    ];
+
+    /// The value stored in `contact_submissions.status`.
+    #[cfg(feature = "server")]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContactStatus::Unread => "unread",
+            ContactStatus::Read => "read",
+            ContactStatus::Replied => "replied",
+            ContactStatus::Archived => "archived",
+            ContactStatus::Spam => "spam",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ContactStatus::Unread => "Unread",
+            ContactStatus::Read => "Read",
+            ContactStatus::Replied => "Replied",
+            ContactStatus::Archived => "Archived",
+            ContactStatus::Spam => "Spam",
+        }
+    }
+
+    /// What the button moving a message to this status says.
+    pub fn action(self) -> &'static str {
+        match self {
+            ContactStatus::Unread => "Mark unread",
+            ContactStatus::Read => "Mark read",
+            ContactStatus::Replied => "Mark replied",
+            ContactStatus::Archived => "Archive",
+            ContactStatus::Spam => "Spam",
+        }
+    }
+
+    /// The statuses a message may move to from this one. Messages go forward
+    /// from unread to replied; archived and spam messages can only be brought
+    /// back as read.
+    pub fn next(self) -> &'static [ContactStatus] {
+        match self {
+            ContactStatus::Unread => &[ContactStatus::Read, ContactStatus::Replied, ContactStatus::Archived, ContactStatus::Spam],
+            ContactStatus::Read => &[ContactStatus::Unread, ContactStatus::Replied, ContactStatus::Archived, ContactStatus::Spam],
+            ContactStatus::Replied => &[ContactStatus::Archived, ContactStatus::Spam],
+            ContactStatus::Archived | ContactStatus::Spam => &[ContactStatus::Read],
+        }
+    }
+
+    /// The statuses a message may move to this one from.
+    #[cfg(feature = "server")]
+    fn previous(self) -> Vec<&'static str> {
+        ContactStatus::ALL
+            .into_iter()
+            .filter(|status| status.next().contains(&self))
+            .map(ContactStatus::as_str)
+            .collect()
+    }
+}
+
+/// A contact message as the inbox shows it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct InboxMessage {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub subject: Option<String>,
+    pub message: String,
+    pub status: ContactStatus,
+    pub created_at: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// Where the message came from and its spam verdict.
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Which messages to list. Empty fields don't filter.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InboxFilter {
+    pub status: Option<ContactStatus>,
+    /// First and last day to include, as `YYYY-MM-DD` in UTC.
+    pub from: String,
+    pub to: String,
+    /// Part of the sender's address.
+    pub email: String,
+    /// From 1.
+    pub page: u32,
+}
+
+/// One page of the inbox.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct InboxPage {
+    pub messages: Vec<InboxMessage>,
+    /// Messages matching the filter, on every page.
+    pub total: usize,
+    pub total_pages: u32,
+}
+
+/// Which way a reply went.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplyDirection {
+    /// Sent from the inbox.
+    #[default]
+    Outgoing,
+    /// An answer from the sender, brought in from a mailbox.
+    Incoming,
+}
+
+/// How an outgoing reply is getting on in `mail_outbox`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReplyDelivery {
+    /// 'pending', 'sent' or 'failed'.
+    pub status: String,
+    pub last_error: Option<String>,
+    pub sent_at: Option<String>,
+}
+
+/// One email in the conversation about a contact message.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InboxReply {
+    pub id: i64,
+    pub direction: ReplyDirection,
+    pub from_address: String,
+    pub to_address: String,
+    pub subject: String,
+    pub body: String,
+    /// The author who sent it.
+    pub sent_by: Option<String>,
+    pub created_at: String,
+    pub mail: Option<ReplyDelivery>,
+}
+
+/// Whether `id` looks like a message's UUID, so it can go into a filter.
+#[cfg(feature = "server")]
+fn valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+/// Contact messages are closed to every signed-in user by row level
+/// security, so the inbox reads and writes them with the service role key,
+/// once `require_author` has let the caller in.
+#[cfg(feature = "server")]
+fn inbox_client() -> Result<crate::api::auth::ServerSupabaseClient, ServerFnError> {
+    crate::api::auth::service_client()
+        .ok_or_else(|| ServerFnError::new("The inbox needs SUPABASE_SERVICE_ROLE_KEY".to_string()))
+}
+
+/// `day` as the start of that day in UTC, for comparing with `created_at`.
+#[cfg(feature = "server")]
+fn day_start(day: &str, days_later: i64) -> Result<String, ServerFnError> {
+    let day = chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .map_err(|_| ServerFnError::new(format!("Invalid date '{}', expected YYYY-MM-DD", day)))?;
+    Ok((day + chrono::Duration::days(days_later)).and_time(chrono::NaiveTime::MIN).and_utc().to_rfc3339())
+}
+
+/// A page of contact messages matching `filter`, newest first.
+#[server(name = ListContactMessages)]
+pub async fn list_contact_messages(access_token: String, filter: InboxFilter) -> Result<InboxPage, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog::content_range_total;
+
+        require_author(&access_token).await?;
+        let mut query = inbox_client()?.table("contact_submissions").select(INBOX_COLUMNS);
+        if let Some(status) = filter.status {
+            query = query.eq("status", status.as_str());
+        }
+        if !filter.from.trim().is_empty() {
+            query = query.gte("created_at", day_start(filter.from.trim(), 0)?);
+        }
+        if !filter.to.trim().is_empty() {
+            query = query.lt("created_at", day_start(filter.to.trim(), 1)?);
+        }
+        // Wildcards typed into the box are taken literally by dropping them.
+        let email: String = filter.email.trim().chars().filter(|c| !matches!(c, '*' | '%')).collect();
+        if !email.is_empty() {
+            query = query.ilike("email", format!("%{}%", email));
+        }
+
+        let offset = (filter.page.max(1) as usize - 1) * INBOX_PAGE_SIZE;
+        let response = query
+            .order("created_at.desc,id.desc")
+            .exact_count()
+            .range(offset, offset + INBOX_PAGE_SIZE - 1)
+            .execute()
+            .await
+            .map_err(|e| ServerFnError::new(format!("Request failed: {}", e)))?;
+        let status = response.status();
+        let total = content_range_total(response.headers().get("content-range").and_then(|v| v.to_str().ok())).unwrap_or(0);
+        let total_pages = total.div_ceil(INBOX_PAGE_SIZE) as u32;
+        let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+
+        // PostgREST answers 416 for a page past the last one.
+        if status.as_u16() == 416 {
+            return Ok(InboxPage { messages: Vec::new(), total, total_pages });
+        }
+        if !status.is_success() {
+            return Err(ServerFnError::new(format!("Failed to list contact messages. Status: {}, Response: {}", status, text)));
+        }
+        let messages: Vec<InboxMessage> = serde_json::from_str(&text)
+            .map_err(|e| ServerFnError::new(format!("Failed to parse contact messages: {}", e)))?;
+        Ok(InboxPage { messages, total, total_pages })
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// How many contact messages nobody has opened yet, for the navbar.
+#[server(name = UnreadContactCount)]
+pub async fn unread_contact_count(access_token: String) -> Result<usize, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::count;
+
+        require_author(&access_token).await?;
+        let query = inbox_client()?
+            .table("contact_submissions")
+            .select("id")
+            .eq("status", ContactStatus::Unread.as_str());
+        count(query, "count unread contact messages").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Move the messages `ids` to `status`. Messages that may not move there
+/// from where they are (see `ContactStatus::next`) are left alone; the
+/// answer is how many moved.
+#[server(name = SetContactStatus)]
+pub async fn set_contact_status(access_token: String, ids: Vec<String>, status: ContactStatus) -> Result<usize, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::run;
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        if ids.len() > MAX_BULK_IDS {
+            return Err(ServerFnError::new(format!("At most {} messages can be changed at once", MAX_BULK_IDS)));
+        }
+        if let Some(id) = ids.iter().find(|id| !valid_id(id)) {
+            return Err(ServerFnError::new(format!("Invalid message id '{}'", id)));
+        }
+
+        let body = serde_json::json!({ "status": status });
+        let query = inbox_client()?
+            .table("contact_submissions")
+            .in_("id", &ids)
+            .in_("status", status.previous())
+            .update(body.to_string())
+            .select("id");
+        let moved: Vec<serde_json::Value> = run(query, "update contact messages").await?;
+        info!("{} marked {} of {} contact messages as {}", user.email, moved.len(), ids.len(), status.as_str());
+        Ok(moved.len())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// The replies to the message `id`, oldest first.
+#[server(name = GetContactThread)]
+pub async fn get_contact_thread(access_token: String, id: String) -> Result<Vec<InboxReply>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::run;
+
+        require_author(&access_token).await?;
+        if !valid_id(&id) {
+            return Err(ServerFnError::new(format!("Invalid message id '{}'", id)));
+        }
+        let query = inbox_client()?
+            .table("contact_replies")
+            .select(REPLY_COLUMNS)
+            .eq("contact_submission_id", &id)
+            .order("created_at.asc,id.asc");
+        run(query, "load replies").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Email `body` to whoever sent the message `id`, quoting their message, and
+/// mark it replied. The reply is recorded before it is queued, so no mail goes
+/// out that the conversation doesn't show; it may still be on its way when
+/// this returns.
+#[server(name = SendContactReply)]
+pub async fn send_contact_reply(access_token: String, id: String, body: String) -> Result<InboxReply, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::run;
+        use crate::server::mail::{self, templates, Thread};
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        if !valid_id(&id) {
+            return Err(ServerFnError::new(format!("Invalid message id '{}'", id)));
+        }
+        if body.trim().is_empty() {
+            return Err(ServerFnError::new("Please write a reply".to_string()));
+        }
+        if body.chars().count() > MAX_REPLY_CHARS {
+            return Err(ServerFnError::new(format!("Replies are limited to {} characters", MAX_REPLY_CHARS)));
+        }
+        if !mail::enabled() {
+            return Err(ServerFnError::new("Replies are sent by email, which is off until MAIL_URL is set".to_string()));
+        }
+
+        let client = inbox_client()?;
+        let query = client.table("contact_submissions").select(INBOX_COLUMNS).eq("id", &id);
+        let original: Vec<InboxMessage> = run(query, "load contact message").await?;
+        let original = original.into_iter().next().ok_or_else(|| ServerFnError::new("No such message".to_string()))?;
+
+        // Answer the latest email in the conversation, so clients thread them.
+        let query = client
+            .table("contact_replies")
+            .select("message_id")
+            .eq("contact_submission_id", &id)
+            .not("is", "message_id", "null")
+            .order("created_at.desc,id.desc")
+            .limit(1);
+        let latest: Vec<serde_json::Value> = run(query, "load replies").await?;
+        let thread = Thread {
+            message_id: mail::new_message_id(),
+            in_reply_to: latest.first().and_then(|reply| reply["message_id"].as_str()).map(str::to_string),
+        };
+
+        let rendered = templates::contact_reply(&original, &body);
+        let config = crate::api::env::get_env_config();
+        let record = serde_json::json!({
+            "contact_submission_id": id,
+            "direction": ReplyDirection::Outgoing,
+            "from_address": config.mail_from.as_deref().unwrap_or_default(),
+            "to_address": original.email,
+            "subject": rendered.subject,
+            "body": body,
+            "message_id": thread.message_id,
+            "in_reply_to": thread.in_reply_to,
+            "sent_by": user.email,
+        });
+        let query = client.table("contact_replies").insert(record.to_string()).select("id");
+        let saved: Vec<serde_json::Value> = run(query, "save reply").await?;
+        let reply_id = saved
+            .first()
+            .and_then(|row| row["id"].as_i64())
+            .ok_or_else(|| ServerFnError::new("The reply was not saved".to_string()))?
+            .to_string();
+
+        let outbox_id =
+            match mail::queue("contact_reply", &original.email, Some(&user.email), rendered, Some(&id), Some(&thread)).await {
+                Ok(outbox_id) => outbox_id,
+                Err(e) => {
+                    // Take the reply back out of the conversation, since it won't be sent.
+                    let query = client.table("contact_replies").eq("id", &reply_id).delete();
+                    if let Err(cleanup) = run::<serde_json::Value>(query, "remove unsent reply").await {
+                        tracing::warn!("Reply {} to contact message {} was saved but not queued: {}", reply_id, id, cleanup);
+                    }
+                    return Err(e);
+                }
+            };
+
+        let link = serde_json::json!({ "mail_outbox_id": outbox_id });
+        let query = client.table("contact_replies").eq("id", &reply_id).update(link.to_string()).select(REPLY_COLUMNS);
+        let saved: Vec<InboxReply> = run(query, "link reply to its mail").await?;
+
+        // Only unread and read messages move to replied; archived and spam
+        // ones stay where they are.
+        let status = serde_json::json!({ "status": ContactStatus::Replied });
+        let query = client
+            .table("contact_submissions")
+            .eq("id", &id)
+            .in_("status", ContactStatus::Replied.previous())
+            .update(status.to_string());
+        let _: serde_json::Value = run(query, "mark contact message replied").await?;
+
+        info!("{} replied to contact message {}", user.email, id);
+        saved.into_iter().next().ok_or_else(|| ServerFnError::new("The reply was queued but not found".to_string()))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
@@ -1,11 +1,29 @@
+#[cfg(feature = "server")]
 pub mod database;
 pub mod auth;
 pub mod contact;
+pub mod inbox;
 
 pub mod blog;
-pub use blog::{get_blog, get_blog_with_slug, BlogPost, GetBlogWithSlug};
+pub use blog::{get_blog_page, get_blog_page_after, get_blog_with_slug, BlogPage, BlogPost, BlogPostSummary};
+pub mod blog_admin;
+pub mod preview;
+pub mod revisions;
+pub mod images;
+pub mod tags;
+pub use tags::{get_tag_archive, get_tags, tag_slug, TagCount};
+pub mod series;
+pub use series::{get_series_archive, PostSeries};
+pub mod comments;
+pub mod webmentions;
+pub mod related;
+pub use related::{get_post_links, PostLinks};
+pub mod search;
+pub use search::{search, SearchHit, SearchKind};
 pub mod projects;
-pub use projects::{get_projects, Project};
 
-mod env;
- 
\ No newline at end of file
+pub mod env;
+#[cfg(feature = "server")]
+pub mod source;
+#[cfg(feature = "server")]
+pub mod storage;
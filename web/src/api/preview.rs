@@ -0,0 +1,145 @@
+//! Shareable preview links for drafts and scheduled posts.
+//!
+//! A link is the post's usual URL plus `?preview=<token>`, where the token is
+//! `<post id>.<expiry in unix seconds>.<signature>` and the signature is a
+//! hex HMAC-SHA256 of the first two parts, keyed with `PREVIEW_SECRET`.
+//! Nothing is stored, so changing the secret revokes every link handed out.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::blog::BlogPost;
+
+/// How long a new preview link stays valid.
+#[cfg(feature = "server")]
+pub const PREVIEW_LINK_HOURS: i64 = 72;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PreviewLink {
+    pub url: String,
+    pub expires_at: String,
+}
+
+/// The `preview` query parameter of a path such as `/blog/post?preview=...`.
+pub fn preview_token(path: &str) -> Option<String> {
+    let query = path.split('#').next()?.split_once('?')?.1;
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("preview="))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(feature = "server")]
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+
+#[cfg(feature = "server")]
+fn mac(payload: &str) -> Result<HmacSha256, ServerFnError> {
+    use hmac::Mac;
+
+    let secret = crate::api::env::get_env_config()
+        .preview_secret
+        .as_deref()
+        .filter(|secret| !secret.is_empty())
+        .ok_or_else(|| ServerFnError::new("Previews are disabled: PREVIEW_SECRET is not set".to_string()))?;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(payload.as_bytes());
+    Ok(mac)
+}
+
+/// A token granting access to post `id` until `expires`.
+#[cfg(feature = "server")]
+pub fn sign_token(id: i32, expires: chrono::DateTime<chrono::Utc>) -> Result<String, ServerFnError> {
+    use hmac::Mac;
+
+    let payload = format!("{}.{}", id, expires.timestamp());
+    let signature: String = mac(&payload)?
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Ok(format!("{}.{}", payload, signature))
+}
+
+/// The post id `token` grants access to, if it is genuine and unexpired.
+#[cfg(feature = "server")]
+fn verify_token(token: &str) -> Result<Option<i32>, ServerFnError> {
+    use hmac::Mac;
+
+    let Some((payload, signature)) = token.rsplit_once('.') else {
+        return Ok(None);
+    };
+    let Some((id, expires)) = payload.split_once('.') else {
+        return Ok(None);
+    };
+    let signature: Option<Vec<u8>> = (signature.len() % 2 == 0)
+        .then(|| {
+            (0..signature.len())
+                .step_by(2)
+                .map(|i| signature.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+                .collect()
+        })
+        .flatten();
+    let Some(signature) = signature else {
+        return Ok(None);
+    };
+    if mac(payload)?.verify_slice(&signature).is_err() {
+        return Ok(None);
+    }
+
+    let unexpired = expires.parse::<i64>().is_ok_and(|expires| expires > chrono::Utc::now().timestamp());
+    Ok(unexpired.then(|| id.parse().ok()).flatten())
+}
+
+/// The post behind a preview link, whatever its publishing state. Fails
+/// unless `token` is a valid, unexpired token for the post at `slug`.
+#[server(name = GetBlogPreview)]
+pub async fn get_blog_preview(slug: String, token: String) -> Result<BlogPost, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use tracing::info;
+
+        let invalid = || ServerFnError::new("This preview link is invalid or has expired".to_string());
+        let id = verify_token(&token)?.ok_or_else(invalid)?;
+        let mut post = fetch_any_post(id).await?.filter(|post| post.slug == slug).ok_or_else(invalid)?;
+
+        info!("Serving preview of blog post {} '{}'", post.id, post.slug);
+        post.content_html = crate::content::rendered_post_html(post.id, &post.content);
+        Ok(post)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Post `id` including drafts. Row level security hides drafts from the
+/// anonymous key, so Supabase is asked with the service role key.
+#[cfg(feature = "server")]
+async fn fetch_any_post(id: i32) -> Result<Option<BlogPost>, ServerFnError> {
+    use crate::api::auth::service_client;
+    use crate::api::blog::SupabaseBlogPost;
+
+    if let Some(posts) = crate::api::source::current().blog_posts(false).await {
+        return posts.map(|posts| posts.into_iter().find(|post| post.id == id));
+    }
+
+    let response = service_client()
+        .ok_or_else(|| ServerFnError::new("Previews need SUPABASE_SERVICE_ROLE_KEY".to_string()))?
+        .table("blog_posts")
+        .select("id,title,content,author,created_at,slug,excerpt,tags,cover_image,cover_image_variants,published_at,updated_at,is_published,series_part,series:blog_series(slug,title)")
+        .eq("id", id.to_string())
+        .execute()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+    if !status.is_success() {
+        return Err(ServerFnError::new(format!("Failed to fetch preview. Status: {}, Response: {}", status, text)));
+    }
+    let posts: Vec<SupabaseBlogPost> = serde_json::from_str(&text)
+        .map_err(|e| ServerFnError::new(format!("Failed to parse blog post JSON: {}", e)))?;
+    Ok(posts.into_iter().next().map(BlogPost::from))
+}
@@ -1,5 +1,7 @@
 use dioxus::prelude::*;
 
+use crate::api::images::ImageVariants;
+
 
 // Project data structure to match our database schema
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -10,6 +12,10 @@ pub struct Project {
     pub category: String,
     pub technologies: Vec<String>,
     pub image_url: Option<String>,
+    /// Responsive versions of `image_url`, for images uploaded through
+    /// `api::images`.
+    #[serde(default)]
+    pub image_variants: Option<ImageVariants>,
     pub github_url: Option<String>,
     pub demo_url: Option<String>,
     pub featured: bool,
@@ -23,6 +29,10 @@ pub async fn get_projects() -> Result<Vec<Project>, ServerFnError> {
         use crate::api::auth::create_server_client;
         use tracing::info;
         
+        if let Some(projects) = crate::api::source::current().projects().await {
+            return projects;
+        }
+
         info!("Fetching projects from Supabase...");
         
         let client = create_server_client();
@@ -30,7 +40,7 @@ pub async fn get_projects() -> Result<Vec<Project>, ServerFnError> {
         // Query projects with specific fields and ordering - remove any potential limits
         let resp = client
             .table("projects")
-            .select("id,title,description,category,technologies,github_url,demo_url,featured,created_at,image_url")
+            .select("id,title,description,category,technologies,github_url,demo_url,featured,created_at,image_url,image_variants")
             .order("created_at.desc")
             .limit(1000) // Explicitly set a high limit to ensure we get all projects
             .execute()
@@ -0,0 +1,205 @@
+//! Where to go after reading a post: its neighbours by publish date, the
+//! rest of its series and the posts most like it.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::blog::BlogPostSummary;
+use crate::api::series::PostSeries;
+
+/// Most related posts shown under a post.
+#[cfg(feature = "server")]
+pub const MAX_RELATED_POSTS: usize = 3;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PostLinks {
+    /// The post published just before this one.
+    pub previous: Option<BlogPostSummary>,
+    /// The post published just after this one.
+    pub next: Option<BlogPostSummary>,
+    /// Best match first: posts sharing the most tags, then the most words of
+    /// the title, tags and excerpt.
+    pub related: Vec<BlogPostSummary>,
+    /// The series the post is part of.
+    pub series: Option<PostSeries>,
+}
+
+/// Links for the end of the public post at `slug`.
+#[server(name = GetPostLinks)]
+pub async fn get_post_links(slug: String) -> Result<PostLinks, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        fetch_post_links(&slug).await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+#[cfg(feature = "server")]
+async fn fetch_post_links(slug: &str) -> Result<PostLinks, ServerFnError> {
+    use crate::api::auth::create_server_client;
+    use crate::api::blog::{fill_reading_stats, only_public, BlogCursor, SUMMARY_COLUMNS};
+    use crate::api::blog_admin::run;
+    use crate::api::series::fetch_post_series;
+    use tracing::info;
+
+    #[derive(Deserialize)]
+    struct PostKey {
+        id: i32,
+        published_at: String,
+        series_id: Option<i32>,
+    }
+
+    let source = crate::api::source::current();
+    if let (Some(posts), Some(series)) = (source.blog_posts(true).await, source.series().await) {
+        return local::links(&posts?, &series?, slug)
+            .ok_or_else(|| ServerFnError::new(format!("Blog post with slug '{}' not found", slug)));
+    }
+
+    info!("Fetching links for blog post '{}' from Supabase...", slug);
+    let client = create_server_client();
+    let key: PostKey = run(
+        only_public(client.table("blog_posts").select("id,published_at,series_id")).eq("slug", slug).single(),
+        "look up blog post",
+    )
+    .await?;
+    let post = BlogCursor { published_at: key.published_at, id: key.id };
+
+    let previous = only_public(client.table("blog_posts").select(SUMMARY_COLUMNS))
+        .or(post.filter())
+        .order("published_at.desc,id.desc")
+        .limit(1);
+    let next = only_public(client.table("blog_posts").select(SUMMARY_COLUMNS))
+        .or(post.preceding_filter())
+        .order("published_at.asc,id.asc")
+        .limit(1);
+    let params = serde_json::json!({ "post_id": post.id, "max_results": MAX_RELATED_POSTS });
+    let related = client.rpc("related_blog_posts", params.to_string()).select(SUMMARY_COLUMNS);
+
+    let series = async {
+        match key.series_id {
+            Some(id) => fetch_post_series(id).await.map(Some),
+            None => Ok(None),
+        }
+    };
+
+    let (mut previous, mut next, mut related, series) = tokio::try_join!(
+        run::<Vec<BlogPostSummary>>(previous, "fetch previous post"),
+        run::<Vec<BlogPostSummary>>(next, "fetch next post"),
+        run::<Vec<BlogPostSummary>>(related, "fetch related posts"),
+        series,
+    )?;
+    fill_reading_stats(&mut previous).await?;
+    fill_reading_stats(&mut next).await?;
+    fill_reading_stats(&mut related).await?;
+
+    Ok(PostLinks { previous: previous.pop(), next: next.pop(), related, series })
+}
+
+/// An in-memory stand-in for `related_blog_posts()` and the neighbour
+/// queries, for the non-Supabase sources: tags score the same, and plain words
+/// weighted like `search_site()` stand in for `ts_rank`.
+#[cfg(feature = "server")]
+mod local {
+    use std::collections::HashSet;
+
+    use super::{PostLinks, MAX_RELATED_POSTS};
+    use crate::api::blog::{BlogPost, BlogPostSummary};
+    use crate::api::series::{sort_series_posts, BlogSeries, PostSeries, SeriesPart};
+    use crate::api::tags::tag_slug;
+
+    /// Words too common to say two posts are alike.
+    const STOP_WORDS: &[&str] = &[
+        "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "how", "in", "into", "is", "it", "its", "of",
+        "on", "or", "that", "the", "this", "to", "was", "what", "when", "why", "with", "you", "your",
+    ];
+
+    fn words(text: &str) -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .filter(|word| !word.is_empty() && !STOP_WORDS.contains(&word.as_str()))
+            .collect()
+    }
+
+    fn tag_slugs(post: &BlogPost) -> HashSet<String> {
+        post.tags.iter().map(|tag| tag_slug(tag)).filter(|slug| !slug.is_empty()).collect()
+    }
+
+    /// One point per shared tag, plus the share of `terms` found in `post`,
+    /// each weighted by the field it was found in.
+    fn score(post: &BlogPost, tags: &HashSet<String>, terms: &HashSet<String>) -> f32 {
+        let shared_tags = tag_slugs(post).intersection(tags).count() as f32;
+        if terms.is_empty() {
+            return shared_tags;
+        }
+
+        let fields = [
+            (1.0, words(&format!("{} {}", post.title, post.tags.join(" ")))),
+            (0.4, words(&post.excerpt)),
+            (0.1, words(&post.content)),
+        ];
+        let text: f32 = terms
+            .iter()
+            .map(|term| {
+                fields
+                    .iter()
+                    .filter(|(_, words)| words.contains(term))
+                    .map(|(weight, _)| *weight)
+                    .fold(0.0, f32::max)
+            })
+            .sum();
+        shared_tags + text / terms.len() as f32
+    }
+
+    /// The series `post` is part of, with its other public posts from `posts`.
+    fn post_series(post: &BlogPost, posts: &[&BlogPost], series: &[BlogSeries]) -> Option<PostSeries> {
+        let slug = &post.series.as_ref()?.slug;
+        let series = series.iter().find(|series| &series.slug == slug)?.clone();
+        let mut members: Vec<&BlogPost> = posts
+            .iter()
+            .filter(|other| other.series.as_ref().is_some_and(|series| &series.slug == slug))
+            .copied()
+            .collect();
+        sort_series_posts(&mut members, |post| (post.series_part, post.published_at.as_str(), post.id));
+        Some(PostSeries {
+            series,
+            parts: members
+                .into_iter()
+                .map(|post| SeriesPart { slug: post.slug.clone(), title: post.title.clone() })
+                .collect(),
+        })
+    }
+
+    pub fn links(posts: &[BlogPost], series: &[BlogSeries], slug: &str) -> Option<PostLinks> {
+        let mut posts: Vec<&BlogPost> = posts.iter().collect();
+        posts.sort_by(|a, b| (a.published_at.as_str(), a.id).cmp(&(b.published_at.as_str(), b.id)));
+        let index = posts.iter().position(|post| post.slug == slug)?;
+        let post = posts[index];
+
+        let tags = tag_slugs(post);
+        let terms = words(&format!("{} {} {}", post.title, post.tags.join(" "), post.excerpt));
+        let mut related: Vec<(f32, &BlogPost)> = posts
+            .iter()
+            .filter(|other| other.id != post.id)
+            .map(|other| (score(other, &tags, &terms), *other))
+            .filter(|(score, _)| *score > 0.0)
+            .collect();
+        related.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| (b.1.published_at.as_str(), b.1.id).cmp(&(a.1.published_at.as_str(), a.1.id)))
+        });
+
+        Some(PostLinks {
+            previous: index.checked_sub(1).map(|i| BlogPostSummary::from(posts[i])),
+            next: posts.get(index + 1).map(|next| BlogPostSummary::from(*next)),
+            related: related
+                .into_iter()
+                .take(MAX_RELATED_POSTS)
+                .map(|(_, post)| BlogPostSummary::from(post))
+                .collect(),
+            series: post_series(post, &posts, series),
+        })
+    }
+}
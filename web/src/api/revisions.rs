@@ -0,0 +1,218 @@
+//! Revision history behind the dashboard's post history page.
+//!
+//! Revisions are written by the database (see
+//! `migrations/008_blog_post_revisions.sql`); these functions only read them
+//! and write restores back through `blog_posts`, which records the restore
+//! as a new revision. The table is closed to signed-in users, so both go
+//! through the dashboard's service client once `require_author` has passed.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::blog_admin::PostDraft;
+
+/// A version of a post in its history list.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RevisionSummary {
+    pub id: i32,
+    pub post_id: i32,
+    pub title: String,
+    /// Email of whoever saved this version, or the database role for edits
+    /// made outside the dashboard. Unknown for the versions that predate the
+    /// history.
+    pub author: Option<String>,
+    pub change_note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DiffKind {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+/// The full snapshot stored with each revision.
+#[cfg(feature = "server")]
+#[derive(Deserialize)]
+struct Revision {
+    id: i32,
+    post_id: i32,
+    #[serde(flatten)]
+    post: PostDraft,
+}
+
+#[cfg(feature = "server")]
+const REVISION_COLUMNS: &str = "id,post_id,title,slug,excerpt,tags,cover_image,cover_image_variants,content,is_published,published_at";
+
+#[cfg(feature = "server")]
+impl Revision {
+    /// The revision as text for diffing: the metadata as header lines, then
+    /// the body.
+    fn to_text(&self) -> String {
+        let post = &self.post;
+        format!(
+            "Title: {}\nSlug: {}\nTags: {}\nCover image: {}\nExcerpt: {}\n\n{}",
+            post.title,
+            post.slug,
+            post.tags.join(", "),
+            post.cover_image.as_deref().unwrap_or(""),
+            post.excerpt,
+            post.content
+        )
+    }
+}
+
+/// Every saved version of post `post_id`, newest (the current one) first.
+#[server(name = ListRevisions)]
+pub async fn list_revisions(access_token: String, post_id: i32) -> Result<Vec<RevisionSummary>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::{admin_client, run};
+
+        require_author(&access_token).await?;
+        let query = admin_client()?
+            .table("blog_post_revisions")
+            .select("id,post_id,title,author,change_note,created_at")
+            .eq("post_id", post_id.to_string())
+            .order("id.desc");
+        run(query, "list revisions").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Line-by-line changes going from revision `from` to revision `to`.
+#[server(name = DiffRevisions)]
+pub async fn diff_revisions(access_token: String, from: i32, to: i32) -> Result<Vec<DiffLine>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+
+        require_author(&access_token).await?;
+        let old = fetch_revision(from).await?;
+        let new = fetch_revision(to).await?;
+        if old.post_id != new.post_id {
+            return Err(ServerFnError::new("Revisions belong to different posts".to_string()));
+        }
+        Ok(line_diff(&old.to_text(), &new.to_text()))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Bring back the text of revision `id`. The post keeps its publishing state,
+/// and the restore becomes the newest revision, so it can be undone too.
+#[server(name = RestoreRevision)]
+pub async fn restore_revision(access_token: String, id: i32) -> Result<PostDraft, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::require_author;
+        use crate::api::blog_admin::{add_reading_stats, admin_client, run, DRAFT_COLUMNS};
+        use tracing::info;
+
+        let user = require_author(&access_token).await?;
+        let revision = fetch_revision(id).await?;
+        let post = &revision.post;
+        let mut body = serde_json::json!({
+            "title": post.title,
+            "slug": post.slug,
+            "excerpt": post.excerpt,
+            "tags": post.tags,
+            "cover_image": post.cover_image,
+            "cover_image_variants": post.cover_image_variants,
+            "content": post.content,
+            "revision_note": format!("Restored revision #{}", revision.id),
+            "revision_author": user.email,
+        });
+        add_reading_stats(&mut body, &post.content);
+        let query = admin_client()?
+            .table("blog_posts")
+            .eq("id", revision.post_id.to_string())
+            .update(body.to_string())
+            .select(DRAFT_COLUMNS)
+            .single();
+        let restored: PostDraft = run(query, "restore revision").await?;
+        info!("{} restored blog post {} to revision {}", user.email, revision.post_id, revision.id);
+        Ok(restored)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+#[cfg(feature = "server")]
+async fn fetch_revision(id: i32) -> Result<Revision, ServerFnError> {
+    use crate::api::blog_admin::{admin_client, run};
+
+    let query = admin_client()?
+        .table("blog_post_revisions")
+        .select(REVISION_COLUMNS)
+        .eq("id", id.to_string())
+        .single();
+    run(query, "load revision").await
+}
+
+/// A shortest line diff of `old` against `new`, from a longest common
+/// subsequence. Lines shared at the start and end are set aside first, since
+/// most edits touch a small part of a post.
+#[cfg(feature = "server")]
+fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let line = |kind, text: &str| DiffLine { kind, text: text.to_string() };
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // common[i][j]: length of the longest common subsequence of a[i..] and b[j..].
+    let mut common = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            common[i][j] = if a[i] == b[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<DiffLine> = old[..prefix].iter().map(|text| line(DiffKind::Unchanged, text)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            lines.push(line(DiffKind::Unchanged, a[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            lines.push(line(DiffKind::Removed, a[i]));
+            i += 1;
+        } else {
+            lines.push(line(DiffKind::Added, b[j]));
+            j += 1;
+        }
+    }
+    lines.extend(a[i..].iter().map(|text| line(DiffKind::Removed, text)));
+    lines.extend(b[j..].iter().map(|text| line(DiffKind::Added, text)));
+    lines.extend(old[old.len() - suffix..].iter().map(|text| line(DiffKind::Unchanged, text)));
+    lines
+}
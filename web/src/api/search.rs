@@ -0,0 +1,279 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Most hits returned for one query.
+pub const MAX_SEARCH_RESULTS: u32 = 20;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    Post,
+    Project,
+}
+
+/// A run of snippet text, highlighted if it matched the query.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnippetPart {
+    pub text: String,
+    pub highlight: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub id: i64,
+    pub title: String,
+    /// Set for posts only.
+    pub slug: Option<String>,
+    pub snippet: Vec<SnippetPart>,
+}
+
+/// Split a `ts_headline` snippet on its `<mark>` delimiters. The text itself
+/// is never treated as HTML.
+#[cfg(feature = "server")]
+fn snippet_parts(snippet: &str) -> Vec<SnippetPart> {
+    let mut parts = Vec::new();
+    for (i, chunk) in snippet.split("<mark>").enumerate() {
+        let (marked, rest) = match chunk.split_once("</mark>") {
+            Some((marked, rest)) if i > 0 => (marked, rest),
+            _ => ("", chunk),
+        };
+        if !marked.is_empty() {
+            parts.push(SnippetPart { text: marked.to_string(), highlight: true });
+        }
+        if !rest.is_empty() {
+            parts.push(SnippetPart { text: rest.to_string(), highlight: false });
+        }
+    }
+    parts
+}
+
+/// A row of `search_site()`.
+#[cfg(feature = "server")]
+#[derive(Deserialize)]
+struct SearchRow {
+    kind: SearchKind,
+    id: i64,
+    title: String,
+    slug: Option<String>,
+    snippet: Option<String>,
+}
+
+#[cfg(feature = "server")]
+impl From<SearchRow> for SearchHit {
+    fn from(row: SearchRow) -> Self {
+        SearchHit {
+            kind: row.kind,
+            id: row.id,
+            title: row.title,
+            slug: row.slug,
+            snippet: snippet_parts(&row.snippet.unwrap_or_default()),
+        }
+    }
+}
+
+/// Published posts and projects matching `q`, best match first. Every word
+/// is matched as a prefix, so partial input already finds results.
+#[server(name = SearchSite)]
+pub async fn search(q: String) -> Result<Vec<SearchHit>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if !q.chars().any(|c| c.is_alphanumeric()) {
+            return Ok(Vec::new());
+        }
+        fetch_search(q.trim()).await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+#[cfg(feature = "server")]
+async fn fetch_search(query: &str) -> Result<Vec<SearchHit>, ServerFnError> {
+    use crate::api::auth::create_server_client;
+    use crate::api::source::ContentSource;
+    use tracing::info;
+
+    let source = crate::api::source::current();
+    if let ContentSource::Postgres = source {
+        return postgres_search(query).await;
+    }
+    if let ContentSource::Fixture(_) = source {
+        let posts = source.blog_posts(true).await.unwrap_or(Ok(Vec::new()))?;
+        let projects = source.projects().await.unwrap_or(Ok(Vec::new()))?;
+        return Ok(local::search(query, &posts, &projects));
+    }
+
+    info!("Searching Supabase for '{}'...", query);
+    let params = serde_json::json!({ "query": query, "max_results": MAX_SEARCH_RESULTS });
+    let response = create_server_client()
+        .rpc("search_site", params.to_string())
+        .execute()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+    if !status.is_success() {
+        let error_msg = format!("Search failed. Status: {}, Response: {}", status, text);
+        info!("{}", error_msg);
+        return Err(ServerFnError::new(error_msg));
+    }
+
+    let rows: Vec<SearchRow> = serde_json::from_str(&text)
+        .map_err(|e| ServerFnError::new(format!("Failed to parse search results JSON: {}", e)))?;
+    Ok(rows.into_iter().map(SearchHit::from).collect())
+}
+
+#[cfg(feature = "server")]
+async fn postgres_search(query: &str) -> Result<Vec<SearchHit>, ServerFnError> {
+    let pool = crate::api::database::connection()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let rows: Vec<sqlx::types::Json<SearchRow>> =
+        sqlx::query_scalar("SELECT to_jsonb(hit) FROM search_site($1, $2) hit")
+            .bind(query)
+            .bind(MAX_SEARCH_RESULTS as i32)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Search failed: {}", e)))?;
+
+    Ok(rows.into_iter().map(|row| SearchHit::from(row.0)).collect())
+}
+
+/// Search for fixture content, which has no full-text index: a plain
+/// case-insensitive match of the whole query against titles, tags and text,
+/// in the order the content comes in.
+#[cfg(feature = "server")]
+mod local {
+    use std::ops::Range;
+
+    use super::{SearchHit, SearchKind, SnippetPart, MAX_SEARCH_RESULTS};
+    use crate::api::blog::BlogPost;
+    use crate::api::projects::Project;
+    use crate::content::markdown::plain_text;
+
+    /// Characters of text kept on each side of the match.
+    const SNIPPET_CONTEXT: usize = 80;
+
+    /// Where `query` first appears in `text`. Only ASCII letters are folded,
+    /// which keeps the byte offsets the same in both.
+    fn find(text: &str, query: &str) -> Option<Range<usize>> {
+        let start = text.to_ascii_lowercase().find(&query.to_ascii_lowercase())?;
+        Some(start..start + query.len())
+    }
+
+    /// The first `chars` characters of `text`, without a word cut in half.
+    fn head(text: &str, chars: usize) -> &str {
+        match text.char_indices().nth(chars) {
+            Some((end, _)) => text[..end].rsplit_once(' ').map_or("", |(kept, _)| kept),
+            None => text,
+        }
+    }
+
+    /// The last `chars` characters of `text`, without a word cut in half.
+    fn tail(text: &str, chars: usize) -> &str {
+        match text.char_indices().rev().nth(chars) {
+            Some((start, _)) => text[start..].split_once(' ').map_or("", |(_, kept)| kept),
+            None => text,
+        }
+    }
+
+    /// Up to `SNIPPET_CONTEXT` characters either side of `found`, with the
+    /// match highlighted. Without a match, the start of the text.
+    fn snippet(text: &str, found: Option<Range<usize>>) -> Vec<SnippetPart> {
+        let Some(found) = found else {
+            return vec![SnippetPart { text: head(text, SNIPPET_CONTEXT * 2).to_string(), highlight: false }];
+        };
+        [
+            (tail(&text[..found.start], SNIPPET_CONTEXT), false),
+            (&text[found.clone()], true),
+            (head(&text[found.end..], SNIPPET_CONTEXT), false),
+        ]
+        .into_iter()
+        .filter(|(text, _)| !text.is_empty())
+        .map(|(text, highlight)| SnippetPart { text: text.to_string(), highlight })
+        .collect()
+    }
+
+    pub fn search(query: &str, posts: &[BlogPost], projects: &[Project]) -> Vec<SearchHit> {
+        let mut hits: Vec<SearchHit> = Vec::new();
+
+        for post in posts {
+            let text = plain_text(&format!("{}\n\n{}", post.excerpt, post.content));
+            let found = find(&text, query);
+            let in_title = find(&post.title, query).is_some() || post.tags.iter().any(|tag| find(tag, query).is_some());
+            if found.is_some() || in_title {
+                hits.push(SearchHit {
+                    kind: SearchKind::Post,
+                    id: post.id as i64,
+                    title: post.title.clone(),
+                    slug: Some(post.slug.clone()),
+                    snippet: snippet(&text, found),
+                });
+            }
+        }
+        for project in projects {
+            let found = find(&project.description, query);
+            let in_title = find(&project.title, query).is_some()
+                || project.technologies.iter().any(|technology| find(technology, query).is_some());
+            if found.is_some() || in_title {
+                hits.push(SearchHit {
+                    kind: SearchKind::Project,
+                    id: project.id,
+                    title: project.title.clone(),
+                    slug: None,
+                    snippet: snippet(&project.description, found),
+                });
+            }
+        }
+
+        hits.truncate(MAX_SEARCH_RESULTS as usize);
+        hits
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::blog::BlogPost;
+
+    fn post(id: i32, title: &str, content: &str) -> BlogPost {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": title,
+            "slug": format!("post-{}", id),
+            "content": content,
+            "excerpt": "",
+            "published_at": "2024-06-01T08:00:00+00:00",
+            "created_at": "2024-06-01T08:00:00+00:00",
+            "tags": ["Rust"],
+            "author": "Dylan Rayburn",
+        }))
+        .unwrap()
+    }
+
+    fn plain(parts: &[SnippetPart]) -> Vec<(&str, bool)> {
+        parts.iter().map(|part| (part.text.as_str(), part.highlight)).collect()
+    }
+
+    #[test]
+    fn fixture_search_snippets_show_text_not_markdown() {
+        let posts = [
+            post(1, "Budgets", "## Why\n\nA **Budget** keeps [spending](https://example.com) in check.\n\n```\nbudget = 0\n```"),
+            post(2, "Unrelated", "Nothing to see."),
+            post(3, "Rust for budgeting", "Ownership, borrowing and lifetimes."),
+        ];
+
+        let hits = local::search("budget", &posts, &[]);
+        let titles: Vec<&str> = hits.iter().map(|hit| hit.title.as_str()).collect();
+        assert_eq!(titles, ["Budgets", "Rust for budgeting"]);
+        assert_eq!(plain(&hits[0].snippet), [("Why A ", false), ("Budget", true), (" keeps spending in check.", false)]);
+        // Found in the title only, so the snippet is the opening text.
+        assert_eq!(plain(&hits[1].snippet), [("Ownership, borrowing and lifetimes.", false)]);
+    }
+}
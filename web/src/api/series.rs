@@ -0,0 +1,148 @@
+//! Multi-part series: named, ordered runs of posts (see
+//! `migrations/012_blog_series.sql`).
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::blog::{null_as_default, BlogPostSummary};
+
+/// Order of the posts in a series, as a PostgREST `order`.
+#[cfg(feature = "server")]
+pub(crate) const SERIES_ORDER: &str = "series_part.asc.nullslast,published_at.asc,id.asc";
+
+/// A series as stored. `id` is `None` until the dashboard first saves it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlogSeries {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    pub title: String,
+    pub slug: String,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub description: String,
+}
+
+impl BlogSeries {
+    /// Problems that would stop the series from saving, in form order.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.title.trim().is_empty() {
+            return Err("Series title is required".to_string());
+        }
+        if self.slug.is_empty() {
+            return Err("Series slug is required".to_string());
+        }
+        if !self.slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+            return Err("Series slug may only contain lowercase letters, digits and dashes".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The series a post belongs to, embedded with the post for badges.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SeriesRef {
+    pub slug: String,
+    pub title: String,
+}
+
+/// A post in the series navigator.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SeriesPart {
+    pub slug: String,
+    pub title: String,
+}
+
+/// The series a post is part of, with every public part in reading order.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PostSeries {
+    pub series: BlogSeries,
+    pub parts: Vec<SeriesPart>,
+}
+
+impl PostSeries {
+    /// 1-based position of the post at `slug`.
+    pub fn part_number(&self, slug: &str) -> Option<usize> {
+        self.parts.iter().position(|part| part.slug == slug).map(|index| index + 1)
+    }
+}
+
+/// A series page.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SeriesArchive {
+    pub series: BlogSeries,
+    /// Reading order, first part first.
+    pub posts: Vec<BlogPostSummary>,
+}
+
+/// The series at `slug` and its public posts.
+#[server(name = GetSeriesArchive)]
+pub async fn get_series_archive(slug: String) -> Result<SeriesArchive, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        fetch_series_archive(&slug).await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Reading order for posts already in memory, the same as `SERIES_ORDER`.
+#[cfg(feature = "server")]
+pub(crate) fn sort_series_posts<T>(posts: &mut [T], key: impl Fn(&T) -> (Option<i32>, &str, i32)) {
+    posts.sort_by(|a, b| {
+        let (a_part, a_published, a_id) = key(a);
+        let (b_part, b_published, b_id) = key(b);
+        (a_part.unwrap_or(i32::MAX), a_published, a_id).cmp(&(b_part.unwrap_or(i32::MAX), b_published, b_id))
+    });
+}
+
+/// The public parts of the series with id `series_id`, in reading order.
+#[cfg(feature = "server")]
+pub(crate) async fn fetch_post_series(series_id: i32) -> Result<PostSeries, ServerFnError> {
+    use crate::api::auth::create_server_client;
+    use crate::api::blog::only_public;
+    use crate::api::blog_admin::run;
+
+    let client = create_server_client();
+    let series = client.table("blog_series").select("id,title,slug,description").eq("id", series_id.to_string()).single();
+    let parts = only_public(client.table("blog_posts").select("slug,title"))
+        .eq("series_id", series_id.to_string())
+        .order(SERIES_ORDER);
+    let (series, parts) = tokio::try_join!(run(series, "fetch series"), run(parts, "fetch series parts"))?;
+    Ok(PostSeries { series, parts })
+}
+
+#[cfg(feature = "server")]
+async fn fetch_series_archive(slug: &str) -> Result<SeriesArchive, ServerFnError> {
+    use crate::api::auth::create_server_client;
+    use crate::api::blog::{fill_reading_stats, only_public, SUMMARY_COLUMNS};
+    use crate::api::blog_admin::run;
+    use tracing::info;
+
+    let not_found = || ServerFnError::new(format!("No series '{}'", slug));
+
+    let source = crate::api::source::current();
+    if let (Some(series), Some(posts)) = (source.series().await, source.blog_posts(true).await) {
+        let series = series?.into_iter().find(|series| series.slug == slug).ok_or_else(not_found)?;
+        let mut posts: Vec<BlogPostSummary> = posts?
+            .iter()
+            .filter(|post| post.series.as_ref().is_some_and(|series| series.slug == slug))
+            .map(BlogPostSummary::from)
+            .collect();
+        sort_series_posts(&mut posts, |post| (post.series_part, post.published_at.as_str(), post.id));
+        return Ok(SeriesArchive { series, posts });
+    }
+
+    info!("Fetching series '{}' from Supabase...", slug);
+    let client = create_server_client();
+    let series: Vec<BlogSeries> =
+        run(client.table("blog_series").select("id,title,slug,description").eq("slug", slug), "fetch series").await?;
+    let series = series.into_iter().next().ok_or_else(not_found)?;
+
+    let query = only_public(client.table("blog_posts").select(SUMMARY_COLUMNS))
+        .eq("series_id", series.id.unwrap_or_default().to_string())
+        .order(SERIES_ORDER);
+    let mut posts: Vec<BlogPostSummary> = run(query, "fetch series posts").await?;
+    fill_reading_stats(&mut posts).await?;
+    Ok(SeriesArchive { series, posts })
+}
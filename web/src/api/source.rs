@@ -0,0 +1,180 @@
+//! Where server-side reads of posts and projects come from.
+//!
+//! The site normally talks to Supabase. The static export can point the same
+//! server functions at a local Postgres (`DATABASE_URL`) or a JSON fixture
+//! file instead, so a copy of the site can be built offline.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use dioxus::prelude::ServerFnError;
+use serde::Deserialize;
+
+use crate::api::blog::{is_public, BlogPost, SupabaseBlogPost};
+use crate::api::projects::Project;
+use crate::api::series::BlogSeries;
+
+static SOURCE: OnceLock<ContentSource> = OnceLock::new();
+
+/// Posts and projects as they would come back from the database, e.g.
+/// `{ "blog_posts": [...], "projects": [...] }`. Posts use the column names of
+/// `blog_posts`, including `is_published`; a post's series is embedded as
+/// `"series": { "slug": ..., "title": ... }`, as Supabase returns it.
+#[derive(Deserialize, Debug, Default)]
+pub struct Fixture {
+    #[serde(default)]
+    pub blog_posts: Vec<SupabaseBlogPost>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub blog_series: Vec<BlogSeries>,
+}
+
+#[derive(Debug, Default)]
+pub enum ContentSource {
+    #[default]
+    Supabase,
+    Postgres,
+    Fixture(Fixture),
+}
+
+impl ContentSource {
+    pub fn from_fixture_file(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read fixture {}: {}", path.display(), e))?;
+        let fixture = serde_json::from_str::<Fixture>(&text)
+            .map_err(|e| format!("Failed to parse fixture {}: {}", path.display(), e))?;
+        Ok(ContentSource::Fixture(fixture))
+    }
+
+    /// Blog posts, newest first. `public_only` leaves out drafts and
+    /// scheduled posts. `None` means "ask Supabase".
+    pub async fn blog_posts(&self, public_only: bool) -> Option<Result<Vec<BlogPost>, ServerFnError>> {
+        let posts = match self {
+            ContentSource::Supabase => return None,
+            ContentSource::Postgres => postgres_blog_posts().await,
+            ContentSource::Fixture(fixture) => Ok(fixture.blog_posts.clone()),
+        };
+
+        let now = chrono::Utc::now();
+        Some(posts.map(|posts| {
+            let mut posts: Vec<SupabaseBlogPost> = posts
+                .into_iter()
+                .filter(|post| !public_only || is_public(post.is_published.unwrap_or(false), &post.published_at, now))
+                .collect();
+            posts.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            posts.into_iter().map(BlogPost::from).collect()
+        }))
+    }
+
+    /// A single public post with its body rendered, as `get_blog_with_slug`
+    /// returns it.
+    pub async fn blog_post(&self, slug: &str) -> Option<Result<BlogPost, ServerFnError>> {
+        let posts = match self.blog_posts(true).await? {
+            Ok(posts) => posts,
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(
+            posts
+                .into_iter()
+                .find(|post| post.slug == slug)
+                .map(|mut post| {
+                    post.content_html = crate::content::rendered_post_html(post.id, &post.content);
+                    post
+                })
+                .ok_or_else(|| ServerFnError::new(format!("Blog post with slug '{}' not found", slug))),
+        )
+    }
+
+    pub async fn series(&self) -> Option<Result<Vec<BlogSeries>, ServerFnError>> {
+        match self {
+            ContentSource::Supabase => None,
+            ContentSource::Postgres => Some(postgres_series().await),
+            ContentSource::Fixture(fixture) => Some(Ok(fixture.blog_series.clone())),
+        }
+    }
+
+    pub async fn projects(&self) -> Option<Result<Vec<Project>, ServerFnError>> {
+        match self {
+            ContentSource::Supabase => None,
+            ContentSource::Postgres => Some(postgres_projects().await),
+            ContentSource::Fixture(fixture) => {
+                let mut projects = fixture.projects.clone();
+                projects.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                Some(Ok(projects))
+            }
+        }
+    }
+}
+
+/// Switch the content source for the rest of the process. Only the first
+/// call has any effect.
+pub fn set(source: ContentSource) {
+    let _ = SOURCE.set(source);
+}
+
+pub fn current() -> &'static ContentSource {
+    SOURCE.get_or_init(ContentSource::default)
+}
+
+/// Rows are fetched as JSON so the same types parse Postgres and PostgREST
+/// output, whichever of the `tags` column types the database was created with.
+async fn postgres_blog_posts() -> Result<Vec<SupabaseBlogPost>, ServerFnError> {
+    let pool = crate::api::database::connection()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let rows: Vec<sqlx::types::Json<SupabaseBlogPost>> = sqlx::query_scalar(
+        r#"
+        SELECT to_jsonb(p)
+            || jsonb_build_object(
+                'excerpt', COALESCE(p.excerpt, ''),
+                'series', (SELECT jsonb_build_object('slug', s.slug, 'title', s.title) FROM blog_series s WHERE s.id = p.series_id)
+            )
+        FROM blog_posts p
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ServerFnError::new(format!("Failed to fetch blog posts: {}", e)))?;
+
+    Ok(rows.into_iter().map(|row| row.0).collect())
+}
+
+async fn postgres_series() -> Result<Vec<BlogSeries>, ServerFnError> {
+    let pool = crate::api::database::connection()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let rows: Vec<sqlx::types::Json<BlogSeries>> =
+        sqlx::query_scalar("SELECT to_jsonb(s) FROM blog_series s ORDER BY s.title")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to fetch blog series: {}", e)))?;
+
+    Ok(rows.into_iter().map(|row| row.0).collect())
+}
+
+async fn postgres_projects() -> Result<Vec<Project>, ServerFnError> {
+    let pool = crate::api::database::connection()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let rows: Vec<sqlx::types::Json<Project>> = sqlx::query_scalar(
+        r#"
+        SELECT to_jsonb(p)
+            || jsonb_build_object(
+                'technologies', COALESCE(to_jsonb(p.technologies), '[]'::jsonb),
+                'featured', COALESCE(p.featured, false)
+            )
+        FROM projects p
+        ORDER BY p.created_at DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ServerFnError::new(format!("Failed to fetch projects: {}", e)))?;
+
+    Ok(rows.into_iter().map(|row| row.0).collect())
+}
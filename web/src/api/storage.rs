@@ -0,0 +1,86 @@
+//! Where uploaded files are kept.
+//!
+//! Uploads normally go to Supabase Storage, into the public buckets created
+//! by `migrations/001_initial.sql`. Setting `IMAGE_STORAGE_DIR` keeps them on
+//! the local filesystem instead, served by the app under `/uploads`, which
+//! suits development and self-hosting.
+
+use std::path::PathBuf;
+
+use dioxus::prelude::ServerFnError;
+
+use crate::api::env;
+
+/// URL prefix the local backend's files are served under.
+pub const LOCAL_UPLOADS_PATH: &str = "/uploads";
+
+pub enum Storage {
+    /// Supabase Storage, writing with the service role key. The buckets take
+    /// no uploads from signed-in users, so callers check the author first.
+    Supabase { service_key: String },
+    Local { root: PathBuf },
+}
+
+impl Storage {
+    /// The configured backend.
+    pub fn configured() -> Result<Self, ServerFnError> {
+        if let Some(root) = local_root() {
+            return Ok(Storage::Local { root });
+        }
+        env::get_env_config()
+            .supabase_service_role_key
+            .clone()
+            .filter(|key| !key.is_empty())
+            .map(|service_key| Storage::Supabase { service_key })
+            .ok_or_else(|| ServerFnError::new("Uploads need SUPABASE_SERVICE_ROLE_KEY".to_string()))
+    }
+
+    /// Store `bytes` at `path` inside `bucket`, replacing any file already
+    /// there, and return its public URL.
+    pub async fn put(&self, bucket: &str, path: &str, bytes: Vec<u8>, content_type: &str) -> Result<String, ServerFnError> {
+        match self {
+            Storage::Supabase { service_key } => {
+                let config = env::get_env_config();
+                let response = reqwest::Client::new()
+                    .post(format!("{}/storage/v1/object/{}/{}", config.supabase_url, bucket, path))
+                    .header("apikey", &config.supabase_anon_key)
+                    .header("x-upsert", "true")
+                    .header(reqwest::header::CONTENT_TYPE, content_type)
+                    .header(reqwest::header::CACHE_CONTROL, "max-age=31536000")
+                    .bearer_auth(service_key)
+                    .body(bytes)
+                    .send()
+                    .await
+                    .map_err(|e| ServerFnError::new(format!("Upload failed: {}", e)))?;
+
+                let status = response.status();
+                if !status.is_success() {
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(ServerFnError::new(format!("Upload of {} failed. Status: {}, Response: {}", path, status, text)));
+                }
+                Ok(format!("{}/storage/v1/object/public/{}/{}", config.supabase_url, bucket, path))
+            }
+            Storage::Local { root } => {
+                let file = root.join(bucket).join(path);
+                if let Some(dir) = file.parent() {
+                    tokio::fs::create_dir_all(dir)
+                        .await
+                        .map_err(|e| ServerFnError::new(format!("Failed to create {}: {}", dir.display(), e)))?;
+                }
+                tokio::fs::write(&file, bytes)
+                    .await
+                    .map_err(|e| ServerFnError::new(format!("Failed to write {}: {}", file.display(), e)))?;
+                Ok(format!("{}/{}/{}", LOCAL_UPLOADS_PATH, bucket, path))
+            }
+        }
+    }
+}
+
+/// The local upload directory, if the filesystem backend is configured.
+pub fn local_root() -> Option<PathBuf> {
+    env::get_env_config()
+        .image_storage_dir
+        .as_deref()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
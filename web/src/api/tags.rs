@@ -0,0 +1,185 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::api::blog::BlogPostSummary;
+
+/// URL form of a tag: lowercase ASCII letters and digits, with every other
+/// run of characters collapsed to a single `-`. "3D Printing", "3d-printing"
+/// and "3D printing!" all become `3d-printing`.
+///
+/// Must match `tag_slug` in `migrations/005_blog_tags.sql`.
+pub fn tag_slug(tag: &str) -> String {
+    let mut slug = String::with_capacity(tag.len());
+    for c in tag.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(slug.trim_end_matches('-').len());
+    slug
+}
+
+/// A tag in the tag cloud.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TagCount {
+    /// Display name, the most common spelling among the posts.
+    pub name: String,
+    pub slug: String,
+    /// Published posts carrying the tag.
+    pub count: u32,
+}
+
+/// A tag's archive page.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TagArchive {
+    pub name: String,
+    pub slug: String,
+    /// Newest first.
+    pub posts: Vec<BlogPostSummary>,
+}
+
+/// Every tag used by a published post, most used first.
+#[server(name = GetTags)]
+pub async fn get_tags() -> Result<Vec<TagCount>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        fetch_tags().await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Published posts carrying `tag`, which may be a slug or any spelling of
+/// the tag.
+#[server(name = GetTagArchive)]
+pub async fn get_tag_archive(tag: String) -> Result<TagArchive, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        fetch_tag_archive(&tag_slug(&tag)).await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
+
+/// Merge spelling variants under one slug, keeping the most common spelling
+/// (ties go to the alphabetically first), as `blog_tag_counts()` does.
+#[cfg(feature = "server")]
+fn count_tags<'a>(tag_lists: impl Iterator<Item = &'a [String]>) -> Vec<TagCount> {
+    use std::collections::BTreeMap;
+
+    // slug -> (posts, spelling -> uses)
+    let mut tags: BTreeMap<String, (u32, BTreeMap<&str, u32>)> = BTreeMap::new();
+    for tag_list in tag_lists {
+        let mut seen = Vec::new();
+        for tag in tag_list {
+            let slug = tag_slug(tag);
+            if slug.is_empty() {
+                continue;
+            }
+            let entry = tags.entry(slug.clone()).or_default();
+            *entry.1.entry(tag.as_str()).or_default() += 1;
+            if !seen.contains(&slug) {
+                entry.0 += 1;
+                seen.push(slug);
+            }
+        }
+    }
+
+    let mut counts: Vec<TagCount> = tags
+        .into_iter()
+        .map(|(slug, (count, spellings))| {
+            let name = spellings
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+                .map(|(name, _)| name.to_string())
+                .unwrap_or_else(|| slug.clone());
+            TagCount { name, slug, count }
+        })
+        .collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.slug.cmp(&b.slug)));
+    counts
+}
+
+#[cfg(feature = "server")]
+async fn fetch_tags() -> Result<Vec<TagCount>, ServerFnError> {
+    use crate::api::auth::create_server_client;
+    use tracing::info;
+
+    if let Some(posts) = crate::api::source::current().blog_posts(true).await {
+        return posts.map(|posts| count_tags(posts.iter().map(|post| post.tags.as_slice())));
+    }
+
+    info!("Fetching blog tags from Supabase...");
+    let response = create_server_client()
+        .rpc("blog_tag_counts", "{}")
+        .execute()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+    if !status.is_success() {
+        let error_msg = format!("Failed to fetch blog tags. Status: {}, Response: {}", status, text);
+        info!("{}", error_msg);
+        return Err(ServerFnError::new(error_msg));
+    }
+
+    serde_json::from_str(&text).map_err(|e| ServerFnError::new(format!("Failed to parse blog tags JSON: {}", e)))
+}
+
+/// Display name for `slug` among the tags of `posts`.
+#[cfg(feature = "server")]
+fn tag_name(posts: &[BlogPostSummary], slug: &str) -> Option<String> {
+    count_tags(posts.iter().map(|post| post.tags.as_slice()))
+        .into_iter()
+        .find(|tag| tag.slug == slug)
+        .map(|tag| tag.name)
+}
+
+#[cfg(feature = "server")]
+async fn fetch_tag_archive(slug: &str) -> Result<TagArchive, ServerFnError> {
+    use crate::api::auth::create_server_client;
+    use crate::api::blog::{fill_reading_stats, SUMMARY_COLUMNS};
+    use tracing::info;
+
+    let not_found = || ServerFnError::new(format!("No posts tagged '{}'", slug));
+
+    if let Some(posts) = crate::api::source::current().blog_posts(true).await {
+        let posts: Vec<BlogPostSummary> = posts?
+            .iter()
+            .filter(|post| post.tags.iter().any(|tag| tag_slug(tag) == slug))
+            .map(BlogPostSummary::from)
+            .collect();
+        let name = tag_name(&posts, slug).ok_or_else(not_found)?;
+        return Ok(TagArchive { name, slug: slug.to_string(), posts });
+    }
+
+    info!("Fetching posts tagged '{}' from Supabase...", slug);
+    let response = create_server_client()
+        .rpc("blog_posts_by_tag", serde_json::json!({ "tag": slug }).to_string())
+        .select(SUMMARY_COLUMNS)
+        .order("published_at.desc,id.desc")
+        .execute()
+        .await
+        .map_err(|e| ServerFnError::new(format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    let text = response.text().await.map_err(|e| ServerFnError::new(e.to_string()))?;
+    if !status.is_success() {
+        let error_msg = format!("Failed to fetch tag archive. Status: {}, Response: {}", status, text);
+        info!("{}", error_msg);
+        return Err(ServerFnError::new(error_msg));
+    }
+
+    let mut posts: Vec<BlogPostSummary> = serde_json::from_str(&text)
+        .map_err(|e| ServerFnError::new(format!("Failed to parse blog summaries JSON: {}", e)))?;
+    fill_reading_stats(&mut posts).await?;
+    let name = tag_name(&posts, slug).ok_or_else(not_found)?;
+    Ok(TagArchive { name, slug: slug.to_string(), posts })
+}
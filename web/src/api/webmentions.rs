@@ -0,0 +1,78 @@
+//! Webmentions shown under a post. Receiving and sending them happens in
+//! `server::webmention`.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What the source page did with the post, from its microformats.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MentionKind {
+    /// Linked to it without saying how.
+    #[default]
+    Mention,
+    Reply,
+    Like,
+    Repost,
+}
+
+/// A verified mention of a post.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Webmention {
+    pub id: i32,
+    pub kind: MentionKind,
+    /// The page that mentions the post.
+    pub source: String,
+    pub author_name: Option<String>,
+    pub author_url: Option<String>,
+    pub author_photo: Option<String>,
+    /// Plain text, shortened.
+    pub content: Option<String>,
+    /// The mention's own permalink, when the source names one.
+    pub url: Option<String>,
+    pub published_at: Option<String>,
+}
+
+impl Webmention {
+    /// Where a link to the mention should go.
+    pub fn link(&self) -> &str {
+        self.url.as_deref().unwrap_or(&self.source)
+    }
+
+    /// Who to credit: the author's name, or else the source's host.
+    pub fn author(&self) -> String {
+        if let Some(name) = self.author_name.as_deref().filter(|name| !name.is_empty()) {
+            return name.to_string();
+        }
+        let host = self.source.split("://").nth(1).unwrap_or(&self.source);
+        host.split('/').next().unwrap_or(host).to_string()
+    }
+}
+
+/// Verified mentions of post `post_id`, oldest first.
+#[server(name = GetWebmentions)]
+pub async fn get_webmentions(post_id: i32) -> Result<Vec<Webmention>, ServerFnError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use crate::api::auth::create_server_client;
+        use crate::api::blog_admin::run;
+        use crate::api::source::{self, ContentSource};
+
+        // Static copies of the site can't receive mentions.
+        if !matches!(source::current(), ContentSource::Supabase) {
+            return Ok(Vec::new());
+        }
+
+        let query = create_server_client()
+            .table("webmentions")
+            .select("id,kind,source,author_name,author_url,author_photo,content,url,published_at")
+            .eq("post_id", post_id.to_string())
+            .eq("status", "verified")
+            .order("created_at.asc,id.asc");
+        run(query, "fetch webmentions").await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err(ServerFnError::new("Server function called on client side".to_string()))
+    }
+}
@@ -0,0 +1,44 @@
+use dioxus::prelude::*;
+
+use crate::api::images::ImageVariants;
+
+/// An image that uses its uploaded variants when it has them: a `picture`
+/// with one `srcset` per format, and the blur placeholder behind it while it
+/// loads. Images without variants (or whose variants belong to an older URL)
+/// render as a plain `img`.
+///
+/// `sizes` describes the rendered width the same way the HTML attribute does,
+/// e.g. `"(min-width: 1024px) 33vw, 100vw"`.
+#[component]
+pub fn ResponsiveImage(
+    src: String,
+    variants: Option<ImageVariants>,
+    alt: String,
+    #[props(default)] class: String,
+    #[props(default = "100vw".to_string())] sizes: String,
+) -> Element {
+    let Some(variants) = ImageVariants::for_src(variants.as_ref(), &src) else {
+        return rsx! {
+            img { class: "{class}", src: "{src}", alt: "{alt}", loading: "lazy", decoding: "async" }
+        };
+    };
+
+    rsx! {
+        picture {
+            for source in variants.sources.iter() {
+                // dioxus-html has no `srcset`/`sizes` on `source`.
+                source { r#type: "{source.mime_type}", "srcset": "{source.srcset()}", "sizes": "{sizes}" }
+            }
+            img {
+                class: "{class}",
+                src: "{variants.src}",
+                alt: "{alt}",
+                width: "{variants.width}",
+                height: "{variants.height}",
+                loading: "lazy",
+                decoding: "async",
+                style: "background-image: url('{variants.placeholder}'); background-size: cover; background-position: center;",
+            }
+        }
+    }
+}
@@ -19,4 +19,13 @@ pub mod input;
 pub use input::{TextInput, PasswordInput, NumberInput, DateInput, SelectInput};
 
 pub mod modal;
-pub use modal::Modal;
\ No newline at end of file
+pub use modal::Modal;
+
+pub mod seo;
+pub use seo::Seo;
+
+pub mod image;
+pub use image::ResponsiveImage;
+
+pub mod scroll;
+pub use scroll::{scroll_to, use_scroll_spy, ScrollSpy, ScrollTarget};
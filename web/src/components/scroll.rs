@@ -0,0 +1,117 @@
+//! Reading progress, the current section, and smooth scrolling for long pages.
+//!
+//! Everything here runs in the browser through `document::eval`, so the same
+//! code works in every build; during server rendering nothing scrolls and
+//! the spy reports the top of the page.
+
+use dioxus::prelude::*;
+
+/// Height kept clear above a scroll target, for the sticky navbar.
+const SCROLL_OFFSET_PX: u32 = 96;
+
+/// Watches the element whose ID it is sent and reports `[progress, section]`
+/// whenever either changes: the share of the element scrolled past (0 to 1)
+/// and the ID of the last `h2`/`h3` in it that has reached the top of the
+/// viewport. Installing a spy removes the previous one, so navigating between
+/// posts doesn't pile up listeners.
+const SCROLL_SPY_SCRIPT: &str = r#"
+const [containerId, offset] = await dioxus.recv();
+let last = "";
+const update = () => {
+    const container = document.getElementById(containerId);
+    if (!container) return;
+    const rect = container.getBoundingClientRect();
+    const scrollable = rect.height - window.innerHeight;
+    const progress = scrollable > 0
+        ? Math.min(Math.max(-rect.top / scrollable, 0), 1)
+        : (rect.bottom <= window.innerHeight ? 1 : 0);
+    let section = null;
+    for (const heading of container.querySelectorAll("h2[id], h3[id]")) {
+        if (heading.getBoundingClientRect().top > offset + 1) break;
+        section = heading.id;
+    }
+    const message = [Math.round(progress * 1000) / 1000, section];
+    const key = JSON.stringify(message);
+    if (key !== last) {
+        last = key;
+        dioxus.send(message);
+    }
+};
+let queued = false;
+const onScroll = () => {
+    if (queued) return;
+    queued = true;
+    requestAnimationFrame(() => { queued = false; update(); });
+};
+if (window.__scrollSpyCleanup) window.__scrollSpyCleanup();
+window.addEventListener("scroll", onScroll, { passive: true });
+window.addEventListener("resize", onScroll);
+window.__scrollSpyCleanup = () => {
+    window.removeEventListener("scroll", onScroll);
+    window.removeEventListener("resize", onScroll);
+};
+update();
+"#;
+
+/// Scrolls to the element whose ID it is sent, or to the top for `null`, and
+/// puts the matching fragment in the address bar so the position can be
+/// shared. Smooth unless the visitor prefers reduced motion.
+const SCROLL_TO_SCRIPT: &str = r##"
+const [id, offset] = await dioxus.recv();
+const target = id === null ? null : document.getElementById(id);
+const top = target ? target.getBoundingClientRect().top + window.scrollY - offset : 0;
+const smooth = !window.matchMedia("(prefers-reduced-motion: reduce)").matches;
+window.scrollTo({ top, behavior: smooth ? "smooth" : "auto" });
+const url = location.pathname + location.search + (target ? "#" + id : "");
+history.replaceState(history.state, "", url);
+"##;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScrollTarget {
+    Top,
+    /// The element with this ID.
+    Element(String),
+}
+
+/// Scroll the window to `target`, leaving room for the navbar.
+pub fn scroll_to(target: ScrollTarget) {
+    let id = match target {
+        ScrollTarget::Top => None,
+        ScrollTarget::Element(id) => Some(id),
+    };
+    let script = document::eval(SCROLL_TO_SCRIPT);
+    let _ = script.send((id, SCROLL_OFFSET_PX));
+}
+
+/// How far the visitor has read through an element, and where they are in
+/// it. See [`use_scroll_spy`].
+#[derive(Clone, Copy, PartialEq)]
+pub struct ScrollSpy {
+    /// 0 at the top of the element, 1 once its end is in view.
+    pub progress: ReadOnlySignal<f64>,
+    /// ID of the section heading currently at the top of the viewport.
+    pub section: ReadOnlySignal<Option<String>>,
+}
+
+/// Track scrolling through the element with ID `container_id`.
+pub fn use_scroll_spy(container_id: &'static str) -> ScrollSpy {
+    let mut progress = use_signal(|| 0.0);
+    let mut section = use_signal::<Option<String>>(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            let mut spy = document::eval(SCROLL_SPY_SCRIPT);
+            if spy.send((container_id, SCROLL_OFFSET_PX)).is_err() {
+                return;
+            }
+            while let Ok((read, current)) = spy.recv::<(f64, Option<String>)>().await {
+                progress.set(read);
+                if *section.peek() != current {
+                    section.set(current);
+                }
+            }
+        });
+    });
+
+    ScrollSpy { progress: progress.into(), section: section.into() }
+}
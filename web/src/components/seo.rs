@@ -0,0 +1,100 @@
+use dioxus::prelude::*;
+use serde_json::{json, Value};
+
+use crate::api::env::absolute_url;
+
+pub const SITE_NAME: &str = "Dylan Rayburn";
+
+/// Preview image for pages that don't have their own.
+const DEFAULT_IMAGE: Asset = asset!("/assets/Headshot_Rayburn.png");
+
+/// `document::Meta` and friends append to `<head>` on mount and never remove
+/// anything, so after client-side navigation the previous page's tags are
+/// still there. Keep only the newest tag for each key.
+const DEDUPE_HEAD_SCRIPT: &str = r#"
+const seen = new Set();
+const tags = document.head.querySelectorAll(
+    'meta[name], meta[property], link[rel="canonical"]'
+);
+for (const tag of Array.from(tags).reverse()) {
+    const key = tag.tagName + ":" + (tag.getAttribute("name") || tag.getAttribute("property") || tag.getAttribute("rel"));
+    if (seen.has(key)) {
+        tag.remove();
+    } else {
+        seen.add(key);
+    }
+}
+"#;
+
+/// Head tags for a page: document title, meta description, canonical URL,
+/// OpenGraph and Twitter cards, and a JSON-LD block.
+///
+/// Head elements can't be updated after the first render, so views whose
+/// metadata depends on fetched data should only render this once the data is
+/// there.
+#[component]
+pub fn Seo(
+    title: String,
+    description: String,
+    /// Path of the page, e.g. `Route::About {}.to_string()`.
+    path: String,
+    /// Preview image, absolute or relative to the site root. Falls back to
+    /// the headshot.
+    #[props(default)]
+    image: Option<String>,
+    /// OpenGraph type, `website` unless the page is an `article`.
+    #[props(default = "website".to_string())]
+    og_type: String,
+    /// Structured data for the page. Defaults to a plain `WebPage`.
+    #[props(default)]
+    json_ld: Option<Value>,
+) -> Element {
+    let full_title = if title == SITE_NAME {
+        title.clone()
+    } else {
+        format!("{} | {}", title, SITE_NAME)
+    };
+    let url = absolute_url(&path);
+    let image = image.filter(|image| !image.is_empty());
+    let twitter_card = if image.is_some() { "summary_large_image" } else { "summary" };
+    let image = absolute_url(&image.unwrap_or_else(|| DEFAULT_IMAGE.to_string()));
+
+    let json_ld = json_ld.unwrap_or_else(|| {
+        json!({
+            "@context": "https://schema.org",
+            "@type": "WebPage",
+            "name": full_title,
+            "description": description,
+            "url": url,
+        })
+    });
+    // `</script>` inside a string would end the block early.
+    let json_ld = json_ld.to_string().replace("</", "<\\/");
+
+    use_effect(|| {
+        document::eval(DEDUPE_HEAD_SCRIPT);
+    });
+
+    rsx! {
+        document::Title { "{full_title}" }
+        document::Meta { name: "description", content: description.clone() }
+        document::Link { rel: "canonical", href: url.clone() }
+
+        document::Meta { property: "og:site_name", content: SITE_NAME }
+        document::Meta { property: "og:type", content: og_type }
+        document::Meta { property: "og:title", content: title.clone() }
+        document::Meta { property: "og:description", content: description.clone() }
+        document::Meta { property: "og:url", content: url }
+        document::Meta { property: "og:image", content: image.clone() }
+
+        document::Meta { name: "twitter:card", content: twitter_card }
+        document::Meta { name: "twitter:title", content: title }
+        document::Meta { name: "twitter:description", content: description }
+        document::Meta { name: "twitter:image", content: image }
+
+        // In the body rather than `document::Script`: head script text gets
+        // HTML-escaped during SSR, which breaks the JSON. It also goes away
+        // with the page, so it needs no deduplication.
+        script { r#type: "application/ld+json", dangerous_inner_html: json_ld }
+    }
+}
@@ -0,0 +1,42 @@
+//! Per-post cache of rendered post bodies.
+//!
+//! Highlighting is by far the most expensive part of serving a post, so the
+//! HTML is kept in memory keyed by post id and a fingerprint of the Markdown
+//! source. Editing a post changes the fingerprint and the entry is rebuilt on
+//! the next view.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+use super::highlight::render_highlighted_markdown;
+
+lazy_static! {
+    static ref RENDERED: Mutex<HashMap<i32, (u64, String)>> = Mutex::new(HashMap::new());
+}
+
+fn fingerprint(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Rendered, highlighted and sanitized HTML for a post body.
+pub fn rendered_post_html(id: i32, content: &str) -> String {
+    let fingerprint = fingerprint(content);
+    if let Some((cached, html)) = RENDERED.lock().expect("render cache poisoned").get(&id) {
+        if *cached == fingerprint {
+            return html.clone();
+        }
+    }
+
+    let html = render_highlighted_markdown(content);
+    RENDERED
+        .lock()
+        .expect("render cache poisoned")
+        .insert(id, (fingerprint, html.clone()));
+    html
+}
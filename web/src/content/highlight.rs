@@ -0,0 +1,145 @@
+//! Server-side syntax highlighting for fenced code blocks.
+//!
+//! Code is tokenized with `syntect` and emitted as classed spans (`hl-*`), so
+//! colours come from `assets/highlight.css` and follow the site's light/dark
+//! preference instead of being baked into the markup. Each source line is
+//! wrapped in its own `span.line`, which lets the stylesheet draw line numbers
+//! and mark the ranges requested in the fence info, e.g. ```` ```rust {3-5} ````.
+
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+use lazy_static::lazy_static;
+use syntect::html::{line_tokens_to_classed_spans, ClassStyle};
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+use super::markdown::{markdown_to_html_with, sanitize_html};
+
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+lazy_static! {
+    static ref SYNTAXES: SyntaxSet = SyntaxSet::load_defaults_newlines();
+}
+
+/// The parts of a fence info string we understand: the language and the
+/// 1-based line ranges to emphasise.
+#[derive(Debug, Default, PartialEq)]
+pub struct FenceInfo {
+    pub lang: Option<String>,
+    pub highlighted: Vec<RangeInclusive<usize>>,
+}
+
+impl FenceInfo {
+    /// Parse strings such as `rust`, `rust {3-5}` or `rust {1,4-6}`.
+    pub fn parse(info: &str) -> Self {
+        let info = info.trim();
+        let (lang, ranges) = match info.find('{') {
+            Some(start) => (&info[..start], info[start..].trim_matches(|c| c == '{' || c == '}')),
+            None => (info, ""),
+        };
+
+        let highlighted = ranges
+            .split(',')
+            .filter_map(|range| {
+                let range = range.trim();
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                let start = start.trim().parse::<usize>().ok()?;
+                let end = end.trim().parse::<usize>().ok()?;
+                (start >= 1 && start <= end).then_some(start..=end)
+            })
+            .collect();
+
+        let lang = lang.split_whitespace().next().map(str::to_string);
+        FenceInfo { lang, highlighted }
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlighted.iter().any(|range| range.contains(&line))
+    }
+}
+
+fn find_syntax(lang: Option<&str>) -> &'static SyntaxReference {
+    lang.and_then(|lang| {
+        SYNTAXES
+            .find_syntax_by_token(lang)
+            .or_else(|| SYNTAXES.find_syntax_by_name(lang))
+    })
+    .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text())
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn open_scope(html: &mut String, scope: &Scope) {
+    html.push_str("<span class=\"");
+    for (i, atom) in scope.build_string().split('.').enumerate() {
+        if i > 0 {
+            html.push(' ');
+        }
+        html.push_str("hl-");
+        html.push_str(atom);
+    }
+    html.push_str("\">");
+}
+
+/// Highlight one fenced code block. Returns `None` if `syntect` fails, in
+/// which case the caller falls back to a plain `<pre>`.
+pub fn highlight_code_block(info: &str, code: &str) -> Option<String> {
+    let fence = FenceInfo::parse(info);
+    let syntax = find_syntax(fence.lang.as_deref());
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+
+    let lang_label = fence.lang.as_deref().map(escape).unwrap_or_default();
+    let mut html = String::with_capacity(code.len() * 4);
+    html.push_str("<div class=\"code-block\"><div class=\"code-block-header\">");
+    write!(html, "<span class=\"code-block-lang\">{lang_label}</span>").ok()?;
+    html.push_str("<button type=\"button\" class=\"code-copy\" aria-label=\"Copy code\">Copy</button></div>");
+    match fence.lang.as_deref() {
+        Some(_) => write!(html, "<pre><code class=\"language-{lang_label}\">").ok()?,
+        None => html.push_str("<pre><code>"),
+    }
+
+    for (index, line) in LinesWithEndings::from(code).enumerate() {
+        let ops = state.parse_line(line, &SYNTAXES).ok()?;
+
+        let class = if fence.is_highlighted(index + 1) { "line highlighted" } else { "line" };
+        write!(html, "<span class=\"{class}\">").ok()?;
+
+        // Spans never cross a line boundary: scopes still open from the
+        // previous line are reopened here and everything is closed below.
+        let carried = stack.as_slice().to_vec();
+        for scope in &carried {
+            open_scope(&mut html, scope);
+        }
+        let (spans, delta) = line_tokens_to_classed_spans(line, &ops, CLASS_STYLE, &mut stack).ok()?;
+        // The newline is part of the tokenized text; drop it so each line
+        // is exactly one `span.line`.
+        html.push_str(&spans.replace(['\n', '\r'], ""));
+        let open = (carried.len() as isize + delta).max(0) as usize;
+        html.push_str(&"</span>".repeat(open));
+
+        html.push_str("</span>\n");
+    }
+
+    html.push_str("</code></pre></div>");
+    Some(html)
+}
+
+/// Render a post body to sanitized HTML with highlighted code blocks.
+pub fn render_highlighted_markdown(source: &str) -> String {
+    sanitize_html(&markdown_to_html_with(source, highlight_code_block))
+}
@@ -0,0 +1,499 @@
+//! Markdown to sanitized HTML.
+//!
+//! Blog post bodies are stored as Markdown (CommonMark plus the GFM tables,
+//! task lists, footnotes and strikethrough extensions). The HTML produced by
+//! `pulldown-cmark` is passed through an allowlist sanitizer before it is
+//! handed to `dangerous_inner_html`, so this is the only place raw post HTML
+//! is ever produced.
+//!
+//! Headings get IDs derived from their text, plus a link to themselves.
+//! [`outline`] derives the same IDs from the Markdown alone, for the table of
+//! contents.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use ammonia::{Builder, UrlRelative};
+use lazy_static::lazy_static;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+
+/// Tags that survive sanitization. Anything else is unwrapped to its text.
+const ALLOWED_TAGS: &[&str] = &[
+    "a", "blockquote", "br", "button", "code", "del", "div", "em", "h1", "h2", "h3", "h4",
+    "h5", "h6", "hr", "img", "input", "li", "ol", "p", "pre", "section", "span", "strong",
+    "sup", "table", "tbody", "td", "th", "thead", "tr", "ul",
+];
+
+/// Classes emitted by `pulldown-cmark` for footnotes and by the code block
+/// renderer. Syntax token classes (`hl-*`) are checked separately.
+const ALLOWED_CLASSES: &[&str] = &[
+    "footnote-definition",
+    "footnote-definition-label",
+    "footnote-reference",
+    "code-block",
+    "code-block-header",
+    "code-block-lang",
+    "code-copy",
+    "line",
+    "highlighted",
+    "heading-anchor",
+];
+
+/// Tags a reader's comment may keep: paragraphs, emphasis, code, quotes,
+/// lists and links. See [`render_comment`].
+const COMMENT_TAGS: &[&str] = &[
+    "a", "blockquote", "br", "code", "del", "em", "li", "ol", "p", "pre", "strong", "ul",
+];
+
+/// Heading levels listed by [`outline`]. The post title is the page's `h1`.
+const OUTLINE_LEVELS: std::ops::RangeInclusive<u8> = 2..=3;
+
+/// A heading in a post's table of contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heading {
+    pub level: u8,
+    /// The heading's `id` in the rendered HTML.
+    pub id: String,
+    pub text: String,
+}
+
+lazy_static! {
+    static ref SANITIZER: Builder<'static> = sanitizer();
+    static ref COMMENT_SANITIZER: Builder<'static> = comment_sanitizer();
+}
+
+pub(super) fn options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+}
+
+fn sanitizer() -> Builder<'static> {
+    let mut builder = Builder::empty();
+    builder
+        .add_tags(ALLOWED_TAGS)
+        .clean_content_tags(HashSet::from(["script", "style"]))
+        .add_tag_attributes("a", &["href", "title", "class", "aria-label"])
+        .add_tag_attributes("h1", &["id"])
+        .add_tag_attributes("h2", &["id"])
+        .add_tag_attributes("h3", &["id"])
+        .add_tag_attributes("h4", &["id"])
+        .add_tag_attributes("h5", &["id"])
+        .add_tag_attributes("h6", &["id"])
+        .add_tag_attributes("img", &["src", "alt", "title"])
+        .add_tag_attributes("code", &["class"])
+        .add_tag_attributes("div", &["class", "id"])
+        .add_tag_attributes("sup", &["class"])
+        .add_tag_attributes("span", &["class"])
+        .add_tag_attributes("button", &["type", "class", "aria-label"])
+        .add_tag_attributes("th", &["style"])
+        .add_tag_attributes("td", &["style"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .url_relative(UrlRelative::PassThrough)
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .attribute_filter(filter_attribute);
+    builder
+}
+
+fn comment_sanitizer() -> Builder<'static> {
+    let mut builder = Builder::empty();
+    builder
+        .add_tags(COMMENT_TAGS)
+        .clean_content_tags(HashSet::from(["script", "style"]))
+        .add_tag_attributes("a", &["href"])
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .url_relative(UrlRelative::Deny)
+        .link_rel(Some("noopener noreferrer nofollow ugc"));
+    builder
+}
+
+/// Narrows the attributes allowed above down to the values `pulldown-cmark`
+/// actually emits.
+fn filter_attribute<'u>(element: &str, attribute: &str, value: &'u str) -> Option<Cow<'u, str>> {
+    let allowed = match (element, attribute) {
+        ("code", "class") => value
+            .strip_prefix("language-")
+            .is_some_and(|lang| lang.chars().all(|c| c.is_ascii_alphanumeric() || "+-_#.".contains(c))),
+        (_, "class") => value
+            .split_whitespace()
+            .all(|class| is_allowed_class(element, class)),
+        ("th" | "td", "style") => matches!(
+            value,
+            "text-align: left" | "text-align: center" | "text-align: right"
+        ),
+        (_, "id") if element.starts_with('h') => is_heading_id(value),
+        ("input", "type") => value == "checkbox",
+        ("button", "type") => value == "button",
+        _ => true,
+    };
+    allowed.then_some(Cow::Borrowed(value))
+}
+
+fn is_allowed_class(element: &str, class: &str) -> bool {
+    if element == "span" {
+        if let Some(token) = class.strip_prefix("hl-") {
+            return token.chars().all(|c| c.is_ascii_alphanumeric() || "+-_".contains(c));
+        }
+    }
+    ALLOWED_CLASSES.contains(&class)
+}
+
+/// Whether `id` could have come from [`HeadingIds`]. Raw HTML headings in a
+/// post can't pick arbitrary IDs that way.
+fn is_heading_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c == '-' || (c.is_alphanumeric() && !c.is_uppercase()))
+}
+
+/// Hands out heading IDs: the heading text lowercased with runs of anything
+/// but letters and digits turned into dashes, and `-2`, `-3`, ... appended to
+/// repeats. IDs only depend on the headings before them, so they stay put
+/// when a post is edited further down.
+#[derive(Default)]
+struct HeadingIds {
+    seen: HashMap<String, usize>,
+}
+
+impl HeadingIds {
+    fn next(&mut self, text: &str) -> String {
+        let mut base = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c.is_alphanumeric() {
+                base.extend(c.to_lowercase());
+            } else if !base.is_empty() && !base.ends_with('-') {
+                base.push('-');
+            }
+        }
+        base.truncate(base.trim_end_matches('-').len());
+        if base.is_empty() {
+            base.push_str("section");
+        }
+
+        let count = self.seen.entry(base.clone()).or_insert(0);
+        *count += 1;
+        let id = if *count == 1 { base } else { format!("{}-{}", base, count) };
+        // A generated `intro-2` must not collide with a heading literally
+        // called "Intro 2".
+        if *count > 1 && self.seen.contains_key(&id) {
+            return self.next(&id);
+        }
+        self.seen.insert(id.clone(), 1);
+        id
+    }
+}
+
+/// The plain text of a heading's inline events.
+fn heading_text(events: &[Event]) -> String {
+    let mut text = String::new();
+    for event in events {
+        match event {
+            Event::Text(part) | Event::Code(part) => text.push_str(part),
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Give every heading an ID and append a link to it.
+fn anchor_headings(events: Vec<Event>) -> Vec<Event> {
+    let mut ids = HeadingIds::default();
+    let mut output = Vec::with_capacity(events.len());
+    let mut heading: Option<(Tag, Vec<Event>)> = None;
+
+    for event in events {
+        match (heading.as_mut(), event) {
+            (None, Event::Start(tag @ Tag::Heading { .. })) => heading = Some((tag, Vec::new())),
+            (Some(_), Event::End(TagEnd::Heading(level))) => {
+                let (tag, inner) = heading.take().expect("inside a heading");
+                let Tag::Heading { classes, attrs, .. } = tag else { unreachable!() };
+                let id = ids.next(&heading_text(&inner));
+                output.push(Event::Start(Tag::Heading { level, id: Some(id.clone().into()), classes, attrs }));
+                output.extend(inner);
+                output.push(Event::Html(
+                    format!(r##" <a class="heading-anchor" href="#{id}" aria-label="Link to this section">#</a>"##).into(),
+                ));
+                output.push(Event::End(TagEnd::Heading(level)));
+            }
+            (Some((_, inner)), event) => inner.push(event),
+            (None, event) => output.push(event),
+        }
+    }
+    output
+}
+
+/// The headings a table of contents lists, with the IDs the rendered HTML
+/// gives them.
+pub fn outline(source: &str) -> Vec<Heading> {
+    let mut ids = HeadingIds::default();
+    let mut headings = Vec::new();
+    let mut current: Option<(u8, Vec<Event>)> = None;
+
+    for event in Parser::new_ext(source, options()) {
+        match (current.as_mut(), event) {
+            (None, Event::Start(Tag::Heading { level, .. })) => current = Some((level as u8, Vec::new())),
+            (Some(_), Event::End(TagEnd::Heading(_))) => {
+                let (level, inner) = current.take().expect("inside a heading");
+                let text = heading_text(&inner);
+                let id = ids.next(&text);
+                if OUTLINE_LEVELS.contains(&level) {
+                    headings.push(Heading { level, id, text });
+                }
+            }
+            (Some((_, inner)), event) => inner.push(event),
+            (None, _) => {}
+        }
+    }
+    headings
+}
+
+/// Every absolute `http` or `https` link in a post, in order, without
+/// repeats.
+#[cfg(feature = "server")]
+pub fn links(source: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    for event in Parser::new_ext(source, options()) {
+        if let Event::Start(Tag::Link { dest_url, .. }) = event {
+            let absolute = dest_url.starts_with("https://") || dest_url.starts_with("http://");
+            if absolute && !links.iter().any(|link| **link == *dest_url) {
+                links.push(dest_url.to_string());
+            }
+        }
+    }
+    links
+}
+
+/// The text of a post as a reader sees it, on one line: no Markdown syntax,
+/// no code blocks or images, and a space wherever a block or line ends.
+#[cfg(feature = "server")]
+pub fn plain_text(source: &str) -> String {
+    let mut text = String::new();
+    // Nesting depth of code blocks and images, whose text is left out.
+    let mut skipped = 0u32;
+    for event in Parser::new_ext(source, options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) | Event::Start(Tag::Image { .. }) => skipped += 1,
+            Event::End(TagEnd::CodeBlock) | Event::End(TagEnd::Image) => skipped = skipped.saturating_sub(1),
+            Event::Text(chunk) | Event::Code(chunk) if skipped == 0 => text.push_str(&chunk),
+            Event::SoftBreak
+            | Event::HardBreak
+            | Event::End(
+                TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item | TagEnd::BlockQuote(_) | TagEnd::TableCell,
+            ) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Render Markdown to HTML without sanitizing it.
+pub fn markdown_to_html(source: &str) -> String {
+    markdown_to_html_with(source, |_, _| None)
+}
+
+/// Like [`markdown_to_html`], but every fenced code block is first offered to
+/// `render_code` as `(info string, code)`. Returning `None` keeps the default
+/// `<pre><code>` output for that block.
+pub fn markdown_to_html_with<F>(source: &str, mut render_code: F) -> String
+where
+    F: FnMut(&str, &str) -> Option<String>,
+{
+    let mut events = Vec::new();
+    let mut fence: Option<(CowStr, String)> = None;
+
+    for event in Parser::new_ext(source, options()) {
+        if let Some((_, code)) = fence.as_mut() {
+            match event {
+                Event::Text(text) => code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => {
+                    let (info, code) = fence.take().expect("inside a fenced code block");
+                    match render_code(&info, &code) {
+                        Some(html) => events.push(Event::Html(html.into())),
+                        None => {
+                            events.push(Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))));
+                            events.push(Event::Text(code.into()));
+                            events.push(Event::End(TagEnd::CodeBlock));
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                fence = Some((info, String::new()));
+            }
+            other => events.push(other),
+        }
+    }
+
+    let mut output = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut output, anchor_headings(events).into_iter());
+    output
+}
+
+/// Strip everything outside the allowlist from an HTML fragment.
+pub fn sanitize_html(html: &str) -> String {
+    SANITIZER.clean(html).to_string()
+}
+
+/// Render Markdown to HTML that is safe to inject into the page.
+pub fn render_markdown(source: &str) -> String {
+    sanitize_html(&markdown_to_html(source))
+}
+
+/// Render a reader's comment to HTML that is safe to inject into the page.
+///
+/// Comments get a smaller Markdown: emphasis, code, quotes, lists and
+/// absolute links. Headings, images and tables are reduced to their text, raw
+/// HTML is shown as typed, and single line breaks are kept.
+pub fn render_comment(source: &str) -> String {
+    let events = Parser::new_ext(source, Options::ENABLE_STRIKETHROUGH).filter_map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Some(Event::Text(html)),
+        Event::SoftBreak => Some(Event::HardBreak),
+        Event::Start(Tag::Image { .. }) | Event::End(TagEnd::Image) => None,
+        other => Some(other),
+    });
+    let mut output = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut output, events);
+    COMMENT_SANITIZER.clean(&output).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The bodies of the posts `migrations/003_blog_posts.sql` seeds: its
+    /// string literals that span several lines.
+    fn seeded_posts() -> Vec<String> {
+        let sql = include_str!("../../../migrations/003_blog_posts.sql");
+        let mut literals = Vec::new();
+        let mut chars = sql.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\'' {
+                continue;
+            }
+            let mut literal = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\'' if chars.peek() == Some(&'\'') => {
+                        chars.next();
+                        literal.push('\'');
+                    }
+                    '\'' => break,
+                    c => literal.push(c),
+                }
+            }
+            literals.push(literal);
+        }
+        literals.into_iter().filter(|literal| literal.contains('\n')).collect()
+    }
+
+    fn count(html: &str, needle: &str) -> usize {
+        html.matches(needle).count()
+    }
+
+    #[test]
+    fn renders_the_seeded_posts() {
+        let posts = seeded_posts();
+        assert_eq!(posts.len(), 3);
+
+        let html = render_markdown(&posts[0]);
+        let heading = concat!(
+            r#"<h2 id="the-evolution-of-fintech">The Evolution of FinTech "#,
+            r##"<a class="heading-anchor" href="#the-evolution-of-fintech" aria-label="Link to this section" "##,
+            r#"rel="noopener noreferrer nofollow">#</a></h2>"#,
+        );
+        assert!(html.contains(heading), "{}", html);
+
+        let html = render_markdown(&posts[1]);
+        assert_eq!(count(&html, "<ul>"), 3);
+        assert_eq!(count(&html, "<ol>"), 1);
+        assert!(html.contains("<li>Layer adhesion and bed leveling</li>"));
+
+        let html = render_markdown(&posts[2]);
+        assert!(html.contains(r#"<h3 id="memory-safety">"#));
+        assert!(html.contains(r#"<code class="language-rust">use rust_decimal::Decimal;"#));
+        assert!(html.contains("<code>rust_decimal</code>"));
+        assert!(html.contains("<strong>Start with the math</strong>"));
+    }
+
+    #[test]
+    fn sanitizing_keeps_everything_the_seeded_posts_use() {
+        for post in seeded_posts() {
+            let unsanitized = markdown_to_html(&post);
+            let html = render_markdown(&post);
+            for tag in ["<h2 ", "<h3 ", "<p>", "<li>", "<pre>", "<code", "<strong>", "class=\"heading-anchor\""] {
+                assert_eq!(count(&html, tag), count(&unsanitized, tag), "{}", tag);
+            }
+        }
+    }
+
+    #[test]
+    fn removes_scripts() {
+        let source = "Before\n\n<script>alert(1)</script>\n\nAfter <script src=\"https://evil.example/x.js\"></script>";
+        let html = render_markdown(source);
+        assert!(!html.contains("script"), "{}", html);
+        assert!(!html.contains("alert"), "{}", html);
+        assert!(html.contains("<p>Before</p>"));
+
+        let html = render_markdown("<style>body { display: none }</style>\n\nText");
+        assert!(!html.contains("display"), "{}", html);
+    }
+
+    #[test]
+    fn removes_javascript_links() {
+        for source in [
+            "[click](javascript:alert(1))",
+            "[click](JavaScript:alert(1))",
+            "<a href=\"javascript:alert(1)\">click</a>",
+            "<a href=\"data:text/html,<script>alert(1)</script>\">click</a>",
+            "![x](javascript:alert(1))",
+        ] {
+            let html = render_markdown(source);
+            assert!(!html.to_lowercase().contains("javascript:"), "{} -> {}", source, html);
+            assert!(!html.contains("data:"), "{} -> {}", source, html);
+        }
+
+        let html = render_markdown("[ok](https://example.com) and [here](/blog)");
+        let link = r#"<a href="https://example.com" rel="noopener noreferrer nofollow">ok</a>"#;
+        assert!(html.contains(link), "{}", html);
+        assert!(html.contains(r#"<a href="/blog" rel="noopener noreferrer nofollow">here</a>"#), "{}", html);
+    }
+
+    #[test]
+    fn removes_raw_html_attributes() {
+        let html = render_markdown(r#"<img src="/a.png" onerror="alert(1)" style="width:1px">"#);
+        assert_eq!(html, r#"<img src="/a.png">"#);
+
+        let html = render_markdown(r#"<div class="evil" id="x" onclick="alert(1)">Hi</div>"#);
+        assert_eq!(html, r#"<div id="x">Hi</div>"#);
+
+        let html = render_markdown(r#"<h2 id="Not An Id">Title</h2>"#);
+        assert_eq!(html, "<h2>Title</h2>");
+
+        let html = render_markdown(r#"<span class="hl-keyword">fn</span> <span class="hl-x other">x</span>"#);
+        assert_eq!(html.trim_end(), r#"<p><span class="hl-keyword">fn</span> <span>x</span></p>"#);
+
+        let cells = r#"<td style="text-align: center">a</td><td style="background: url(x)">b</td>"#;
+        let html = render_markdown(&format!("<table><tr>{}</tr></table>", cells));
+        assert!(html.contains(r#"<td style="text-align: center">a</td><td>b</td>"#), "{}", html);
+
+        let html =
+            render_markdown(r#"<iframe src="https://evil.example"></iframe><form><input type="text" name="q"></form>"#);
+        assert_eq!(html, "<input>");
+    }
+
+    #[test]
+    fn comments_show_raw_html_as_typed() {
+        let source = "Hi <b onclick=\"x\">there</b>\n[bad](javascript:alert(1)) [ok](https://example.com)";
+        let html = render_comment(source);
+        assert!(html.contains("&lt;b onclick=\"x\"&gt;there&lt;/b&gt;"), "{}", html);
+        assert!(!html.contains("javascript:"), "{}", html);
+        let link = r#"<a href="https://example.com" rel="noopener noreferrer nofollow ugc">ok</a>"#;
+        assert!(html.contains(link), "{}", html);
+    }
+}
@@ -0,0 +1,13 @@
+//! Content rendering shared by the server and the WASM client.
+
+pub mod markdown;
+pub use markdown::{outline, render_comment, render_markdown, Heading};
+pub mod reading;
+pub use reading::reading_stats;
+
+#[cfg(feature = "server")]
+pub mod highlight;
+#[cfg(feature = "server")]
+pub mod cache;
+#[cfg(feature = "server")]
+pub use cache::rendered_post_html;
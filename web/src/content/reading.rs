@@ -0,0 +1,57 @@
+//! Word count and reading time of a post body.
+//!
+//! Counts the text a reader actually reads: the Markdown's rendered text,
+//! leaving out code blocks (skimmed, not read) and image alt text, with a
+//! fixed cost per image instead.
+
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+use super::markdown::options;
+
+/// Reading speed for prose.
+pub const WORDS_PER_MINUTE: u32 = 200;
+
+/// Time spent on each image.
+pub const SECONDS_PER_IMAGE: u32 = 12;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReadingStats {
+    pub word_count: u32,
+    pub image_count: u32,
+    /// Whole minutes, rounded up, and never less than one.
+    pub reading_time: u32,
+}
+
+/// Count the words and images in a Markdown body.
+pub fn reading_stats(source: &str) -> ReadingStats {
+    let mut word_count = 0u32;
+    let mut image_count = 0u32;
+    // Nesting depth of code blocks and images, whose text isn't counted.
+    let mut skipped = 0u32;
+
+    for event in Parser::new_ext(source, options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => skipped += 1,
+            Event::Start(Tag::Image { .. }) => {
+                image_count += 1;
+                skipped += 1;
+            }
+            Event::End(TagEnd::CodeBlock) | Event::End(TagEnd::Image) => skipped = skipped.saturating_sub(1),
+            Event::Text(text) | Event::Code(text) if skipped == 0 => {
+                word_count += text.split_whitespace().count() as u32;
+            }
+            // Raw `<img>` tags count as images; other markup isn't text.
+            Event::Html(html) | Event::InlineHtml(html) => {
+                image_count += html.to_ascii_lowercase().matches("<img").count() as u32;
+            }
+            _ => {}
+        }
+    }
+
+    let seconds = word_count * 60 / WORDS_PER_MINUTE + image_count * SECONDS_PER_IMAGE;
+    ReadingStats {
+        word_count,
+        image_count,
+        reading_time: seconds.div_ceil(60).max(1),
+    }
+}
@@ -1,17 +1,22 @@
 // src/main.rs
 use dioxus::prelude::*;
 use tracing::info;
-use views::{AppLayout, About, Blog, Contact, Home, Projects, Resume, BlogPostDetail, Protected, Callback, Login};
+use views::{AppLayout, About, AdminBlog, AdminBlogEdit, AdminBlogHistory, AdminBlogNew, AdminComments, AdminInbox, Blog, BlogPaged, BlogSeries, BlogTag, BlogTags, Contact, Home, Projects, Resume, Search, BlogPostDetail, Protected, Callback, Login};
 
 mod components;
 mod views;
 mod api;
+mod content;
+#[cfg(feature = "server")]
+mod server;
 
 
 
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
+const HIGHLIGHT_CSS: Asset = asset!("/assets/highlight.css");
+const POST_CSS: Asset = asset!("/assets/post.css");
 
 
 fn main() {
@@ -21,16 +26,55 @@ fn main() {
         dotenv::dotenv().ok();
         info!("loaded env variables");
     });
-    
+
+    #[cfg(feature = "server")]
+    if std::env::args().nth(1).as_deref() == Some("export") {
+        server::export::main();
+    } else {
+        launch_server();
+    }
+
+    #[cfg(not(feature = "server"))]
     dioxus::launch(App);
 }
 
+/// The full site: the plain HTTP routes in `server` (feeds and friends), which
+/// `dioxus::launch` has no way to register, in front of the Dioxus app.
+#[cfg(feature = "server")]
+fn app_router() -> axum::Router {
+    axum::Router::new()
+        .merge(server::routes())
+        .serve_dioxus_application(ServeConfig::new().expect("Failed to load index.html"), App)
+}
+
+#[cfg(feature = "server")]
+#[tokio::main]
+async fn launch_server() {
+    let address = dioxus::cli_config::fullstack_address_or_localhost();
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .expect("Failed to bind server address");
+    info!("listening on {}", address);
+    tokio::spawn(server::webmention::run_sender());
+    tokio::spawn(server::mail::run_mailer());
+    // The connection's address is what `server::client_ip` starts from.
+    axum::serve(listener, app_router().into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .await
+        .expect("Server error");
+}
+
 
 #[component]
 fn App() -> Element {
+    // Where `protected` pages send the visitor back to after signing in.
+    use_context_provider(|| Signal::new(views::routes::GuardContext::default()));
+
     rsx! {
         document::Link { rel: "icon", href: FAVICON }
+        document::Link { rel: "webmention", href: "/webmention" }
         document::Link { rel: "stylesheet", href: TAILWIND_CSS }
+        document::Link { rel: "stylesheet", href: HIGHLIGHT_CSS }
+        document::Link { rel: "stylesheet", href: POST_CSS }
         Router::<Route> {}
     }
 }
@@ -42,8 +86,20 @@ enum Route {
         #[route("/")]
         Home {},
 
-        #[route("/blog")]
-        Blog {},
+        #[route("/blog?:page")]
+        Blog { page: u32 },
+
+        #[route("/blog/page/:page")]
+        BlogPaged { page: u32 },
+
+        #[route("/blog/tags")]
+        BlogTags {},
+
+        #[route("/blog/tags/:tag")]
+        BlogTag { tag: String },
+
+        #[route("/blog/series/:slug")]
+        BlogSeries { slug: String },
         
         #[route("/blog/:slug")]
         BlogPostDetail { slug: String },
@@ -60,8 +116,25 @@ enum Route {
         #[route("/resume")]
         Resume {},
 
+        #[route("/search?:q")]
+        Search { q: String },
+
         #[route("/protected")]
         Protected {},
+
+        #[route("/admin/blog")]
+        AdminBlog {},
+        #[route("/admin/blog/new")]
+        AdminBlogNew {},
+        #[route("/admin/blog/:id")]
+        AdminBlogEdit { id: i32 },
+        #[route("/admin/blog/:id/history")]
+        AdminBlogHistory { id: i32 },
+        #[route("/admin/comments")]
+        AdminComments {},
+        #[route("/admin/inbox")]
+        AdminInbox {},
+
         #[route("/login")]
         Login {},
 
@@ -0,0 +1,77 @@
+//! The visitor's IP address, for rate limits and the contact inbox.
+//!
+//! The address of the connection is only the visitor's when nothing sits in
+//! between. Requests arriving from one of the `TRUSTED_PROXIES` (loopback by
+//! default) carry the real address in `X-Forwarded-For` instead, which is
+//! read from the right, skipping further trusted hops, so a visitor can't
+//! pick their own address by sending the header themselves.
+
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::ConnectInfo;
+use axum::http::request::Parts;
+use ipnet::IpNet;
+use lazy_static::lazy_static;
+use tracing::warn;
+
+lazy_static! {
+    static ref TRUSTED_PROXIES: Vec<IpNet> = trusted_proxies();
+}
+
+/// The comma-separated addresses and ranges in `TRUSTED_PROXIES`, or
+/// loopback while it is unset.
+fn trusted_proxies() -> Vec<IpNet> {
+    let config = crate::api::env::get_env_config();
+    let Some(list) = config.trusted_proxies.as_deref() else {
+        return vec!["127.0.0.0/8".parse().unwrap(), "::1/128".parse().unwrap()];
+    };
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let net = entry.parse::<IpNet>().ok().or_else(|| entry.parse::<IpAddr>().ok().map(IpNet::from));
+            if net.is_none() {
+                warn!("Ignoring {:?} in TRUSTED_PROXIES: not an address or range", entry);
+            }
+            net
+        })
+        .collect()
+}
+
+fn is_trusted(ip: IpAddr) -> bool {
+    TRUSTED_PROXIES.iter().any(|net| net.contains(&ip))
+}
+
+/// The address the request came from, looking through trusted proxies.
+/// `None` when the connection's address is unknown, as for requests made
+/// while rendering on the server.
+pub fn client_ip(parts: &Parts) -> Option<IpAddr> {
+    let peer = parts.extensions.get::<ConnectInfo<SocketAddr>>()?.0.ip().to_canonical();
+    if !is_trusted(peer) {
+        return Some(peer);
+    }
+
+    let forwarded: Vec<&str> = parts
+        .headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    if forwarded.is_empty() {
+        let real_ip = parts.headers.get("x-real-ip").and_then(|value| value.to_str().ok());
+        return Some(real_ip.and_then(|ip| ip.trim().parse().ok()).unwrap_or(peer));
+    }
+
+    let mut client = peer;
+    for hop in forwarded.into_iter().rev() {
+        // Anything left of a malformed entry can't be trusted either.
+        let Ok(hop) = hop.parse::<IpAddr>() else { break };
+        client = hop.to_canonical();
+        if !is_trusted(client) {
+            break;
+        }
+    }
+    Some(client)
+}
@@ -0,0 +1,293 @@
+//! Static export of the whole site, for hosting a fallback copy on plain
+//! static hosting.
+//!
+//! The exporter starts the real server on a loopback port and requests every
+//! public page through it, so the output is exactly what the live site
+//! renders, head tags and hydration data included. Each page lands in
+//! `<out>/<path>/index.html`, next to the feeds, the sitemap, `robots.txt`
+//! and a copy of the bundled assets.
+//!
+//! Static hosts ignore the query string, so blog pages 2..N are exported from
+//! their `/blog/page/<n>` aliases and the pages' links rewritten to match.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use tracing::info;
+
+use crate::api::blog::{fetch_blog_posts, total_pages};
+use crate::api::source::{self, ContentSource};
+use crate::api::tag_slug;
+use crate::views::routes::{canonical_path, public_routes};
+use crate::Route;
+
+const USAGE: &str = "\
+Usage: web export [--out <dir>] [--fixture <file.json> | --postgres]
+
+  --out <dir>         Where to write the site (default: dist)
+  --fixture <file>    Read posts and projects from a JSON fixture
+  --postgres          Read posts and projects from DATABASE_URL
+";
+
+/// Files that aren't `Route`s but belong in the export.
+const EXTRA_FILES: &[&str] = &["/feed.xml", "/atom.xml", "/feed.json", "/sitemap.xml", "/robots.txt"];
+
+struct ExportArgs {
+    out_dir: PathBuf,
+    source: ContentSource,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<ExportArgs, String> {
+    let mut out_dir = PathBuf::from("dist");
+    let mut source = ContentSource::Supabase;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => {
+                out_dir = args.next().ok_or("--out needs a directory")?.into();
+            }
+            "--fixture" => {
+                let path = args.next().ok_or("--fixture needs a file")?;
+                source = ContentSource::from_fixture_file(Path::new(&path))?;
+            }
+            "--postgres" => source = ContentSource::Postgres,
+            other => return Err(format!("Unknown argument: {}", other)),
+        }
+    }
+
+    Ok(ExportArgs { out_dir, source })
+}
+
+/// Entry point for `web export`.
+pub fn main() {
+    let args = match parse_args(std::env::args().skip(2)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    source::set(args.source);
+
+    if let Err(e) = run(&args.out_dir) {
+        eprintln!("Export failed: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// A URL to request and the file, relative to the output directory, to
+/// store the response in.
+struct ExportTarget {
+    url_path: String,
+    file: PathBuf,
+}
+
+impl ExportTarget {
+    /// Static hosts ignore the query string, so `/blog?page=1` is stored as
+    /// `blog/index.html`.
+    fn page(path: String) -> Self {
+        let file_path = path.split('?').next().unwrap_or(&path);
+        let file = Path::new(file_path.trim_start_matches('/')).join("index.html");
+        ExportTarget { url_path: path, file }
+    }
+
+    fn file(path: &str) -> Self {
+        ExportTarget { url_path: path.to_string(), file: path.trim_start_matches('/').into() }
+    }
+}
+
+/// Every public page, the rest of the blog listing and one detail page per
+/// published post, then the feeds and crawler files, then an archive page and
+/// feed per tag, then a page per series.
+async fn export_targets() -> Result<Vec<ExportTarget>, String> {
+    let posts = fetch_blog_posts(true).await.map_err(|e| e.to_string())?;
+
+    let mut targets: Vec<ExportTarget> = public_routes()
+        .into_iter()
+        .map(|route| ExportTarget::page(canonical_path(&route)))
+        .collect();
+    targets.extend(
+        (2..=total_pages(posts.len())).map(|page| ExportTarget::page(Route::BlogPaged { page }.to_string())),
+    );
+    targets.extend(
+        posts
+            .iter()
+            .map(|post| ExportTarget::page(Route::BlogPostDetail { slug: post.slug.clone() }.to_string())),
+    );
+    targets.extend(EXTRA_FILES.iter().map(|path| ExportTarget::file(path)));
+
+    let mut tags: Vec<String> = posts.iter().flat_map(|post| &post.tags).map(|tag| tag_slug(tag)).collect();
+    tags.sort();
+    tags.dedup();
+    tags.retain(|tag| !tag.is_empty());
+    for tag in tags {
+        targets.push(ExportTarget::page(Route::BlogTag { tag: tag.clone() }.to_string()));
+        targets.push(ExportTarget::file(&format!("/tags/{}/feed.xml", tag)));
+    }
+
+    let mut series: Vec<String> = posts.iter().filter_map(|post| post.series.as_ref()).map(|series| series.slug.clone()).collect();
+    series.sort();
+    series.dedup();
+    targets.extend(series.into_iter().map(|slug| ExportTarget::page(Route::BlogSeries { slug }.to_string())));
+
+    Ok(targets)
+}
+
+/// Where the CLI bundles the client build and assets, next to the server
+/// executable. This is the directory `ServeConfig` serves from.
+fn public_dir() -> PathBuf {
+    std::env::current_exe()
+        .expect("Failed to get current executable path")
+        .parent()
+        .unwrap()
+        .join("public")
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+async fn fetch(client: &reqwest::Client, address: SocketAddr, path: &str) -> Result<String, String> {
+    let response = client
+        .get(format!("http://{}{}", address, path))
+        // The app only renders HTML for clients that ask for it.
+        .header(reqwest::header::ACCEPT, "text/html,*/*")
+        .send()
+        .await
+        .map_err(|e| format!("GET {} failed: {}", path, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("GET {} returned {}", path, status));
+    }
+    response.text().await.map_err(|e| format!("GET {} failed: {}", path, e))
+}
+
+/// Points the blog listing links at the files the export writes: `/blog` for
+/// the first page and `/blog/page/<n>` for the others.
+fn static_links(html: &str) -> String {
+    const LINK: &str = "href=\"/blog?page=";
+
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find(LINK) {
+        let after = &rest[start + LINK.len()..];
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        out.push_str(&rest[..start]);
+        match after[..digits].parse::<u32>() {
+            Ok(page) if after[digits..].starts_with('"') => {
+                let path = match page {
+                    0 | 1 => canonical_path(&Route::Blog { page }),
+                    page => Route::BlogPaged { page }.to_string(),
+                };
+                out.push_str(&format!("href=\"{}", path));
+                rest = &after[digits..];
+            }
+            _ => {
+                out.push_str(LINK);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[tokio::main]
+async fn run(out_dir: &Path) -> Result<(), String> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Failed to bind export server: {}", e))?;
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+    let server = tokio::spawn(async move {
+        axum::serve(listener, crate::app_router().into_make_service()).await
+    });
+
+    let public = public_dir();
+    copy_dir(&public, out_dir)
+        .map_err(|e| format!("Failed to copy assets from {}: {}", public.display(), e))?;
+
+    let client = reqwest::Client::new();
+    let targets = export_targets().await?;
+    for target in &targets {
+        let mut body = fetch(&client, address, &target.url_path).await?;
+        if target.file.ends_with("index.html") {
+            body = static_links(&body);
+        }
+        let file = out_dir.join(&target.file);
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&file, body).map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+        info!("Exported {} -> {}", target.url_path, file.display());
+    }
+
+    server.abort();
+    info!("Exported {} files to {}", targets.len(), out_dir.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn exports_every_page_of_the_fixture() {
+        crate::api::env::use_test_config();
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/export.json");
+        source::set(ContentSource::from_fixture_file(&fixture).unwrap());
+
+        let targets = export_targets().await.unwrap();
+        let files: Vec<&str> = targets.iter().map(|target| target.file.to_str().unwrap()).collect();
+        assert_eq!(
+            files,
+            [
+                "index.html",
+                "blog/index.html",
+                "blog/tags/index.html",
+                "about/index.html",
+                "contact/index.html",
+                "projects/index.html",
+                "resume/index.html",
+                "search/index.html",
+                "blog/combining-finance-and-technology/index.html",
+                "feed.xml",
+                "atom.xml",
+                "feed.json",
+                "sitemap.xml",
+                "robots.txt",
+                "blog/tags/finance/index.html",
+                "tags/finance/feed.xml",
+                "blog/tags/technology/index.html",
+                "tags/technology/feed.xml",
+            ]
+        );
+    }
+
+    #[test]
+    fn pages_past_the_first_live_at_paths() {
+        let target = ExportTarget::page(Route::BlogPaged { page: 3 }.to_string());
+        assert_eq!(target.url_path, "/blog/page/3");
+        assert_eq!(target.file, Path::new("blog/page/3/index.html"));
+    }
+
+    #[test]
+    fn points_blog_links_at_the_exported_files() {
+        let html = r#"<a href="/blog?page=1">1</a><a href="/blog?page=2" rel="next">2</a><a href="/blog?page=x">?</a>"#;
+        assert_eq!(
+            static_links(html),
+            r#"<a href="/blog">1</a><a href="/blog/page/2" rel="next">2</a><a href="/blog?page=x">?</a>"#
+        );
+    }
+}
@@ -0,0 +1,474 @@
+//! RSS 2.0, Atom 1.0 and JSON Feed 1.1 for the blog.
+//!
+//! All three are built from the same published posts `get_blog` reads, with
+//! the full rendered body, tags as categories and `updated_at` as the
+//! modification date. `cover_image` is an enclosure in RSS and Atom and an
+//! attachment in JSON Feed; its size isn't known, so the RSS enclosure gives
+//! a length of 0, as the RSS Advisory Board recommends. RSS `<author>` must be
+//! an email address, so RSS names the author with `dc:creator` instead. Posts
+//! without a readable publication date are left out, rather than shown as new
+//! on every fetch.
+
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::Serialize;
+use tracing::warn;
+
+use super::{absolute_url, parse_timestamp};
+use crate::api::blog::{fetch_blog_posts, BlogPost};
+use crate::api::tag_slug;
+use crate::content::rendered_post_html;
+use crate::views::routes::canonical_path;
+use crate::Route;
+
+const FEED_TITLE: &str = "Dylan Rayburn's Blog";
+const FEED_DESCRIPTION: &str =
+    "Exploring the intersection of finance, technology, and innovation.";
+
+/// A published post with everything the feed formats need resolved.
+struct FeedEntry {
+    post: BlogPost,
+    url: String,
+    html: String,
+    published: DateTime<FixedOffset>,
+    updated: DateTime<FixedOffset>,
+}
+
+impl FeedEntry {
+    /// `None` when the post has no date to publish it under.
+    fn new(post: BlogPost) -> Option<Self> {
+        let Some(published) = parse_timestamp(&post.published_at)
+            .or_else(|| post.created_at.as_deref().and_then(parse_timestamp))
+        else {
+            warn!("Leaving '{}' out of the feeds: no readable publication date", post.slug);
+            return None;
+        };
+        let url = absolute_url(&Route::BlogPostDetail { slug: post.slug.clone() }.to_string());
+        let html = rendered_post_html(post.id, &post.content);
+        let updated = post
+            .updated_at
+            .as_deref()
+            .and_then(parse_timestamp)
+            .unwrap_or(published);
+
+        Some(FeedEntry { post, url, html, published, updated })
+    }
+
+    fn cover_image(&self) -> Option<(String, &'static str)> {
+        let image = self.post.cover_image.as_deref().filter(|image| !image.is_empty())?;
+        Some((absolute_url(image), image_mime_type(image)))
+    }
+}
+
+fn image_mime_type(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_ascii_lowercase();
+    match path.rsplit('.').next() {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("svg") => "image/svg+xml",
+        _ => "image/jpeg",
+    }
+}
+
+/// Published posts, newest first, optionally limited to one tag (any
+/// spelling; tags are compared by slug).
+async fn load_entries(tag: Option<&str>) -> Result<Vec<FeedEntry>, Response> {
+    let posts = fetch_blog_posts(true).await.map_err(|e| {
+        warn!("Failed to build feed: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load blog posts").into_response()
+    })?;
+
+    let mut entries: Vec<FeedEntry> = posts
+        .into_iter()
+        .filter(|post| match tag {
+            Some(tag) => post.tags.iter().any(|t| tag_slug(t) == tag_slug(tag)),
+            None => true,
+        })
+        .filter_map(FeedEntry::new)
+        .collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.published));
+    Ok(entries)
+}
+
+fn last_updated(entries: &[FeedEntry]) -> DateTime<FixedOffset> {
+    entries
+        .iter()
+        .map(|entry| entry.updated)
+        .max()
+        .unwrap_or_else(|| Utc::now().fixed_offset())
+}
+
+fn feed_response(content_type: &'static str, body: String) -> Response {
+    ([(header::CONTENT_TYPE, content_type)], body).into_response()
+}
+
+fn render_rss(title: &str, self_url: &str, entries: &[FeedEntry]) -> String {
+    use rss::extension::atom::{AtomExtension, Link};
+    use rss::extension::dublincore::DublinCoreExtension;
+    use rss::{Category, Channel, Enclosure, Guid, Item};
+
+    let items = entries
+        .iter()
+        .map(|entry| Item {
+            title: Some(entry.post.title.clone()),
+            link: Some(entry.url.clone()),
+            description: Some(entry.post.excerpt.clone()),
+            content: Some(entry.html.clone()),
+            dublin_core_ext: Some(DublinCoreExtension {
+                creators: vec![entry.post.author.clone()],
+                ..Default::default()
+            }),
+            enclosure: entry.cover_image().map(|(url, mime_type)| Enclosure {
+                url,
+                length: "0".to_string(),
+                mime_type: mime_type.to_string(),
+            }),
+            categories: entry
+                .post
+                .tags
+                .iter()
+                .map(|tag| Category { name: tag.clone(), domain: None })
+                .collect(),
+            guid: Some(Guid { value: entry.url.clone(), permalink: true }),
+            pub_date: Some(entry.published.to_rfc2822()),
+            ..Default::default()
+        })
+        .collect();
+
+    let channel = Channel {
+        title: title.to_string(),
+        link: absolute_url(&canonical_path(&Route::Blog { page: 1 })),
+        description: FEED_DESCRIPTION.to_string(),
+        language: Some("en-us".to_string()),
+        last_build_date: Some(last_updated(entries).to_rfc2822()),
+        generator: Some("rayburn_personal_website".to_string()),
+        atom_ext: Some(AtomExtension {
+            links: vec![Link {
+                href: self_url.to_string(),
+                rel: "self".to_string(),
+                mime_type: Some("application/rss+xml".to_string()),
+                ..Default::default()
+            }],
+        }),
+        items,
+        ..Default::default()
+    };
+    channel.to_string()
+}
+
+fn render_atom(entries: &[FeedEntry]) -> String {
+    use atom_syndication::{Category, Content, Entry, Feed, Link, Person, Text};
+
+    let self_url = absolute_url("/atom.xml");
+    let updated = last_updated(entries);
+    let entries = entries
+        .iter()
+        .map(|entry| {
+            let mut links = vec![Link {
+                href: entry.url.clone(),
+                rel: "alternate".to_string(),
+                mime_type: Some("text/html".to_string()),
+                ..Default::default()
+            }];
+            if let Some((url, mime_type)) = entry.cover_image() {
+                links.push(Link {
+                    href: url,
+                    rel: "enclosure".to_string(),
+                    mime_type: Some(mime_type.to_string()),
+                    ..Default::default()
+                });
+            }
+
+            Entry {
+                title: Text::plain(entry.post.title.clone()),
+                id: entry.url.clone(),
+                updated: entry.updated,
+                published: Some(entry.published),
+                authors: vec![Person { name: entry.post.author.clone(), ..Default::default() }],
+                categories: entry
+                    .post
+                    .tags
+                    .iter()
+                    .map(|tag| Category { term: tag.clone(), ..Default::default() })
+                    .collect(),
+                links,
+                summary: Some(Text::plain(entry.post.excerpt.clone())),
+                content: Some(Content {
+                    value: Some(entry.html.clone()),
+                    content_type: Some("html".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let feed = Feed {
+        title: Text::plain(FEED_TITLE),
+        id: self_url.clone(),
+        updated,
+        subtitle: Some(Text::plain(FEED_DESCRIPTION)),
+        links: vec![
+            Link { href: self_url, rel: "self".to_string(), ..Default::default() },
+            Link {
+                href: absolute_url(&canonical_path(&Route::Blog { page: 1 })),
+                rel: "alternate".to_string(),
+                ..Default::default()
+            },
+        ],
+        entries,
+        ..Default::default()
+    };
+    feed.to_string()
+}
+
+/// JSON Feed 1.1, see <https://www.jsonfeed.org/version/1.1/>.
+#[derive(Serialize)]
+struct JsonFeed {
+    version: &'static str,
+    title: &'static str,
+    home_page_url: String,
+    feed_url: String,
+    description: &'static str,
+    language: &'static str,
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedItem {
+    id: String,
+    url: String,
+    title: String,
+    content_html: String,
+    summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    date_published: String,
+    date_modified: String,
+    authors: Vec<JsonFeedAuthor>,
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    attachments: Vec<JsonFeedAttachment>,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAuthor {
+    name: String,
+}
+
+#[derive(Serialize)]
+struct JsonFeedAttachment {
+    url: String,
+    mime_type: &'static str,
+}
+
+fn render_json_feed(entries: &[FeedEntry]) -> String {
+    let items = entries
+        .iter()
+        .map(|entry| {
+            let cover = entry.cover_image();
+            JsonFeedItem {
+                id: entry.url.clone(),
+                url: entry.url.clone(),
+                title: entry.post.title.clone(),
+                content_html: entry.html.clone(),
+                summary: entry.post.excerpt.clone(),
+                image: cover.as_ref().map(|(url, _)| url.clone()),
+                date_published: entry.published.to_rfc3339(),
+                date_modified: entry.updated.to_rfc3339(),
+                authors: vec![JsonFeedAuthor { name: entry.post.author.clone() }],
+                tags: entry.post.tags.clone(),
+                attachments: cover
+                    .into_iter()
+                    .map(|(url, mime_type)| JsonFeedAttachment { url, mime_type })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    let feed = JsonFeed {
+        version: "https://jsonfeed.org/version/1.1",
+        title: FEED_TITLE,
+        home_page_url: absolute_url(&canonical_path(&Route::Blog { page: 1 })),
+        feed_url: absolute_url("/feed.json"),
+        description: FEED_DESCRIPTION,
+        language: "en-US",
+        items,
+    };
+    serde_json::to_string_pretty(&feed).unwrap_or_default()
+}
+
+pub async fn rss_feed() -> Response {
+    match load_entries(None).await {
+        Ok(entries) => feed_response(
+            "application/rss+xml; charset=utf-8",
+            render_rss(FEED_TITLE, &absolute_url("/feed.xml"), &entries),
+        ),
+        Err(response) => response,
+    }
+}
+
+pub async fn tag_rss_feed(Path(tag): Path<String>) -> Response {
+    match load_entries(Some(&tag)).await {
+        Ok(entries) => feed_response(
+            "application/rss+xml; charset=utf-8",
+            render_rss(
+                &format!("{} - {}", FEED_TITLE, tag),
+                &absolute_url(&format!("/tags/{}/feed.xml", tag_slug(&tag))),
+                &entries,
+            ),
+        ),
+        Err(response) => response,
+    }
+}
+
+pub async fn atom_feed() -> Response {
+    match load_entries(None).await {
+        Ok(entries) => feed_response("application/atom+xml; charset=utf-8", render_atom(&entries)),
+        Err(response) => response,
+    }
+}
+
+pub async fn json_feed() -> Response {
+    match load_entries(None).await {
+        Ok(entries) => feed_response("application/feed+json; charset=utf-8", render_json_feed(&entries)),
+        Err(response) => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn post(id: i32, slug: &str, published_at: &str, created_at: Option<&str>, cover_image: Option<&str>) -> BlogPost {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": format!("Post {}", id),
+            "slug": slug,
+            "content": "Some **Markdown**, with `code` & <b>HTML</b>.",
+            "excerpt": "A short summary",
+            "published_at": published_at,
+            "updated_at": null,
+            "created_at": created_at,
+            "tags": ["Rust", "Web Dev"],
+            "cover_image": cover_image,
+            "author": "Dylan Rayburn",
+        }))
+        .unwrap()
+    }
+
+    /// Two posts with dates, newest first, and one without, which is left out.
+    fn entries() -> Vec<FeedEntry> {
+        crate::api::env::use_test_config();
+        [
+            post(2, "second", "2024-06-01T08:00:00+00:00", None, Some("/uploads/cover.png")),
+            post(1, "first", "not a date", Some("2024-05-01"), None),
+            post(3, "undated", "", None, None),
+        ]
+        .into_iter()
+        .filter_map(FeedEntry::new)
+        .collect()
+    }
+
+    #[test]
+    fn leaves_out_posts_without_a_date() {
+        let entries = entries();
+        let slugs: Vec<&str> = entries.iter().map(|entry| entry.post.slug.as_str()).collect();
+        assert_eq!(slugs, ["second", "first"]);
+        assert_eq!(entries[1].published.to_rfc3339(), "2024-05-01T00:00:00+00:00");
+        assert_eq!(entries[1].updated, entries[1].published);
+    }
+
+    #[test]
+    fn rss_is_valid() {
+        use rss::validation::Validate;
+
+        let xml = render_rss(FEED_TITLE, &absolute_url("/feed.xml"), &entries());
+        let channel = rss::Channel::read_from(xml.as_bytes()).unwrap();
+        // The validator wants enclosures to have a positive length, which
+        // would have to be made up; check the enclosure below instead.
+        let mut without_enclosures = channel.clone();
+        without_enclosures.items.iter_mut().for_each(|item| item.enclosure = None);
+        without_enclosures.validate().unwrap();
+
+        assert_eq!(channel.link, "https://site.test/blog");
+        assert_eq!(channel.last_build_date.as_deref(), Some("Sat, 1 Jun 2024 08:00:00 +0000"));
+        assert_eq!(channel.items.len(), 2);
+        let item = &channel.items[0];
+        assert_eq!(item.link.as_deref(), Some("https://site.test/blog/second"));
+        assert_eq!(item.guid.as_ref().map(|guid| guid.value.as_str()), Some("https://site.test/blog/second"));
+        assert_eq!(item.pub_date.as_deref(), Some("Sat, 1 Jun 2024 08:00:00 +0000"));
+        assert!(item.author.is_none());
+        let creators = item.dublin_core_ext.as_ref().map(|ext| ext.creators.clone()).unwrap_or_default();
+        assert_eq!(creators, ["Dylan Rayburn"]);
+        let enclosure = item.enclosure.as_ref().unwrap();
+        assert_eq!(enclosure.url, "https://site.test/uploads/cover.png");
+        assert_eq!(enclosure.mime_type, "image/png");
+        assert_eq!(enclosure.length, "0");
+        assert!(channel.items[1].enclosure.is_none());
+        assert!(item.content.as_deref().unwrap().contains("<strong>Markdown</strong>"));
+    }
+
+    #[test]
+    fn atom_has_what_rfc_4287_requires() {
+        use atom_syndication::Feed;
+
+        let feed = Feed::read_from(render_atom(&entries()).as_bytes()).unwrap();
+        assert_eq!(feed.id, "https://site.test/atom.xml");
+        assert!(!feed.title.value.is_empty());
+        assert_eq!(feed.updated.to_rfc3339(), "2024-06-01T08:00:00+00:00");
+        let alternate = feed.links.iter().find(|link| link.rel == "alternate").unwrap();
+        assert_eq!(alternate.href, "https://site.test/blog");
+        assert!(feed.links.iter().any(|link| link.rel == "self" && link.href == feed.id));
+
+        assert_eq!(feed.entries.len(), 2);
+        for entry in &feed.entries {
+            assert!(entry.id.starts_with("https://site.test/blog/"));
+            assert!(!entry.title.value.is_empty());
+            // Every entry needs an author, since the feed has none of its own.
+            assert!(entry.authors.iter().any(|author| !author.name.is_empty()));
+            assert!(entry.links.iter().any(|link| link.rel == "alternate" && link.href == entry.id));
+            assert!(entry.published.is_some());
+        }
+        let enclosure = feed.entries[0].links.iter().find(|link| link.rel == "enclosure").unwrap();
+        assert_eq!(enclosure.href, "https://site.test/uploads/cover.png");
+        assert_eq!(enclosure.mime_type.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn json_feed_has_what_version_1_1_requires() {
+        let feed: serde_json::Value = serde_json::from_str(&render_json_feed(&entries())).unwrap();
+        assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+        assert!(feed["title"].as_str().is_some_and(|title| !title.is_empty()));
+        assert_eq!(feed["home_page_url"], "https://site.test/blog");
+        assert_eq!(feed["feed_url"], "https://site.test/feed.json");
+
+        let items = feed["items"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        for item in items {
+            assert!(item["id"].as_str().is_some_and(|id| !id.is_empty()));
+            assert!(item["content_html"].is_string() || item["content_text"].is_string());
+            for date in ["date_published", "date_modified"] {
+                assert!(DateTime::parse_from_rfc3339(item[date].as_str().unwrap()).is_ok(), "{}", date);
+            }
+            assert!(item["authors"].as_array().unwrap().iter().all(|author| author["name"].is_string()));
+        }
+        let cover = json!([{ "url": "https://site.test/uploads/cover.png", "mime_type": "image/png" }]);
+        assert_eq!(items[0]["attachments"], cover);
+        assert!(items[1].get("attachments").is_none());
+    }
+
+    #[test]
+    fn output_is_the_same_every_time() {
+        let rss = || render_rss(FEED_TITLE, "https://site.test/feed.xml", &entries());
+        assert_eq!(rss(), rss());
+        assert_eq!(render_atom(&entries()), render_atom(&entries()));
+        assert_eq!(render_json_feed(&entries()), render_json_feed(&entries()));
+    }
+}
@@ -0,0 +1,239 @@
+//! Outgoing email, through the `mail_outbox` queue
+//! (`migrations/017_mail_outbox.sql`).
+//!
+//! `queue` only stores a message rendered from `templates` and wakes
+//! `run_mailer`, which runs in the background for as long as the server does.
+//! The mailer sends whatever is due every `POLL_INTERVAL` or when woken. A
+//! message that fails is tried again after `RETRY_DELAYS`, and is marked
+//! `failed` once they run out. Mail is off while `MAIL_URL` is unset.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use dioxus::prelude::ServerFnError;
+use lazy_static::lazy_static;
+use lettre::message::header::ContentType;
+use lettre::message::{Mailbox, MultiPart, SinglePart};
+use lettre::Message;
+use serde::Deserialize;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use crate::api::auth::service_client;
+use crate::api::blog_admin::run;
+use crate::api::env::get_env_config;
+
+pub mod templates;
+mod transport;
+
+use templates::Rendered;
+use transport::Transport;
+
+/// How often the queue is checked when nothing wakes the mailer.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Waits before each retry; a message is given up after the last.
+const RETRY_DELAYS: [Duration; 6] = [
+    Duration::from_secs(60),
+    Duration::from_secs(5 * 60),
+    Duration::from_secs(30 * 60),
+    Duration::from_secs(2 * 60 * 60),
+    Duration::from_secs(6 * 60 * 60),
+    Duration::from_secs(24 * 60 * 60),
+];
+/// How long a message being sent is kept from other server instances.
+const SENDING_LEASE: Duration = Duration::from_secs(10 * 60);
+/// Messages sent per pass.
+const BATCH_SIZE: usize = 20;
+
+lazy_static! {
+    static ref SEND_NOW: Notify = Notify::new();
+}
+
+/// Headers tying a message into a conversation, so mail clients thread it.
+pub struct Thread {
+    /// The message's own Message-ID, from `new_message_id`.
+    pub message_id: String,
+    /// The Message-ID of the message it answers.
+    pub in_reply_to: Option<String>,
+}
+
+/// Whether `MAIL_URL` is set, so queued mail will go anywhere.
+pub fn enabled() -> bool {
+    get_env_config().mail_url.as_deref().is_some_and(|url| !url.is_empty())
+}
+
+/// A Message-ID in the domain of `MAIL_FROM`.
+pub fn new_message_id() -> String {
+    let config = get_env_config();
+    let domain = config
+        .mail_from
+        .as_deref()
+        .and_then(|from| from.parse::<Mailbox>().ok())
+        .map(|from| from.email.domain().to_string())
+        .unwrap_or_else(|| "localhost".to_string());
+    format!("<{}@{}>", uuid::Uuid::new_v4(), domain)
+}
+
+/// Queue `mail` for `to` and return its id in the queue. `kind` says what it
+/// is, for whoever reads the table; `contact_submission_id` links it to the
+/// message it is about.
+pub async fn queue(
+    kind: &str,
+    to: &str,
+    reply_to: Option<&str>,
+    mail: Rendered,
+    contact_submission_id: Option<&str>,
+    thread: Option<&Thread>,
+) -> Result<i64, ServerFnError> {
+    let client =
+        service_client().ok_or_else(|| ServerFnError::new("Mail needs SUPABASE_SERVICE_ROLE_KEY".to_string()))?;
+    let body = serde_json::json!({
+        "kind": kind,
+        "to_address": to,
+        "reply_to": reply_to,
+        "subject": mail.subject,
+        "text_body": mail.text,
+        "html_body": mail.html,
+        "contact_submission_id": contact_submission_id,
+        "message_id": thread.map(|thread| &thread.message_id),
+        "in_reply_to": thread.and_then(|thread| thread.in_reply_to.as_ref()),
+    });
+    let queued: Vec<serde_json::Value> =
+        run(client.table("mail_outbox").insert(body.to_string()).select("id"), "queue mail").await?;
+    SEND_NOW.notify_one();
+    queued
+        .first()
+        .and_then(|row| row["id"].as_i64())
+        .ok_or_else(|| ServerFnError::new("Queued mail came back without an id".to_string()))
+}
+
+/// Send queued mail until the server stops.
+pub async fn run_mailer() {
+    let config = get_env_config();
+    let Some(url) = config.mail_url.as_deref().filter(|url| !url.is_empty()) else {
+        info!("Not sending mail: MAIL_URL is unset");
+        return;
+    };
+    if service_client().is_none() {
+        info!("Not sending mail: SUPABASE_SERVICE_ROLE_KEY is unset");
+        return;
+    }
+    let transport = match Transport::from_url(url) {
+        Ok(transport) => transport,
+        Err(e) => {
+            warn!("Not sending mail: {}", e);
+            return;
+        }
+    };
+    let from = match config.mail_from.as_deref().unwrap_or_default().parse::<Mailbox>() {
+        Ok(from) => from,
+        Err(e) => {
+            warn!("Not sending mail: MAIL_FROM is missing or invalid ({})", e);
+            return;
+        }
+    };
+
+    loop {
+        if let Err(e) = send_due(&transport, &from).await {
+            warn!("Sending mail failed: {}", e);
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            _ = SEND_NOW.notified() => {}
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct QueuedMail {
+    id: i64,
+    to_address: String,
+    reply_to: Option<String>,
+    subject: String,
+    text_body: String,
+    html_body: String,
+    attempts: i32,
+    message_id: Option<String>,
+    in_reply_to: Option<String>,
+}
+
+const QUEUED_COLUMNS: &str = "id,to_address,reply_to,subject,text_body,html_body,attempts,message_id,in_reply_to";
+
+async fn send_due(transport: &Transport, from: &Mailbox) -> Result<(), ServerFnError> {
+    let client = service_client().ok_or_else(|| ServerFnError::new("SUPABASE_SERVICE_ROLE_KEY is unset".to_string()))?;
+    let now = Utc::now();
+    let due = client
+        .table("mail_outbox")
+        .select(QUEUED_COLUMNS)
+        .eq("status", "pending")
+        .lte("next_attempt_at", now.to_rfc3339())
+        .order("next_attempt_at.asc")
+        .limit(BATCH_SIZE);
+    let due: Vec<QueuedMail> = run(due, "fetch queued mail").await?;
+
+    for mail in due {
+        // Take the message by counting the attempt, unless another server
+        // instance got there first.
+        let attempts = mail.attempts + 1;
+        let claim = client
+            .table("mail_outbox")
+            .update(
+                serde_json::json!({
+                    "attempts": attempts,
+                    "next_attempt_at": (now + SENDING_LEASE).to_rfc3339(),
+                })
+                .to_string(),
+            )
+            .eq("id", mail.id.to_string())
+            .eq("status", "pending")
+            .eq("attempts", mail.attempts.to_string());
+        let claimed: Vec<serde_json::Value> = run(claim, "claim queued mail").await?;
+        if claimed.is_empty() {
+            continue;
+        }
+
+        let result = match build_message(from, &mail) {
+            Ok(message) => transport.send(message).await,
+            Err(e) => Err(e),
+        };
+        let update = match &result {
+            Ok(()) => serde_json::json!({ "status": "sent", "sent_at": Utc::now().to_rfc3339(), "last_error": null }),
+            Err(e) => match RETRY_DELAYS.get(attempts as usize - 1) {
+                Some(delay) => serde_json::json!({
+                    "next_attempt_at": (Utc::now() + *delay).to_rfc3339(),
+                    "last_error": e,
+                }),
+                None => serde_json::json!({ "status": "failed", "last_error": e }),
+            },
+        };
+        match &result {
+            Ok(()) => info!("Sent mail {} to {}", mail.id, mail.to_address),
+            Err(e) => warn!("Sending mail {} failed (attempt {}): {}", mail.id, attempts, e),
+        }
+        let saved = client.table("mail_outbox").update(update.to_string()).eq("id", mail.id.to_string());
+        let _: serde_json::Value = run(saved, "update queued mail").await?;
+    }
+    Ok(())
+}
+
+fn build_message(from: &Mailbox, mail: &QueuedMail) -> Result<Message, String> {
+    let to = mail.to_address.parse::<Mailbox>().map_err(|e| format!("Invalid recipient: {}", e))?;
+    let mut builder = Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(&mail.subject)
+        .message_id(mail.message_id.clone());
+    if let Some(reply_to) = &mail.reply_to {
+        builder = builder.reply_to(reply_to.parse::<Mailbox>().map_err(|e| format!("Invalid reply-to: {}", e))?);
+    }
+    if let Some(in_reply_to) = &mail.in_reply_to {
+        builder = builder.in_reply_to(in_reply_to.clone()).references(in_reply_to.clone());
+    }
+    builder
+        .multipart(
+            MultiPart::alternative()
+                .singlepart(SinglePart::builder().header(ContentType::TEXT_PLAIN).body(mail.text_body.clone()))
+                .singlepart(SinglePart::builder().header(ContentType::TEXT_HTML).body(mail.html_body.clone())),
+        )
+        .map_err(|e| format!("Failed to build the message: {}", e))
+}
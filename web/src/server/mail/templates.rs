@@ -0,0 +1,165 @@
+//! The emails the site sends, each as plain text and HTML.
+//!
+//! Values from visitors are escaped in the HTML versions; the text versions
+//! use them as they are, since `ContactForm::validate` has already stripped
+//! control characters.
+
+use crate::api::contact::ContactForm;
+use crate::api::inbox::InboxMessage;
+use crate::api::env::SITE_URL;
+
+/// A message ready to queue.
+pub struct Rendered {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Tells the site owner about a new contact message. Replying to it
+/// answers the sender.
+pub fn contact_notification(form: &ContactForm, ip_address: Option<&str>) -> Rendered {
+    let subject_line = if form.subject.is_empty() { "(no subject)" } else { &form.subject };
+    let received = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string();
+    let ip_address = ip_address.unwrap_or("unknown");
+
+    let text = format!(
+        "New message from the contact form on {site}\n\n\
+         From:     {name} <{email}>\n\
+         Subject:  {subject}\n\
+         Received: {received}\n\
+         IP:       {ip}\n\n\
+         {message}\n\n\
+         --\nReply to this email to answer {name}.\n",
+        site = *SITE_URL,
+        name = form.name,
+        email = form.email,
+        subject = subject_line,
+        received = received,
+        ip = ip_address,
+        message = form.message,
+    );
+
+    let html = layout(
+        "New contact message",
+        &format!(
+            r#"<table style="border-collapse:collapse;margin-bottom:16px;font-size:14px">
+{from}{subject}{received}{ip}</table>
+<div style="padding:16px;background:#f3f4f6;border-radius:8px">{message}</div>
+<p style="color:#6b7280;font-size:13px">Reply to this email to answer {name}.</p>"#,
+            from = row("From", &format!("{} &lt;{}&gt;", escape(&form.name), escape(&form.email))),
+            subject = row("Subject", &escape(subject_line)),
+            received = row("Received", &received),
+            ip = row("IP", &escape(ip_address)),
+            message = paragraphs(&form.message),
+            name = escape(&form.name),
+        ),
+    );
+
+    Rendered { subject: format!("Contact form: {} from {}", subject_line, form.name), text, html }
+}
+
+/// Lets the sender know their message arrived. Only their name is repeated
+/// back, so the form can't be used to send someone else arbitrary text.
+pub fn contact_acknowledgement(form: &ContactForm) -> Rendered {
+    let text = format!(
+        "Hi {name},\n\n\
+         Thanks for getting in touch. Your message arrived safely, and I'll get back to you as soon as I can, usually within a couple of working days.\n\n\
+         If you didn't send a message through {site}, you can ignore this email.\n\n\
+         Dylan Rayburn\n{site}\n",
+        name = form.name,
+        site = *SITE_URL,
+    );
+
+    let html = layout(
+        "Thanks for your message",
+        &format!(
+            r#"<p>Hi {name},</p>
+<p>Thanks for getting in touch. Your message arrived safely, and I'll get back to you as soon as I can, usually within a couple of working days.</p>
+<p style="color:#6b7280;font-size:13px">If you didn't send a message through <a href="{site}">{site}</a>, you can ignore this email.</p>
+<p>Dylan Rayburn</p>"#,
+            name = escape(&form.name),
+            site = escape(&SITE_URL),
+        ),
+    );
+
+    Rendered { subject: "Thanks for your message".to_string(), text, html }
+}
+
+/// An author's answer to a contact message, quoting it underneath.
+pub fn contact_reply(original: &InboxMessage, body: &str) -> Rendered {
+    let subject = match original.subject.as_deref() {
+        Some(subject) if subject.to_lowercase().starts_with("re:") => subject.to_string(),
+        Some(subject) => format!("Re: {}", subject),
+        None => "Re: Your message".to_string(),
+    };
+    let written = chrono::DateTime::parse_from_rfc3339(&original.created_at)
+        .map(|at| at.with_timezone(&chrono::Utc).format("%Y-%m-%d at %H:%M UTC").to_string())
+        .unwrap_or_else(|_| original.created_at.clone());
+    let attribution = format!("On {}, {} wrote:", written, original.name);
+    let quoted: String = original.message.lines().map(|line| format!("> {}\n", line)).collect();
+
+    let text = format!("{}\n\n{}\n{}", body.trim_end(), attribution, quoted);
+    let html = page(
+        &subject,
+        &format!(
+            r#"{body}
+<p style="margin:24px 0 8px;color:#6b7280;font-size:13px">{attribution}</p>
+<blockquote style="margin:0;padding:0 0 0 12px;border-left:3px solid #d1d5db;color:#4b5563">{quoted}</blockquote>"#,
+            body = paragraphs(body),
+            attribution = escape(&attribution),
+            quoted = paragraphs(&original.message),
+        ),
+    );
+
+    Rendered { subject, text, html }
+}
+
+/// The HTML page every message shares, headed by `title`.
+fn layout(title: &str, body: &str) -> String {
+    let heading = format!(r#"<h1 style="font-size:20px;margin:0 0 16px">{}</h1>"#, escape(title));
+    page(title, &format!("{}\n{}", heading, body))
+}
+
+/// The page around a message, without a heading, for mail that should read
+/// like a personal letter.
+fn page(title: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head><meta charset="utf-8"><meta name="viewport" content="width=device-width"><title>{title}</title></head>
+<body style="margin:0;padding:24px;background:#ffffff;color:#111827;font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Helvetica,Arial,sans-serif;line-height:1.5">
+<div style="max-width:600px;margin:0 auto">
+{body}
+</div>
+</body>
+</html>
+"#,
+        title = escape(title),
+        body = body,
+    )
+}
+
+fn row(label: &str, value_html: &str) -> String {
+    format!(
+        r#"<tr><td style="padding:2px 16px 2px 0;color:#6b7280">{}</td><td style="padding:2px 0">{}</td></tr>
+"#,
+        label, value_html
+    )
+}
+
+/// `text` as HTML paragraphs, keeping single line breaks.
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| format!("<p style=\"margin:0 0 12px\">{}</p>", escape(paragraph).replace('\n', "<br>")))
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
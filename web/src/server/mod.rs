@@ -0,0 +1,61 @@
+//! Plain HTTP endpoints served alongside the Dioxus app.
+//!
+//! Everything here is server-only and is merged into the fullstack router in
+//! `main`, ahead of the app's catch-all route.
+
+use axum::routing::{get, post};
+use axum::Router;
+use tower_http::services::ServeDir;
+
+use crate::api::storage::LOCAL_UPLOADS_PATH;
+
+pub use crate::api::env::absolute_url;
+
+pub mod client_ip;
+pub mod export;
+mod feeds;
+pub mod mail;
+pub mod rate_limit;
+mod sitemap;
+pub mod spam;
+pub mod webmention;
+
+/// Extra routes mounted next to the Dioxus application.
+pub fn routes() -> Router {
+    let router = Router::new()
+        .route("/feed.xml", get(feeds::rss_feed))
+        .route("/atom.xml", get(feeds::atom_feed))
+        .route("/feed.json", get(feeds::json_feed))
+        .route("/tags/:tag/feed.xml", get(feeds::tag_rss_feed))
+        .route("/sitemap.xml", get(sitemap::sitemap))
+        .route("/sitemaps/:file", get(sitemap::sitemap_page))
+        .route("/robots.txt", get(sitemap::robots))
+        .route("/webmention", post(webmention::receive));
+
+    // Uploads kept on disk (`IMAGE_STORAGE_DIR`) are served from here; with
+    // Supabase Storage they have their own public URLs.
+    match crate::api::storage::local_root() {
+        Some(root) => router.nest_service(LOCAL_UPLOADS_PATH, ServeDir::new(root)),
+        None => router,
+    }
+}
+
+/// Parse a timestamp as returned by PostgREST (`2024-05-01T12:34:56.789+00:00`),
+/// tolerating the zone-less and date-only forms used by hand-written rows.
+pub fn parse_timestamp(value: &str) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .ok()
+                .map(|naive| naive.and_utc().fixed_offset())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|naive| naive.and_utc().fixed_offset())
+        })
+}
@@ -0,0 +1,116 @@
+//! Sliding-window rate limits for things visitors can send, like comments
+//! and contact messages.
+//!
+//! Counts are kept in memory unless `RATE_LIMIT_STORE=postgres`, in which
+//! case they go through the `hit_rate_limits` database function
+//! (`migrations/016_rate_limits.sql`) so every server instance shares them.
+//! If the database can't be reached the in-memory counts are used instead,
+//! rather than letting everything through.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+/// At most `max` requests per key in any `window`.
+pub struct Limit {
+    /// Keeps the counts of different limits apart.
+    pub bucket: &'static str,
+    pub max: usize,
+    pub window: Duration,
+}
+
+/// Recent requests per bucket and key, with the bucket's window.
+type Hits = HashMap<(&'static str, String), (Duration, Vec<Instant>)>;
+
+lazy_static! {
+    static ref RECENT: Mutex<Hits> = Mutex::new(HashMap::new());
+}
+
+/// Count a request for `key` and say whether `limit` allows it. Refused
+/// requests aren't counted.
+pub async fn allow(limit: &Limit, key: &str) -> bool {
+    allow_all(&[(limit, key)]).await
+}
+
+/// Count a request against several limits at once, each with its own key,
+/// and say whether all of them allow it. A request one limit refuses isn't
+/// counted against the others either.
+pub async fn allow_all(requests: &[(&Limit, &str)]) -> bool {
+    let postgres = crate::api::env::get_env_config()
+        .rate_limit_store
+        .as_deref()
+        .is_some_and(|store| store.eq_ignore_ascii_case("postgres"));
+    if postgres {
+        match allow_in_database(requests).await {
+            Ok(allowed) => return allowed,
+            Err(error) => warn!("Falling back to in-memory rate limits: {}", error),
+        }
+    }
+    allow_in_memory(requests)
+}
+
+fn allow_in_memory(requests: &[(&Limit, &str)]) -> bool {
+    let now = Instant::now();
+    let mut recent = RECENT.lock().expect("rate limits poisoned");
+    recent.retain(|_, (window, hits)| {
+        hits.retain(|at| now.duration_since(*at) < *window);
+        !hits.is_empty()
+    });
+    let allowed = requests.iter().all(|(limit, key)| {
+        recent.get(&(limit.bucket, key.to_string())).map_or(0, |(_, hits)| hits.len()) < limit.max
+    });
+    if allowed {
+        for (limit, key) in requests {
+            let entry = recent.entry((limit.bucket, key.to_string()));
+            entry.or_insert_with(|| (limit.window, Vec::new())).1.push(now);
+        }
+    }
+    allowed
+}
+
+async fn allow_in_database(requests: &[(&Limit, &str)]) -> Result<bool, String> {
+    let client = crate::api::auth::service_client().ok_or("SUPABASE_SERVICE_ROLE_KEY is not set")?;
+    let hits: Vec<serde_json::Value> = requests
+        .iter()
+        .map(|(limit, key)| {
+            serde_json::json!({
+                "bucket": limit.bucket,
+                "key": format!("{:x}", Sha256::digest(key.as_bytes())),
+                "max_hits": limit.max,
+                "window_seconds": limit.window.as_secs(),
+            })
+        })
+        .collect();
+    let params = serde_json::json!({ "hits": hits });
+    let response = client
+        .rpc("hit_rate_limits", params.to_string())
+        .execute()
+        .await
+        .map_err(|e| format!("Failed to reach the database: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("hit_rate_limits answered {}", response.status()));
+    }
+    let body = response.text().await.map_err(|e| format!("Failed to read hit_rate_limits' answer: {}", e))?;
+    serde_json::from_str(&body).map_err(|e| format!("Failed to parse hit_rate_limits' answer: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST: Limit = Limit { bucket: "test-first", max: 2, window: Duration::from_secs(60) };
+    const SECOND: Limit = Limit { bucket: "test-second", max: 1, window: Duration::from_secs(60) };
+
+    #[test]
+    fn a_refused_request_counts_against_no_limit() {
+        assert!(allow_in_memory(&[(&FIRST, "visitor"), (&SECOND, "a@example.com")]));
+        assert!(!allow_in_memory(&[(&FIRST, "visitor"), (&SECOND, "a@example.com")]));
+        // The refusal above left the first limit one short, so this still fits.
+        assert!(allow_in_memory(&[(&FIRST, "visitor"), (&SECOND, "b@example.com")]));
+        assert!(!allow_in_memory(&[(&FIRST, "visitor"), (&SECOND, "c@example.com")]));
+    }
+}
@@ -0,0 +1,186 @@
+//! `sitemap.xml` and `robots.txt`.
+//!
+//! Static pages come from the `Route` enum, filtered through the same guard
+//! rules the router uses, and every published post adds a `BlogPostDetail`
+//! URL, plus a `BlogTag` archive for each of its tags and a `BlogSeries`
+//! page for its series. Past the protocol limit of 50,000 URLs per file, `/sitemap.xml`
+//! becomes a sitemap index pointing at `/sitemaps/<n>.xml`.
+
+use std::collections::BTreeMap;
+
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, FixedOffset};
+use dioxus::prelude::Routable;
+use tracing::info;
+
+use super::{absolute_url, parse_timestamp};
+use crate::api::blog::fetch_blog_posts;
+use crate::api::projects::get_projects;
+use crate::api::tag_slug;
+use crate::views::routes::{canonical_path, is_public, public_routes};
+use crate::Route;
+
+/// Maximum number of URLs allowed in a single sitemap file.
+const MAX_URLS_PER_SITEMAP: usize = 50_000;
+
+struct SitemapUrl {
+    loc: String,
+    lastmod: Option<DateTime<FixedOffset>>,
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_response(body: String) -> Response {
+    ([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], body).into_response()
+}
+
+/// Every public URL on the site, static pages first.
+async fn collect_urls() -> Result<Vec<SitemapUrl>, Response> {
+    let posts = fetch_blog_posts(true).await.map_err(|e| {
+        info!("Failed to build sitemap: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load blog posts").into_response()
+    })?;
+    // Projects only affect the listing's lastmod, so a failure here isn't fatal.
+    let projects = get_projects().await.unwrap_or_default();
+
+    let post_dates: Vec<_> = posts
+        .iter()
+        .map(|post| {
+            post.updated_at
+                .as_deref()
+                .and_then(parse_timestamp)
+                .or_else(|| parse_timestamp(&post.published_at))
+        })
+        .collect();
+    let newest_post = post_dates.iter().flatten().max().copied();
+    let newest_project = projects
+        .iter()
+        .filter_map(|project| parse_timestamp(&project.created_at))
+        .max();
+
+    let mut urls: Vec<SitemapUrl> = public_routes()
+        .into_iter()
+        .map(|route| {
+            let lastmod = match route {
+                Route::Blog { .. } | Route::BlogTags {} => newest_post,
+                Route::Projects {} => newest_project,
+                _ => None,
+            };
+            SitemapUrl { loc: absolute_url(&canonical_path(&route)), lastmod }
+        })
+        .collect();
+
+    urls.extend(posts.iter().zip(&post_dates).map(|(post, lastmod)| SitemapUrl {
+        loc: absolute_url(&Route::BlogPostDetail { slug: post.slug.clone() }.to_string()),
+        lastmod: *lastmod,
+    }));
+
+    // A tag archive changes whenever one of its posts does.
+    let mut tags: BTreeMap<String, Option<DateTime<FixedOffset>>> = BTreeMap::new();
+    for (post, lastmod) in posts.iter().zip(&post_dates) {
+        for tag in &post.tags {
+            let newest = tags.entry(tag_slug(tag)).or_default();
+            *newest = (*newest).max(*lastmod);
+        }
+    }
+    urls.extend(tags.into_iter().filter(|(slug, _)| !slug.is_empty()).map(|(tag, lastmod)| SitemapUrl {
+        loc: absolute_url(&Route::BlogTag { tag }.to_string()),
+        lastmod,
+    }));
+
+    // So does a series page.
+    let mut series: BTreeMap<String, Option<DateTime<FixedOffset>>> = BTreeMap::new();
+    for (post, lastmod) in posts.iter().zip(&post_dates) {
+        if let Some(post_series) = &post.series {
+            let newest = series.entry(post_series.slug.clone()).or_default();
+            *newest = (*newest).max(*lastmod);
+        }
+    }
+    urls.extend(series.into_iter().map(|(slug, lastmod)| SitemapUrl {
+        loc: absolute_url(&Route::BlogSeries { slug }.to_string()),
+        lastmod,
+    }));
+    Ok(urls)
+}
+
+fn render_urlset(urls: &[SitemapUrl]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for url in urls {
+        xml.push_str("  <url>\n");
+        xml.push_str(&format!("    <loc>{}</loc>\n", escape_xml(&url.loc)));
+        if let Some(lastmod) = url.lastmod {
+            xml.push_str(&format!("    <lastmod>{}</lastmod>\n", lastmod.to_rfc3339()));
+        }
+        xml.push_str("  </url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+fn render_index(pages: usize) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for page in 1..=pages {
+        xml.push_str("  <sitemap>\n");
+        xml.push_str(&format!(
+            "    <loc>{}</loc>\n",
+            escape_xml(&absolute_url(&format!("/sitemaps/{}.xml", page)))
+        ));
+        xml.push_str("  </sitemap>\n");
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
+
+pub async fn sitemap() -> Response {
+    let urls = match collect_urls().await {
+        Ok(urls) => urls,
+        Err(response) => return response,
+    };
+
+    if urls.len() <= MAX_URLS_PER_SITEMAP {
+        xml_response(render_urlset(&urls))
+    } else {
+        xml_response(render_index(urls.len().div_ceil(MAX_URLS_PER_SITEMAP)))
+    }
+}
+
+/// One page of a split sitemap, `/sitemaps/<n>.xml` with `n` starting at 1.
+pub async fn sitemap_page(Path(file): Path<String>) -> Response {
+    let page = match file.strip_suffix(".xml").and_then(|n| n.parse::<usize>().ok()) {
+        Some(page) if page >= 1 => page,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let urls = match collect_urls().await {
+        Ok(urls) => urls,
+        Err(response) => return response,
+    };
+
+    match urls.chunks(MAX_URLS_PER_SITEMAP).nth(page - 1) {
+        Some(chunk) => xml_response(render_urlset(chunk)),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+pub async fn robots() -> Response {
+    let mut body = String::from("User-agent: *\n");
+    for route in Route::static_routes().iter().filter(|route| !is_public(route)) {
+        body.push_str(&format!("Disallow: {}\n", route));
+    }
+    body.push_str("Allow: /\n\n");
+    body.push_str(&format!("Sitemap: {}\n", absolute_url("/sitemap.xml")));
+
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], body).into_response()
+}
@@ -0,0 +1,185 @@
+//! Spam checks for contact messages.
+//!
+//! Each message gets a score. The hidden honeypot field, a form token that is
+//! missing, forged, reused, too old or used within `MIN_FILL_TIME` of being
+//! issued, and an unsolved proof of work each add `SPAM_SCORE` on their own;
+//! links, link markup and blocked phrases add smaller amounts. The signals
+//! behind the score are saved in the message's `metadata`.
+//!
+//! Form tokens are `<issue time in unix milliseconds>.<signature>`, where the
+//! signature is a hex HMAC-SHA256 keyed with `SUPABASE_SERVICE_ROLE_KEY`, the
+//! key the contact form needs anyway. Nothing is stored but the tokens
+//! already used, so those are only refused until the server restarts.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use hmac::Mac;
+use lazy_static::lazy_static;
+
+use crate::api::contact::{proof_holds, ContactForm, ContactProof};
+
+/// Leading zero bits the proof of work needs: about 65,000 hashes, a moment
+/// in the browser.
+pub const PROOF_OF_WORK_BITS: u32 = 16;
+/// A score from here up marks a message as spam.
+pub const SPAM_SCORE: u32 = 4;
+/// Nobody fills in the form faster than this.
+const MIN_FILL_TIME: Duration = Duration::from_secs(3);
+/// Tokens older than this are refused.
+const MAX_TOKEN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Phrases that rarely turn up outside spam, matched ignoring case. More can
+/// be added with `CONTACT_BLOCKED_PHRASES`.
+const BLOCKED_PHRASES: &[&str] = &[
+    "seo services",
+    "backlinks",
+    "guest post",
+    "first page of google",
+    "increase your traffic",
+    "casino",
+    "viagra",
+    "cialis",
+    "forex signals",
+    "crypto investment",
+    "binary options",
+];
+
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+
+lazy_static! {
+    /// Tokens already sent with a message, with when they were sent. Kept
+    /// until they would have expired anyway.
+    static ref USED_TOKENS: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+}
+
+fn mac(payload: &str) -> Option<HmacSha256> {
+    let key = crate::api::env::get_env_config()
+        .supabase_service_role_key
+        .as_deref()
+        .filter(|key| !key.is_empty())?;
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(b"contact-form:");
+    mac.update(payload.as_bytes());
+    Some(mac)
+}
+
+/// A token for a contact form shown now. `None` without the service role key.
+pub fn issue_token() -> Option<String> {
+    let issued = chrono::Utc::now().timestamp_millis().to_string();
+    let signature: String = mac(&issued)?
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Some(format!("{}.{}", issued, signature))
+}
+
+/// What is wrong with `token`, if anything. A token passing the check is
+/// remembered, so it fails as reused the next time.
+fn check_token(token: &str) -> Option<&'static str> {
+    if token.is_empty() {
+        return Some("no token");
+    }
+    let Some((issued, signature)) = token.split_once('.') else {
+        return Some("forged token");
+    };
+    let signature: Option<Vec<u8>> = (signature.len() % 2 == 0)
+        .then(|| {
+            (0..signature.len())
+                .step_by(2)
+                .map(|i| signature.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+                .collect()
+        })
+        .flatten();
+    let genuine = match (signature, mac(issued)) {
+        (Some(signature), Some(mac)) => mac.verify_slice(&signature).is_ok(),
+        _ => false,
+    };
+    let Some(issued) = issued.parse::<i64>().ok().filter(|_| genuine) else {
+        return Some("forged token");
+    };
+
+    let age = Duration::from_millis(chrono::Utc::now().timestamp_millis().saturating_sub(issued).max(0) as u64);
+    if age < MIN_FILL_TIME {
+        return Some("sent too fast");
+    }
+    if age > MAX_TOKEN_AGE {
+        return Some("expired token");
+    }
+
+    let now = Instant::now();
+    let mut used = USED_TOKENS.lock().expect("used contact tokens poisoned");
+    used.retain(|_, at| now.duration_since(*at) < MAX_TOKEN_AGE);
+    if used.insert(token.to_string(), now).is_some() {
+        return Some("reused token");
+    }
+    None
+}
+
+/// A message's spam score and the signals that make it up.
+#[derive(Debug, Default)]
+pub struct Verdict {
+    pub score: u32,
+    pub signals: Vec<String>,
+}
+
+impl Verdict {
+    pub fn is_spam(&self) -> bool {
+        self.score >= SPAM_SCORE
+    }
+
+    fn add(&mut self, points: u32, signal: impl Into<String>) {
+        self.score += points;
+        self.signals.push(signal.into());
+    }
+}
+
+/// Score a validated contact message and the proof sent with it.
+pub fn assess(form: &ContactForm, proof: &ContactProof) -> Verdict {
+    let mut verdict = Verdict::default();
+
+    if !form.website.is_empty() {
+        verdict.add(SPAM_SCORE, "honeypot");
+    }
+    if let Some(problem) = check_token(&proof.token) {
+        verdict.add(SPAM_SCORE, problem);
+    } else if !proof_holds(&proof.token, proof.counter, PROOF_OF_WORK_BITS) {
+        verdict.add(SPAM_SCORE, "no proof of work");
+    }
+
+    let text = format!("{}\n{}", form.subject, form.message);
+    let links = text.split_whitespace().filter(|word| is_link(word)).count() as u32;
+    if links > 1 {
+        // One link is normal enough; every further one counts.
+        verdict.add(links - 1, format!("{} links", links));
+    }
+    if form.name.split_whitespace().any(is_link) {
+        verdict.add(3, "link in name");
+    }
+    let lowered = text.to_lowercase();
+    if lowered.contains("[url") || lowered.contains("<a href") {
+        verdict.add(3, "link markup");
+    }
+
+    let configured = crate::api::env::get_env_config().contact_blocked_phrases.as_deref().unwrap_or_default();
+    let phrases = BLOCKED_PHRASES
+        .iter()
+        .copied()
+        .chain(configured.split(',').map(str::trim))
+        .filter(|phrase| !phrase.is_empty());
+    for phrase in phrases {
+        if lowered.contains(&phrase.to_lowercase()) {
+            verdict.add(2, format!("phrase \"{}\"", phrase));
+        }
+    }
+
+    verdict
+}
+
+fn is_link(word: &str) -> bool {
+    let word = word.to_lowercase();
+    word.contains("http://") || word.contains("https://") || word.starts_with("www.")
+}
@@ -0,0 +1,797 @@
+//! Webmentions (<https://www.w3.org/TR/webmention/>), sent and received.
+//!
+//! Sending runs in the background for as long as the server does. Every
+//! `SEND_INTERVAL`, or soon after an author saves a post, it compares each
+//! public post with `webmentions_sent`. Then it notifies the links that are
+//! new or were removed since the last version it sent for.
+//!
+//! `POST /webmention` receives mentions, up to `RECEIVE_LIMIT` per sender.
+//! The request is checked and stored as pending straight away, and answered
+//! with `202 Accepted`. The source page is fetched afterwards. If it links to
+//! the post, its h-entry decides whether the mention is a reply, like, repost
+//! or plain mention. If it doesn't, or it is gone, the mention is dropped.
+//!
+//! Both directions fetch pages named by other people, so the client only
+//! connects to public addresses, whatever a host name or redirect points at.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::Form;
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use dioxus::prelude::ServerFnError;
+use lazy_static::lazy_static;
+use reqwest::header::{CONTENT_TYPE, LINK};
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use tokio::sync::{Notify, Semaphore};
+use tracing::{info, warn};
+
+use super::rate_limit::{self, Limit};
+use super::{absolute_url, client_ip, parse_timestamp};
+use crate::api::auth::{create_server_client, service_client};
+use crate::api::blog::only_public;
+use crate::api::blog_admin::run;
+use crate::api::env::SITE_URL;
+use crate::api::webmentions::MentionKind;
+use crate::Route;
+
+/// How often posts are checked for links to notify.
+const SEND_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REDIRECTS: usize = 5;
+/// Pages are read up to this size when looking for links.
+const MAX_PAGE_BYTES: usize = 1024 * 1024;
+/// Sources verified at once; more mentions wait their turn.
+const MAX_VERIFYING: usize = 4;
+/// Longest reply or mention text kept, in characters.
+const MAX_CONTENT_CHARS: usize = 500;
+/// Mentions one sender may send in ten minutes; each means fetching a page.
+const RECEIVE_LIMIT: Limit = Limit { bucket: "webmentions", max: 20, window: Duration::from_secs(10 * 60) };
+
+lazy_static! {
+    static ref SEND_NOW: Notify = Notify::new();
+    static ref VERIFYING: Semaphore = Semaphore::new(MAX_VERIFYING);
+}
+
+/// A client that only reaches public addresses: host names are checked as
+/// they resolve, and literal addresses on every redirect. Callers check the
+/// first URL with `fetchable`.
+fn http_client() -> reqwest::Client {
+    let redirect = reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if !is_http(attempt.url()) || !is_public_address(attempt.url()) {
+            attempt.error("redirected to a private address")
+        } else {
+            attempt.follow()
+        }
+    });
+    reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .redirect(redirect)
+        .dns_resolver(Arc::new(PublicResolver))
+        .user_agent(format!("Webmention (+{})", *SITE_URL))
+        .build()
+        .expect("Failed to build the webmention HTTP client")
+}
+
+/// Resolves host names to their public addresses only, so a name pointing
+/// into the server's network can't be used to reach it.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.filter(|address| may_reach(address.ip())).collect();
+            if addresses.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addresses.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// The response body as text, cut off at `MAX_PAGE_BYTES`.
+async fn read_page(mut response: reqwest::Response) -> Result<String, String> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_PAGE_BYTES {
+            body.truncate(MAX_PAGE_BYTES);
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+fn is_html(response: &reqwest::Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("html"))
+}
+
+/// Whether `ip` is outside the server's own network.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            let shared = a == 100 && b & 0xc0 == 64;
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || a == 0
+                || shared)
+        }
+        IpAddr::V6(ip) => {
+            let unique_local = ip.segments()[0] & 0xfe00 == 0xfc00;
+            let link_local = ip.segments()[0] & 0xffc0 == 0xfe80;
+            !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+        }
+    }
+}
+
+/// Whether the client may connect to `ip`. Tests stand in for other sites on
+/// loopback, so there it may connect anywhere.
+fn may_reach(ip: IpAddr) -> bool {
+    cfg!(test) || is_public_ip(ip)
+}
+
+/// Whether `url` can be fetched without reaching into the server's own
+/// network, as far as can be told without resolving its host: literal
+/// addresses and `localhost`. `PublicResolver` checks the rest.
+fn is_public_address(url: &Url) -> bool {
+    let Some(host) = url.host_str() else { return false };
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) => may_reach(ip),
+        Err(_) => cfg!(test) || (host != "localhost" && !host.ends_with(".localhost")),
+    }
+}
+
+/// `url`, parsed, if the webmention client may fetch it.
+fn fetchable(url: &str) -> Result<Url, String> {
+    let url = Url::parse(url).map_err(|e| e.to_string())?;
+    if !is_http(&url) {
+        return Err(format!("{} is not an http or https URL", url));
+    }
+    if !is_public_address(&url) {
+        return Err(format!("{} is not a public address", url));
+    }
+    Ok(url)
+}
+
+fn is_http(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+}
+
+/// The same page, ignoring a fragment and a trailing slash.
+fn same_page(a: &Url, b: &Url) -> bool {
+    let key = |url: &Url| {
+        let mut url = url.clone();
+        url.set_fragment(None);
+        url.as_str().trim_end_matches('/').to_string()
+    };
+    key(a) == key(b)
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("valid selector")
+}
+
+// Sending
+
+/// Check for links to notify now rather than at the next interval, e.g.
+/// after a post is published.
+pub fn send_soon() {
+    SEND_NOW.notify_one();
+}
+
+/// Notify the links of public posts, for the life of the server.
+pub async fn run_sender() {
+    if service_client().is_none() {
+        info!("Not sending webmentions: SUPABASE_SERVICE_ROLE_KEY is unset");
+        return;
+    }
+    loop {
+        if let Err(e) = send_pending().await {
+            warn!("Sending webmentions failed: {}", e);
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(SEND_INTERVAL) => {}
+            _ = SEND_NOW.notified() => {}
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PublicPost {
+    id: i32,
+    slug: String,
+    content: String,
+    updated_at: String,
+}
+
+#[derive(Deserialize)]
+struct SentMention {
+    post_id: i32,
+    target: String,
+    post_updated_at: String,
+}
+
+/// What came of notifying one link.
+#[derive(Default)]
+struct SendOutcome {
+    endpoint: Option<String>,
+    status_code: Option<u16>,
+    error: Option<String>,
+}
+
+/// Links in `content` to other sites.
+fn outgoing_links(content: &str) -> Vec<String> {
+    let own_host = Url::parse(&SITE_URL).ok().and_then(|url| url.host_str().map(str::to_string));
+    crate::content::markdown::links(content)
+        .into_iter()
+        .filter(|link| {
+            let host = Url::parse(link).ok().and_then(|url| url.host_str().map(str::to_string));
+            host.is_some() && host != own_host
+        })
+        .collect()
+}
+
+async fn send_pending() -> Result<(), ServerFnError> {
+    let Some(db) = service_client() else { return Ok(()) };
+    let posts: Vec<PublicPost> =
+        run(only_public(db.table("blog_posts").select("id,slug,content,updated_at")), "list posts for webmentions").await?;
+    let sent: Vec<SentMention> =
+        run(db.table("webmentions_sent").select("post_id,target,post_updated_at"), "list sent webmentions").await?;
+    let client = http_client();
+
+    for post in posts {
+        let source = absolute_url(&Route::BlogPostDetail { slug: post.slug.clone() }.to_string());
+        for target in due_targets(&post, &sent) {
+            let outcome = send(&client, &source, &target).await;
+            info!(
+                "Webmention for {} to {}: {}",
+                source,
+                target,
+                match (&outcome.error, outcome.status_code) {
+                    (Some(error), _) => error.clone(),
+                    (None, Some(status)) => format!("status {}", status),
+                    (None, None) => "no endpoint".to_string(),
+                }
+            );
+            let row = serde_json::json!({
+                "post_id": post.id,
+                "target": target,
+                "post_updated_at": post.updated_at,
+                "endpoint": outcome.endpoint,
+                "status_code": outcome.status_code,
+                "error": outcome.error,
+                "sent_at": chrono::Utc::now().to_rfc3339(),
+            });
+            let query = db.table("webmentions_sent").upsert(row.to_string()).on_conflict("post_id,target");
+            let _: serde_json::Value = run(query, "record sent webmention").await?;
+        }
+    }
+    Ok(())
+}
+
+/// The links of `post` to notify: those not yet notified of this version of
+/// it, and removed ones, once more, so the other side can update.
+fn due_targets(post: &PublicPost, sent: &[SentMention]) -> Vec<String> {
+    let version = parse_timestamp(&post.updated_at);
+    let previous: Vec<&SentMention> = sent.iter().filter(|sent| sent.post_id == post.id).collect();
+    let mut targets = outgoing_links(&post.content);
+    for sent in &previous {
+        if !targets.contains(&sent.target) {
+            targets.push(sent.target.clone());
+        }
+    }
+    targets.retain(|target| {
+        !previous.iter().any(|sent| &sent.target == target && parse_timestamp(&sent.post_updated_at) == version)
+    });
+    targets
+}
+
+async fn send(client: &reqwest::Client, source: &str, target: &str) -> SendOutcome {
+    let endpoint = match discover_endpoint(client, target).await {
+        Ok(Some(endpoint)) => endpoint,
+        Ok(None) => return SendOutcome::default(),
+        Err(error) => return SendOutcome { error: Some(error), ..Default::default() },
+    };
+    if !is_public_address(&endpoint) {
+        let error = format!("{} is not a public address", endpoint);
+        return SendOutcome { endpoint: Some(endpoint.to_string()), error: Some(error), ..Default::default() };
+    }
+    let response = client.post(endpoint.clone()).form(&[("source", source), ("target", target)]).send().await;
+    SendOutcome {
+        endpoint: Some(endpoint.to_string()),
+        status_code: response.as_ref().ok().map(|response| response.status().as_u16()),
+        error: response.err().map(|e| e.to_string()),
+    }
+}
+
+/// The Webmention endpoint `target` advertises: in a `Link` header, or else
+/// the first `<link>` or `<a>` with `rel="webmention"`.
+async fn discover_endpoint(client: &reqwest::Client, target: &str) -> Result<Option<Url>, String> {
+    let response = client.get(fetchable(target)?).send().await.map_err(|e| e.to_string())?;
+    let base = response.url().clone();
+
+    for value in response.headers().get_all(LINK) {
+        if let Some(href) = value.to_str().ok().and_then(webmention_link) {
+            return Ok(base.join(href).ok().filter(is_http));
+        }
+    }
+    if !response.status().is_success() || !is_html(&response) {
+        return Ok(None);
+    }
+
+    let page = Html::parse_document(&read_page(response).await?);
+    let endpoint = page
+        .select(&selector("link[rel][href], a[rel][href]"))
+        .find(|element| has_word(element.value().attr("rel"), "webmention"))
+        .and_then(|element| element.value().attr("href"))
+        .and_then(|href| base.join(href).ok())
+        .filter(is_http);
+    Ok(endpoint)
+}
+
+/// The URL of the `rel="webmention"` entry in a `Link` header.
+fn webmention_link(header: &str) -> Option<&str> {
+    header.split(',').find_map(|link| {
+        let (url, params) = link.trim().strip_prefix('<')?.split_once('>')?;
+        let rel = params.split(';').find_map(|param| param.trim().strip_prefix("rel="))?;
+        has_word(Some(rel.trim_matches('"')), "webmention").then_some(url)
+    })
+}
+
+fn has_word(list: Option<&str>, word: &str) -> bool {
+    list.is_some_and(|list| list.split_ascii_whitespace().any(|item| item.eq_ignore_ascii_case(word)))
+}
+
+// Receiving
+
+#[derive(Deserialize)]
+pub struct MentionRequest {
+    source: String,
+    target: String,
+}
+
+#[derive(Deserialize)]
+struct PostId {
+    id: i32,
+}
+
+/// `POST /webmention`
+pub async fn receive(parts: Parts, Form(request): Form<MentionRequest>) -> (StatusCode, String) {
+    let bad_request = |message: &str| (StatusCode::BAD_REQUEST, message.to_string());
+
+    // Requests whose address is unknown share one allowance.
+    let sender = client_ip::client_ip(&parts).map(|ip| ip.to_string()).unwrap_or_else(|| "unknown".to_string());
+    if !rate_limit::allow(&RECEIVE_LIMIT, &sender).await {
+        return (StatusCode::TOO_MANY_REQUESTS, "Too many mentions; please try again later".to_string());
+    }
+
+    let (Ok(source), Ok(target)) = (Url::parse(&request.source), Url::parse(&request.target)) else {
+        return bad_request("source and target must be URLs");
+    };
+    if !is_http(&source) || !is_http(&target) {
+        return bad_request("source and target must be http or https URLs");
+    }
+    if same_page(&source, &target) {
+        return bad_request("source and target must be different pages");
+    }
+    if !is_public_address(&source) {
+        return bad_request("source must be a public address");
+    }
+    let Some(slug) = post_slug(&target) else {
+        return bad_request("target is not a post on this site");
+    };
+    let Some(db) = service_client() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Webmentions are not accepted right now".to_string());
+    };
+
+    let post = only_public(create_server_client().table("blog_posts").select("id")).eq("slug", &slug);
+    let post_id = match run::<Vec<PostId>>(post, "look up mentioned post").await {
+        Ok(posts) => match posts.first() {
+            Some(post) => post.id,
+            None => return bad_request("target is not a post on this site"),
+        },
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+
+    // A mention that is already stored stays as it is until it's verified again.
+    let row = serde_json::json!({ "post_id": post_id, "source": request.source, "target": request.target });
+    match db.table("webmentions").insert(row.to_string()).execute().await {
+        Ok(response) if response.status().is_success() || response.status().as_u16() == 409 => {}
+        Ok(response) => {
+            warn!("Failed to store webmention from {}: status {}", request.source, response.status());
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to store the mention".to_string());
+        }
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+
+    info!("Webmention received from {} for '{}'", request.source, slug);
+    tokio::spawn(verify(request.source, request.target));
+    (StatusCode::ACCEPTED, "Accepted; the mention will appear once it has been verified".to_string())
+}
+
+/// The slug of the post at `target`, if it is one of this site's post URLs.
+fn post_slug(target: &Url) -> Option<String> {
+    let site = Url::parse(&SITE_URL).ok()?;
+    if target.host_str() != site.host_str() {
+        return None;
+    }
+    let slug = target.path().strip_prefix("/blog/")?.trim_end_matches('/');
+    (!slug.is_empty() && !slug.contains('/')).then(|| slug.to_string())
+}
+
+/// How a source page relates to the post, as stored.
+#[derive(Debug, Default, serde::Serialize)]
+struct Mention {
+    kind: MentionKind,
+    author_name: Option<String>,
+    author_url: Option<String>,
+    author_photo: Option<String>,
+    content: Option<String>,
+    url: Option<String>,
+    published_at: Option<String>,
+}
+
+/// Fetch `source` and keep, update or drop its mention of `target`.
+async fn verify(source: String, target: String) {
+    let _turn = VERIFYING.acquire().await.expect("verification semaphore closed");
+    let Some(db) = service_client() else { return };
+    let mention = db.table("webmentions").eq("source", &source).eq("target", &target);
+
+    match check_source(&http_client(), &source, &target).await {
+        Ok(Some(found)) => {
+            let mut row = serde_json::to_value(&found).unwrap_or_default();
+            row["status"] = "verified".into();
+            row["verified_at"] = chrono::Utc::now().to_rfc3339().into();
+            match run::<serde_json::Value>(mention.update(row.to_string()), "save webmention").await {
+                Ok(_) => info!("Verified {:?} webmention from {}", found.kind, source),
+                Err(e) => warn!("{}", e),
+            }
+        }
+        Ok(None) => {
+            info!("Dropping webmention from {}: it doesn't link to {}", source, target);
+            if let Err(e) = run::<serde_json::Value>(mention.delete(), "delete webmention").await {
+                warn!("{}", e);
+            }
+        }
+        // Most likely temporary; whatever is stored stays until the next try.
+        Err(e) => warn!("Could not verify webmention from {}: {}", source, e),
+    }
+}
+
+/// The mention of `target` on the page at `source`. `None` if the page is
+/// gone or no longer links to it.
+async fn check_source(client: &reqwest::Client, source: &str, target: &str) -> Result<Option<Mention>, String> {
+    let target = Url::parse(target).map_err(|e| e.to_string())?;
+    let response = client.get(fetchable(source)?).send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if matches!(status.as_u16(), 404 | 410) {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(format!("status {}", status));
+    }
+    let base = response.url().clone();
+
+    if !is_html(&response) {
+        let text = read_page(response).await?;
+        return Ok(text.contains(target.as_str()).then(Mention::default));
+    }
+    let page = Html::parse_document(&read_page(response).await?);
+    Ok(read_mention(&page, &base, &target))
+}
+
+/// Read the mention of `target` from a source page, using the h-entry that
+/// contains the link when there is one.
+fn read_mention(page: &Html, base: &Url, target: &Url) -> Option<Mention> {
+    let resolve = |href: &str| base.join(href).ok().filter(is_http);
+    let links_to_target = |element: &ElementRef| {
+        ["href", "src"]
+            .iter()
+            .filter_map(|attribute| element.value().attr(attribute))
+            .filter_map(resolve)
+            .any(|url| same_page(&url, target))
+    };
+
+    let link = page.select(&selector("[href], [src]")).find(links_to_target)?;
+    let Some(entry) = link.ancestors().filter_map(ElementRef::wrap).find(|element| has_class(element, "h-entry")) else {
+        return Some(Mention::default());
+    };
+
+    let kind = property(entry, ".u-in-reply-to, .u-like-of, .u-repost-of")
+        .into_iter()
+        .filter(links_to_target)
+        .find_map(|element| {
+            if has_class(&element, "u-like-of") {
+                Some(MentionKind::Like)
+            } else if has_class(&element, "u-repost-of") {
+                Some(MentionKind::Repost)
+            } else {
+                has_class(&element, "u-in-reply-to").then_some(MentionKind::Reply)
+            }
+        })
+        .unwrap_or_default();
+
+    let url_of = |element: &ElementRef, attribute: &str| element.value().attr(attribute).and_then(resolve).map(String::from);
+
+    let mut mention = Mention { kind, ..Default::default() };
+    if let Some(author) = property(entry, ".p-author, .u-author").first() {
+        if has_class(author, "h-card") {
+            let card = *author;
+            mention.author_name = property(card, ".p-name").first().map(text).or_else(|| Some(text(author)));
+            mention.author_url = property(card, ".u-url").first().and_then(|url| url_of(url, "href"));
+            mention.author_photo = property(card, ".u-photo").first().and_then(|photo| url_of(photo, "src"));
+        } else {
+            mention.author_name = Some(text(author));
+        }
+        mention.author_url = mention.author_url.or_else(|| url_of(author, "href"));
+        mention.author_name = mention.author_name.filter(|name| !name.is_empty());
+    }
+    mention.content = property(entry, ".e-content, .p-content")
+        .first()
+        .map(text)
+        .filter(|content| !content.is_empty())
+        .map(|content| match content.char_indices().nth(MAX_CONTENT_CHARS) {
+            Some((cut, _)) => format!("{}…", content[..cut].trim_end()),
+            None => content,
+        });
+    // An element that is both `u-url` and an item (say the author's h-card)
+    // is that item's URL, not the entry's.
+    mention.url = property(entry, ".u-url")
+        .iter()
+        .find(|url| !url.value().classes().any(|class| class.starts_with("h-")))
+        .and_then(|url| url_of(url, "href"));
+    mention.published_at = property(entry, ".dt-published")
+        .first()
+        .map(|published| published.value().attr("datetime").map(str::to_string).unwrap_or_else(|| text(published)))
+        .and_then(|published| parse_timestamp(&published))
+        .map(|published| published.to_rfc3339());
+    Some(mention)
+}
+
+fn has_class(element: &ElementRef, class: &str) -> bool {
+    element.value().classes().any(|name| name == class)
+}
+
+/// The elements matching `css` that belong to `item` itself, rather than to
+/// a microformat nested inside it.
+fn property<'a>(item: ElementRef<'a>, css: &str) -> Vec<ElementRef<'a>> {
+    item.select(&selector(css))
+        .filter(|element| {
+            element
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .take_while(|ancestor| ancestor.id() != item.id())
+                .all(|ancestor| !ancestor.value().classes().any(|class| class.starts_with("h-")))
+        })
+        .collect()
+}
+
+/// An element's text with whitespace collapsed.
+fn text(element: &ElementRef) -> String {
+    element.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::http::header;
+    use axum::response::IntoResponse;
+    use axum::routing::{get, post};
+    use axum::Router;
+
+    use super::*;
+
+    /// The webmention client, once the settings it reads are in place.
+    fn client() -> reqwest::Client {
+        crate::api::env::use_test_config();
+        http_client()
+    }
+
+    /// Serve `router` on a local port standing in for another site, and
+    /// return its origin.
+    async fn stand_in(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://localhost:{}", port)
+    }
+
+    fn html(body: &'static str) -> impl IntoResponse {
+        ([(header::CONTENT_TYPE, "text/html; charset=utf-8")], body)
+    }
+
+    fn mention_of(page: &str, target: &str) -> Option<Mention> {
+        let base = Url::parse("https://alice.example/notes/1").unwrap();
+        read_mention(&Html::parse_document(page), &base, &Url::parse(target).unwrap())
+    }
+
+    const TARGET: &str = "https://site.test/blog/hello";
+
+    #[tokio::test]
+    async fn discovers_the_endpoint_in_a_link_header() {
+        let origin = stand_in(Router::new().route(
+            "/post",
+            get(|| async {
+                let links = r#"<https://other.example/>; rel="me", </mentions>; rel="webmention""#;
+                ([(header::LINK, links)], html("<p>Hi</p>"))
+            }),
+        ))
+        .await;
+        let endpoint = discover_endpoint(&client(), &format!("{}/post", origin)).await.unwrap();
+        assert_eq!(endpoint.map(String::from), Some(format!("{}/mentions", origin)));
+    }
+
+    #[tokio::test]
+    async fn discovers_the_endpoint_in_a_link_element() {
+        let origin = stand_in(Router::new().route(
+            "/post",
+            get(|| async {
+                html(r#"<head><link rel="stylesheet" href="/a.css"><link rel="webmention" href="/wm"></head>"#)
+            }),
+        ))
+        .await;
+        let endpoint = discover_endpoint(&client(), &format!("{}/post", origin)).await.unwrap();
+        assert_eq!(endpoint.map(String::from), Some(format!("{}/wm", origin)));
+    }
+
+    #[tokio::test]
+    async fn discovers_the_endpoint_in_an_anchor() {
+        let origin = stand_in(Router::new().route(
+            "/post",
+            get(|| async {
+                html(r#"<body><a rel="nofollow webmention" href="https://hub.example/wm?site=1">wm</a></body>"#)
+            }),
+        ))
+        .await;
+        let endpoint = discover_endpoint(&client(), &format!("{}/post", origin)).await.unwrap();
+        assert_eq!(endpoint.map(String::from), Some("https://hub.example/wm?site=1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn finds_no_endpoint_on_a_page_without_one() {
+        let origin = stand_in(Router::new().route("/post", get(|| async { html("<a href=\"/wm\">wm</a>") }))).await;
+        assert_eq!(discover_endpoint(&client(), &format!("{}/post", origin)).await, Ok(None));
+    }
+
+    #[test]
+    fn reads_a_like() {
+        let page = r#"<div class="h-entry">
+            <a class="p-author h-card" href="/"><img class="u-photo" src="/me.jpg"><span class="p-name">Alice</span></a>
+            likes <a class="u-like-of" href="https://site.test/blog/hello/">Hello</a>
+            <a class="u-url" href="/notes/1"><time class="dt-published" datetime="2024-05-01T12:00:00Z">May 1</time></a>
+        </div>"#;
+        let mention = mention_of(page, TARGET).unwrap();
+        assert_eq!(mention.kind, MentionKind::Like);
+        assert_eq!(mention.author_name.as_deref(), Some("Alice"));
+        assert_eq!(mention.author_url.as_deref(), Some("https://alice.example/"));
+        assert_eq!(mention.author_photo.as_deref(), Some("https://alice.example/me.jpg"));
+        assert_eq!(mention.url.as_deref(), Some("https://alice.example/notes/1"));
+        assert_eq!(mention.published_at.as_deref(), Some("2024-05-01T12:00:00+00:00"));
+    }
+
+    #[test]
+    fn reads_a_repost() {
+        let page = r#"<article class="h-entry">
+            <span class="p-author">Bob</span> reposted
+            <a class="u-repost-of" href="https://site.test/blog/hello#top">Hello</a>
+        </article>"#;
+        let mention = mention_of(page, TARGET).unwrap();
+        assert_eq!(mention.kind, MentionKind::Repost);
+        assert_eq!(mention.author_name.as_deref(), Some("Bob"));
+    }
+
+    #[test]
+    fn reads_a_reply() {
+        let page = r#"<article class="h-entry">
+            <p>In reply to <a class="u-in-reply-to" href="https://site.test/blog/hello">Hello</a></p>
+            <div class="e-content"><p>Great   post,
+                thanks!</p></div>
+        </article>"#;
+        let mention = mention_of(page, TARGET).unwrap();
+        assert_eq!(mention.kind, MentionKind::Reply);
+        assert_eq!(mention.content.as_deref(), Some("Great post, thanks!"));
+    }
+
+    #[test]
+    fn reads_a_plain_link_as_a_mention() {
+        let page = r#"<p>See <a href="https://site.test/blog/hello">this</a>.</p>"#;
+        assert_eq!(mention_of(page, TARGET).unwrap().kind, MentionKind::Mention);
+        // Replying to something else while linking to the post is still just a mention.
+        let page = r#"<div class="h-entry"><a class="u-in-reply-to" href="https://elsewhere.example/">x</a>
+            <a href="https://site.test/blog/hello">Hello</a></div>"#;
+        assert_eq!(mention_of(page, TARGET).unwrap().kind, MentionKind::Mention);
+    }
+
+    #[tokio::test]
+    async fn drops_a_source_that_does_not_link_back() {
+        let origin = stand_in(
+            Router::new()
+                .route("/unrelated", get(|| async { html(r#"<a href="https://site.test/blog/other">Other</a>"#) }))
+                .route("/gone", get(|| async { StatusCode::GONE }))
+                .route("/linked", get(|| async { html(r#"<a href="https://site.test/blog/hello">Hello</a>"#) })),
+        )
+        .await;
+        let client = client();
+        for (path, links_back) in [("/unrelated", false), ("/gone", false), ("/linked", true)] {
+            let mention = check_source(&client, &format!("{}{}", origin, path), TARGET).await.unwrap();
+            assert_eq!(mention.is_some(), links_back, "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn notifies_a_removed_link() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        let origin = stand_in(
+            Router::new()
+                .route("/article", get(|| async { ([(header::LINK, r#"</webmention>; rel="webmention""#)], "") }))
+                .route(
+                    "/webmention",
+                    post(move |Form(form): Form<MentionRequest>| async move {
+                        log.lock().unwrap().push((form.source, form.target));
+                        StatusCode::ACCEPTED
+                    }),
+                ),
+        )
+        .await;
+        let removed = format!("{}/article", origin);
+        let post = PublicPost {
+            id: 1,
+            slug: "hello".to_string(),
+            content: "Now only [this](https://kept.example/) is linked.".to_string(),
+            updated_at: "2024-05-02T00:00:00+00:00".to_string(),
+        };
+        let sent = |target: &str, version: &str| SentMention {
+            post_id: 1,
+            target: target.to_string(),
+            post_updated_at: version.to_string(),
+        };
+        let history =
+            [sent(&removed, "2024-05-01T00:00:00+00:00"), sent("https://kept.example/", "2024-05-02T00:00:00+00:00")];
+
+        // The kept link was already told about this version; the removed one wasn't.
+        assert_eq!(due_targets(&post, &history), vec![removed.clone()]);
+
+        let outcome = send(&client(), TARGET, &removed).await;
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.status_code, Some(202));
+        assert_eq!(*received.lock().unwrap(), vec![(TARGET.to_string(), removed)]);
+    }
+
+    #[test]
+    fn refuses_private_addresses() {
+        let private = [
+            "127.0.0.1",
+            "10.1.2.3",
+            "192.168.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "::ffff:127.0.0.1",
+        ];
+        for ip in private {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{} should be private", ip);
+        }
+        for ip in ["93.184.216.34", "2606:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{} should be public", ip);
+        }
+    }
+}
@@ -1,10 +1,27 @@
 use dioxus::prelude::*;
+use crate::api::env::absolute_url;
+use crate::components::{seo::SITE_NAME, Seo};
+use crate::Route;
+use serde_json::json;
 const PHOTO: Asset = asset!("/assets/about_page_min.JPG");
 
 
 #[component]
 pub fn About() -> Element {
     rsx! {
+        Seo {
+            title: "About",
+            description: "Who is Dylan? A finance professional with a passion for technology, software development and 3D printing.",
+            path: Route::About {}.to_string(),
+            og_type: "profile",
+            json_ld: json!({
+                "@context": "https://schema.org",
+                "@type": "Person",
+                "name": SITE_NAME,
+                "url": absolute_url(&Route::Home {}.to_string()),
+                "jobTitle": "Software Developer & Mortgage Professional",
+            }),
+        }
         div { class: "max-w-6xl mx-auto",
             div { class: "flex flex-col md:flex-row gap-8 mb-8",
                 // Profile photo container (left side)
@@ -0,0 +1,719 @@
+use dioxus::prelude::*;
+use crate::api::auth::access_token;
+use crate::api::blog_admin::{
+    admin_get_post, admin_list_posts, admin_list_series, create_preview_link, delete_post, save_post, save_series,
+    set_post_published, AdminPostSummary, PostDraft, PublicationStatus,
+};
+use crate::api::comments::pending_comment_count;
+use crate::api::images::{upload_image, ImageBucket, MAX_IMAGE_BYTES};
+use crate::api::preview::PreviewLink;
+use crate::api::series::BlogSeries;
+use crate::api::tag_slug;
+use crate::components::{Button, ButtonScheme, ButtonSize, Modal};
+use crate::content::render_markdown;
+use crate::views::routes::protected;
+use crate::Route;
+
+const INPUT_CLASS: &str = "w-full px-3 py-2 rounded-md border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-indigo-500";
+const LABEL_CLASS: &str = "block text-sm font-medium mb-1";
+
+/// The session only exists in the browser, so the dashboard renders a
+/// placeholder on the server and loads once mounted. That also keeps the
+/// first client render identical to the server's for hydration.
+#[component]
+pub(super) fn ClientOnly(children: Element) -> Element {
+    let mut mounted = use_signal(|| false);
+    use_effect(move || mounted.set(true));
+
+    if mounted() {
+        children
+    } else {
+        rsx! {
+            div { class: "flex justify-center py-20",
+                div { class: "animate-spin rounded-full h-12 w-12 border-t-2 border-b-2 border-indigo-600" }
+            }
+        }
+    }
+}
+
+/// `published_at` as a `datetime-local` input value. The dashboard works in
+/// UTC so the date means the same thing to every author.
+fn to_datetime_local(published_at: Option<&str>) -> String {
+    published_at
+        .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| at.with_timezone(&chrono::Utc).format("%Y-%m-%dT%H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn from_datetime_local(value: &str) -> Option<String> {
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+        .ok()
+        .map(|at| at.and_utc().to_rfc3339())
+}
+
+fn status_badge(status: PublicationStatus) -> Element {
+    let (label, class) = match status {
+        PublicationStatus::Draft => ("Draft", "bg-gray-200 text-gray-700"),
+        PublicationStatus::Scheduled => ("Scheduled", "bg-yellow-100 text-yellow-800"),
+        PublicationStatus::Published => ("Published", "bg-green-100 text-green-800"),
+    };
+    rsx! {
+        span { class: "px-2 py-1 text-xs rounded-full {class}", "{label}" }
+    }
+}
+
+pub(super) fn error_banner(message: String) -> Element {
+    rsx! {
+        div { class: "p-4 mb-6 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-red-800 dark:text-red-200 rounded-lg",
+            "{message}"
+        }
+    }
+}
+
+/// `/admin/blog`: every post, drafts included.
+#[component]
+pub fn AdminBlog() -> Element {
+    use_effect(|| protected(Route::Login {}, Route::AdminBlog {}));
+
+    rsx! {
+        div { class: "max-w-6xl mx-auto",
+            div { class: "flex items-center justify-between mb-8",
+                h1 { class: "text-3xl font-bold", "Blog posts" }
+                div { class: "flex items-center gap-4",
+                    Link { to: Route::AdminComments {}, class: "text-sm text-indigo-600 hover:underline", "Comments" }
+                    Link { to: Route::AdminInbox {}, class: "text-sm text-indigo-600 hover:underline", "Inbox" }
+                    Link {
+                        to: Route::AdminBlogNew {},
+                        class: "rounded-md bg-indigo-600 px-4 py-2 text-sm font-medium text-white hover:bg-indigo-700",
+                        "New post"
+                    }
+                }
+            }
+            ClientOnly {
+                PendingComments {}
+                AdminPostList {}
+            }
+        }
+    }
+}
+
+/// A notice while comments are waiting for moderation.
+#[component]
+fn PendingComments() -> Element {
+    let pending = use_resource(|| pending_comment_count(access_token().unwrap_or_default()));
+
+    let count = match *pending.read() {
+        Some(Ok(count)) => count,
+        _ => 0,
+    };
+    match count {
+        0 => rsx! {},
+        count => rsx! {
+            div { class: "flex items-center justify-between gap-4 p-4 mb-6 bg-amber-50 dark:bg-amber-900/20 border border-amber-200 dark:border-amber-800 rounded-lg",
+                p {
+                    if count == 1 { "1 new comment is" } else { "{count} new comments are" }
+                    " waiting for moderation."
+                }
+                Link { to: Route::AdminComments {}, class: "text-sm font-medium text-indigo-600 hover:underline", "Review" }
+            }
+        },
+    }
+}
+
+#[component]
+fn AdminPostList() -> Element {
+    let mut posts = use_resource(|| admin_list_posts(access_token().unwrap_or_default()));
+    let mut pending_delete = use_signal::<Option<AdminPostSummary>>(|| None);
+    let mut action_error = use_signal::<Option<String>>(|| None);
+
+    let toggle_published = move |post: AdminPostSummary| {
+        spawn(async move {
+            let token = access_token().unwrap_or_default();
+            match set_post_published(token, post.id, !post.is_published).await {
+                Ok(_) => posts.restart(),
+                Err(e) => action_error.set(Some(format!("Failed to update '{}': {}", post.title, e))),
+            }
+        });
+    };
+
+    let confirm_delete = move |_| {
+        let Some(post) = pending_delete() else { return };
+        spawn(async move {
+            let token = access_token().unwrap_or_default();
+            match delete_post(token, post.id).await {
+                Ok(()) => posts.restart(),
+                Err(e) => action_error.set(Some(format!("Failed to delete '{}': {}", post.title, e))),
+            }
+            pending_delete.set(None);
+        });
+    };
+
+    let list = match &*posts.read() {
+        None => return rsx! { p { class: "text-gray-500", "Loading posts..." } },
+        Some(Err(e)) => return error_banner(format!("Failed to load posts: {}", e)),
+        Some(Ok(list)) => list.clone(),
+    };
+
+    rsx! {
+        if let Some(err) = action_error() {
+            {error_banner(err)}
+        }
+        if list.is_empty() {
+            p { class: "text-gray-500 dark:text-gray-400 py-12 text-center", "No posts yet." }
+        } else {
+            table { class: "w-full text-left text-sm",
+                thead { class: "border-b border-gray-300 dark:border-gray-700 text-gray-500",
+                    tr {
+                        th { class: "py-2", "Title" }
+                        th { class: "py-2", "Status" }
+                        th { class: "py-2", "Published" }
+                        th { class: "py-2 text-right", "Actions" }
+                    }
+                }
+                tbody {
+                    for post in list {
+                        tr { key: "{post.id}", class: "border-b border-gray-200 dark:border-gray-800",
+                            td { class: "py-3 pr-4",
+                                Link {
+                                    to: Route::AdminBlogEdit { id: post.id },
+                                    class: "font-medium hover:text-indigo-600",
+                                    "{post.title}"
+                                }
+                                div { class: "text-xs text-gray-500", "/blog/{post.slug}" }
+                            }
+                            td { class: "py-3 pr-4", {status_badge(post.status())} }
+                            td { class: "py-3 pr-4 text-gray-500",
+                                "{to_datetime_local(post.published_at.as_deref()).replace('T', \" \")}"
+                            }
+                            td { class: "py-3 text-right whitespace-nowrap",
+                                Button {
+                                    text: if post.is_published { "Unpublish" } else { "Publish" },
+                                    button_scheme: Some(if post.is_published { ButtonScheme::Warn } else { ButtonScheme::Success }),
+                                    button_size: Some(ButtonSize::ExtraSmall),
+                                    on_click: {
+                                        let post = post.clone();
+                                        move |_| toggle_published(post.clone())
+                                    },
+                                }
+                                Button {
+                                    text: "Delete",
+                                    button_scheme: Some(ButtonScheme::Danger),
+                                    button_size: Some(ButtonSize::ExtraSmall),
+                                    on_click: {
+                                        let post = post.clone();
+                                        move |_| pending_delete.set(Some(post.clone()))
+                                    },
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Modal {
+            title: "Delete post?",
+            is_open: pending_delete.read().is_some(),
+            on_close: move |_| pending_delete.set(None),
+            body_children: rsx! {
+                p {
+                    "\"{pending_delete.read().as_ref().map(|post| post.title.clone()).unwrap_or_default()}\" will be removed permanently."
+                }
+            },
+            footer_children: rsx! {
+                Button {
+                    text: "Delete",
+                    button_scheme: Some(ButtonScheme::Danger),
+                    on_click: confirm_delete,
+                }
+                Button {
+                    text: "Cancel",
+                    button_scheme: Some(ButtonScheme::Outline),
+                    on_click: move |_| pending_delete.set(None),
+                }
+            },
+        }
+    }
+}
+
+/// `/admin/blog/new`
+#[component]
+pub fn AdminBlogNew() -> Element {
+    use_effect(|| protected(Route::Login {}, Route::AdminBlogNew {}));
+
+    rsx! {
+        div { class: "max-w-7xl mx-auto",
+            ClientOnly { PostEditor { initial: PostDraft::default() } }
+        }
+    }
+}
+
+/// `/admin/blog/:id`
+#[component]
+pub fn AdminBlogEdit(id: ReadOnlySignal<i32>) -> Element {
+    use_effect(move || protected(Route::Login {}, Route::AdminBlogEdit { id: id() }));
+
+    rsx! {
+        div { class: "max-w-7xl mx-auto",
+            ClientOnly { LoadedPostEditor { key: "{id}", id } }
+        }
+    }
+}
+
+#[component]
+fn LoadedPostEditor(id: ReadOnlySignal<i32>) -> Element {
+    let post = use_resource(move || admin_get_post(access_token().unwrap_or_default(), id()));
+
+    let post = match &*post.read() {
+        None => return rsx! { p { class: "text-gray-500", "Loading post..." } },
+        Some(Err(e)) => return error_banner(format!("Failed to load post: {}", e)),
+        Some(Ok(post)) => post.clone(),
+    };
+    rsx! { PostEditor { initial: post } }
+}
+
+/// Form and live preview for one post.
+#[component]
+fn PostEditor(initial: PostDraft) -> Element {
+    let is_new = initial.id.is_none();
+    let mut tags_text = use_signal(|| initial.tags.join(", "));
+    let mut draft = use_signal(|| initial);
+    // New posts take their slug from the title until it's edited by hand.
+    let mut slug_edited = use_signal(move || !is_new);
+    let mut saving = use_signal(|| false);
+    let mut status = use_signal::<Option<Result<String, String>>>(|| None);
+    let mut preview_link = use_signal::<Option<PreviewLink>>(|| None);
+    let mut change_note = use_signal(String::new);
+    let mut uploading = use_signal(|| false);
+
+    let save = move |_| {
+        let mut post = draft();
+        post.tags = tags_text()
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect();
+        if let Err(e) = post.validate() {
+            status.set(Some(Err(e)));
+            return;
+        }
+
+        saving.set(true);
+        spawn(async move {
+            match save_post(access_token().unwrap_or_default(), post, change_note()).await {
+                Ok(saved) => {
+                    let id = saved.id;
+                    draft.set(saved);
+                    change_note.set(String::new());
+                    status.set(Some(Ok("Saved".to_string())));
+                    if let (true, Some(id)) = (is_new, id) {
+                        navigator().replace(Route::AdminBlogEdit { id });
+                    }
+                }
+                Err(e) => status.set(Some(Err(e.to_string()))),
+            }
+            saving.set(false);
+        });
+    };
+
+    let share_preview = move |_| {
+        let Some(id) = draft.read().id else { return };
+        spawn(async move {
+            match create_preview_link(access_token().unwrap_or_default(), id).await {
+                Ok(link) => preview_link.set(Some(link)),
+                Err(e) => status.set(Some(Err(e.to_string()))),
+            }
+        });
+    };
+
+    // Becomes the cover, with its variants, once the server has converted it.
+    let upload_cover = move |evt: FormEvent| async move {
+        use base64::Engine;
+
+        let Some(files) = evt.files() else { return };
+        let Some(name) = files.files().into_iter().next() else { return };
+        let Some(bytes) = files.read_file(&name).await else {
+            status.set(Some(Err(format!("Could not read {}", name))));
+            return;
+        };
+        if bytes.len() > MAX_IMAGE_BYTES {
+            status.set(Some(Err(format!("{} is larger than {} MB", name, MAX_IMAGE_BYTES / (1024 * 1024)))));
+            return;
+        }
+
+        uploading.set(true);
+        let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+        match upload_image(access_token().unwrap_or_default(), ImageBucket::Blog, data).await {
+            Ok(variants) => {
+                let mut post = draft.write();
+                post.cover_image = Some(variants.src.clone());
+                post.cover_image_variants = Some(variants);
+                status.set(Some(Ok(format!("Uploaded {}", name))));
+            }
+            Err(e) => status.set(Some(Err(format!("Failed to upload {}: {}", name, e)))),
+        }
+        uploading.set(false);
+    };
+
+    let preview = use_memo(move || render_markdown(&draft.read().content));
+    let post = draft.read().clone();
+
+    rsx! {
+        div { class: "flex flex-wrap items-center justify-between gap-4 mb-6",
+            div { class: "flex items-center gap-4",
+                Link { to: Route::AdminBlog {}, class: "text-indigo-600 hover:underline", "← All posts" }
+                h1 { class: "text-2xl font-bold", if is_new { "New post" } else { "Edit post" } }
+            }
+            div { class: "flex items-center gap-4",
+                match status() {
+                    Some(Ok(message)) => rsx! { span { class: "text-sm text-green-600", "{message}" } },
+                    Some(Err(message)) => rsx! { span { class: "text-sm text-red-600", "{message}" } },
+                    None => rsx! {},
+                }
+                if !is_new {
+                    {status_badge(post.status())}
+                }
+                if post.status() == PublicationStatus::Published && !is_new {
+                    Link {
+                        to: Route::BlogPostDetail { slug: post.slug.clone() },
+                        class: "text-sm text-indigo-600 hover:underline",
+                        "View post"
+                    }
+                } else if !is_new {
+                    Button {
+                        text: "Share preview",
+                        button_scheme: Some(ButtonScheme::Outline),
+                        on_click: share_preview,
+                    }
+                }
+                if let (false, Some(id)) = (is_new, post.id) {
+                    Link {
+                        to: Route::AdminBlogHistory { id },
+                        class: "text-sm text-indigo-600 hover:underline",
+                        "History"
+                    }
+                }
+                input {
+                    class: "px-3 py-2 rounded-md border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-800 text-sm w-56",
+                    placeholder: "Change note (optional)",
+                    aria_label: "Change note",
+                    value: "{change_note}",
+                    oninput: move |evt| change_note.set(evt.value()),
+                }
+                Button {
+                    text: if saving() { "Saving..." } else { "Save" },
+                    disabled: saving(),
+                    on_click: save,
+                }
+            }
+        }
+        if let Some(link) = preview_link() {
+            div { class: "p-4 mb-6 bg-indigo-50 dark:bg-indigo-900/20 border border-indigo-200 dark:border-indigo-800 rounded-lg",
+                label { class: LABEL_CLASS, r#for: "preview-link",
+                    "Anyone with this link can read the post until {to_datetime_local(Some(&link.expires_at)).replace('T', \" \")} UTC"
+                }
+                input {
+                    id: "preview-link",
+                    class: INPUT_CLASS,
+                    readonly: true,
+                    value: "{link.url}",
+                    onfocus: move |_| {
+                        document::eval("document.getElementById('preview-link').select()");
+                    },
+                }
+            }
+        }
+        div { class: "grid gap-8 lg:grid-cols-2",
+            // Editor
+            div { class: "space-y-4",
+                div {
+                    label { class: LABEL_CLASS, r#for: "post-title", "Title" }
+                    input {
+                        id: "post-title",
+                        class: INPUT_CLASS,
+                        value: "{post.title}",
+                        oninput: move |evt| {
+                            let title = evt.value();
+                            let mut draft = draft.write();
+                            if !slug_edited() {
+                                // Same normalization as tag slugs: lowercase words joined by dashes.
+                                draft.slug = tag_slug(&title);
+                            }
+                            draft.title = title;
+                        },
+                    }
+                }
+                div {
+                    label { class: LABEL_CLASS, r#for: "post-slug", "Slug" }
+                    input {
+                        id: "post-slug",
+                        class: INPUT_CLASS,
+                        value: "{post.slug}",
+                        oninput: move |evt| {
+                            slug_edited.set(true);
+                            draft.write().slug = evt.value();
+                        },
+                    }
+                }
+                div {
+                    label { class: LABEL_CLASS, r#for: "post-excerpt", "Excerpt" }
+                    textarea {
+                        id: "post-excerpt",
+                        class: INPUT_CLASS,
+                        rows: 2,
+                        value: "{post.excerpt}",
+                        oninput: move |evt| draft.write().excerpt = evt.value(),
+                    }
+                }
+                div { class: "grid gap-4 sm:grid-cols-2",
+                    div {
+                        label { class: LABEL_CLASS, r#for: "post-tags", "Tags (comma separated)" }
+                        input {
+                            id: "post-tags",
+                            class: INPUT_CLASS,
+                            value: "{tags_text}",
+                            oninput: move |evt| tags_text.set(evt.value()),
+                        }
+                    }
+                    div {
+                        label { class: LABEL_CLASS, r#for: "post-cover", "Cover image URL" }
+                        input {
+                            id: "post-cover",
+                            class: INPUT_CLASS,
+                            value: "{post.cover_image.clone().unwrap_or_default()}",
+                            oninput: move |evt| draft.write().cover_image = Some(evt.value()),
+                        }
+                        label { class: "mt-2 flex items-center gap-2 text-sm text-gray-600 dark:text-gray-400",
+                            if uploading() { "Uploading..." } else { "or upload:" }
+                            input {
+                                r#type: "file",
+                                accept: "image/jpeg,image/png,image/webp,image/gif",
+                                disabled: uploading(),
+                                class: "text-sm",
+                                onchange: upload_cover,
+                            }
+                        }
+                    }
+                }
+                SeriesPicker { draft }
+                div {
+                    label { class: LABEL_CLASS, r#for: "post-content", "Content (Markdown)" }
+                    textarea {
+                        id: "post-content",
+                        class: "{INPUT_CLASS} font-mono text-sm",
+                        rows: 24,
+                        value: "{post.content}",
+                        oninput: move |evt| draft.write().content = evt.value(),
+                    }
+                }
+                div { class: "grid gap-4 sm:grid-cols-2 items-end",
+                    div {
+                        label { class: LABEL_CLASS, r#for: "post-published-at", "Publish at (UTC)" }
+                        input {
+                            id: "post-published-at",
+                            r#type: "datetime-local",
+                            class: INPUT_CLASS,
+                            value: "{to_datetime_local(post.published_at.as_deref())}",
+                            oninput: move |evt| draft.write().published_at = from_datetime_local(&evt.value()),
+                        }
+                    }
+                    label { class: "flex items-center gap-2 py-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: post.is_published,
+                            onchange: move |evt| draft.write().is_published = evt.checked(),
+                        }
+                        "Published"
+                    }
+                }
+                p { class: "text-xs text-gray-500",
+                    "A published post dated in the future stays hidden until that time."
+                }
+            }
+            // Live preview
+            div {
+                h2 { class: "text-sm font-medium uppercase tracking-wide text-gray-500 mb-2", "Preview" }
+                article { class: "prose dark:prose-invert max-w-none p-6 bg-white dark:bg-gray-800 rounded-lg shadow min-h-[20rem]",
+                    h1 { "{post.title}" }
+                    div { dangerous_inner_html: "{preview}" }
+                }
+            }
+        }
+    }
+}
+
+/// Series membership for the post in `draft`, and a form to create or
+/// rename the series themselves.
+#[component]
+fn SeriesPicker(draft: Signal<PostDraft>) -> Element {
+    let mut series_list = use_resource(|| admin_list_series(access_token().unwrap_or_default()));
+    // The series open in the form; one without an id is new.
+    let mut editing = use_signal::<Option<BlogSeries>>(|| None);
+    let mut slug_edited = use_signal(|| false);
+    let mut form_error = use_signal::<Option<String>>(|| None);
+    let mut saving = use_signal(|| false);
+
+    let series_id = draft.read().series_id;
+    let series_part = draft.read().series_part;
+    let all_series = match &*series_list.read() {
+        Some(Ok(list)) => list.clone(),
+        _ => Vec::new(),
+    };
+    let current = all_series.iter().find(|series| series.id == series_id).cloned();
+
+    let save = move |_| {
+        let Some(series) = editing() else { return };
+        if let Err(e) = series.validate() {
+            form_error.set(Some(e));
+            return;
+        }
+
+        saving.set(true);
+        spawn(async move {
+            match save_series(access_token().unwrap_or_default(), series).await {
+                Ok(saved) => {
+                    draft.write().series_id = saved.id;
+                    editing.set(None);
+                    series_list.restart();
+                }
+                Err(e) => form_error.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+
+    rsx! {
+        div { class: "grid gap-4 sm:grid-cols-[1fr_8rem] items-end",
+            div {
+                label { class: LABEL_CLASS, r#for: "post-series", "Series" }
+                div { class: "flex gap-2",
+                    select {
+                        id: "post-series",
+                        class: INPUT_CLASS,
+                        onchange: move |evt| {
+                            let mut draft = draft.write();
+                            draft.series_id = evt.value().parse().ok();
+                            // Joining another series puts the post at its end.
+                            draft.series_part = None;
+                        },
+                        option { value: "", selected: series_id.is_none(), "None" }
+                        for series in all_series {
+                            option {
+                                value: "{series.id.unwrap_or_default()}",
+                                selected: series.id == series_id,
+                                "{series.title}"
+                            }
+                        }
+                    }
+                    if let Some(series) = current {
+                        Button {
+                            text: "Edit",
+                            button_scheme: Some(ButtonScheme::Outline),
+                            on_click: move |_| {
+                                slug_edited.set(true);
+                                form_error.set(None);
+                                editing.set(Some(series.clone()));
+                            },
+                        }
+                    }
+                    Button {
+                        text: "New",
+                        button_scheme: Some(ButtonScheme::Outline),
+                        on_click: move |_| {
+                            slug_edited.set(false);
+                            form_error.set(None);
+                            editing.set(Some(BlogSeries::default()));
+                        },
+                    }
+                }
+            }
+            div {
+                label { class: LABEL_CLASS, r#for: "post-series-part", "Part" }
+                input {
+                    id: "post-series-part",
+                    r#type: "number",
+                    min: 1,
+                    class: INPUT_CLASS,
+                    disabled: series_id.is_none(),
+                    placeholder: "Last",
+                    value: "{series_part.map(|part| part.to_string()).unwrap_or_default()}",
+                    oninput: move |evt| draft.write().series_part = evt.value().parse().ok(),
+                }
+            }
+        }
+        if let Some(Err(e)) = &*series_list.read() {
+            p { class: "text-xs text-red-600", "Failed to load series: {e}" }
+        }
+        Modal {
+            title: if editing().is_some_and(|series| series.id.is_some()) { "Edit series" } else { "New series" },
+            is_open: editing().is_some(),
+            on_close: move |_| editing.set(None),
+            body_children: rsx! {
+                if let Some(series) = editing() {
+                    div { class: "space-y-4",
+                        if let Some(message) = form_error() {
+                            p { class: "text-sm text-red-600", "{message}" }
+                        }
+                        div {
+                            label { class: LABEL_CLASS, r#for: "series-title", "Title" }
+                            input {
+                                id: "series-title",
+                                class: INPUT_CLASS,
+                                value: "{series.title}",
+                                oninput: move |evt| {
+                                    let title = evt.value();
+                                    if let Some(series) = editing.write().as_mut() {
+                                        if !slug_edited() {
+                                            series.slug = tag_slug(&title);
+                                        }
+                                        series.title = title;
+                                    }
+                                },
+                            }
+                        }
+                        div {
+                            label { class: LABEL_CLASS, r#for: "series-slug", "Slug" }
+                            input {
+                                id: "series-slug",
+                                class: INPUT_CLASS,
+                                value: "{series.slug}",
+                                oninput: move |evt| {
+                                    slug_edited.set(true);
+                                    if let Some(series) = editing.write().as_mut() {
+                                        series.slug = evt.value();
+                                    }
+                                },
+                            }
+                        }
+                        div {
+                            label { class: LABEL_CLASS, r#for: "series-description", "Description" }
+                            textarea {
+                                id: "series-description",
+                                class: INPUT_CLASS,
+                                rows: 3,
+                                value: "{series.description}",
+                                oninput: move |evt| {
+                                    if let Some(series) = editing.write().as_mut() {
+                                        series.description = evt.value();
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+            },
+            footer_children: rsx! {
+                Button {
+                    text: if saving() { "Saving..." } else { "Save series" },
+                    disabled: saving(),
+                    on_click: save,
+                }
+                Button {
+                    text: "Cancel",
+                    button_scheme: Some(ButtonScheme::Outline),
+                    on_click: move |_| editing.set(None),
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use dioxus::prelude::*;
+use crate::api::auth::access_token;
+use crate::api::revisions::{diff_revisions, list_revisions, restore_revision, DiffKind, DiffLine, RevisionSummary};
+use crate::components::{Button, ButtonScheme, ButtonSize, Modal};
+use crate::views::routes::protected;
+use crate::Route;
+
+use super::admin_blog::{error_banner, ClientOnly};
+
+/// Unchanged lines kept around each change; longer unchanged runs fold.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// A row of the rendered diff.
+enum DiffRow {
+    Line(DiffLine),
+    Folded(usize),
+}
+
+/// Fold unchanged runs that are more than `DIFF_CONTEXT_LINES` away from any
+/// change.
+fn fold_unchanged(lines: Vec<DiffLine>) -> Vec<DiffRow> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.kind != DiffKind::Unchanged)
+        .map(|(i, _)| i)
+        .collect();
+    let near_change = |i: usize| changed.iter().any(|&c| c.abs_diff(i) <= DIFF_CONTEXT_LINES);
+
+    let mut rows = Vec::new();
+    let mut folded = 0;
+    for (i, line) in lines.into_iter().enumerate() {
+        if near_change(i) {
+            if folded > 0 {
+                rows.push(DiffRow::Folded(folded));
+                folded = 0;
+            }
+            rows.push(DiffRow::Line(line));
+        } else {
+            folded += 1;
+        }
+    }
+    if folded > 0 {
+        rows.push(DiffRow::Folded(folded));
+    }
+    rows
+}
+
+/// `/admin/blog/:id/history`: every saved version of a post, a diff between
+/// any two, and restoring an old one.
+#[component]
+pub fn AdminBlogHistory(id: ReadOnlySignal<i32>) -> Element {
+    use_effect(move || protected(Route::Login {}, Route::AdminBlogHistory { id: id() }));
+
+    rsx! {
+        div { class: "max-w-6xl mx-auto",
+            div { class: "flex items-center gap-4 mb-8",
+                Link { to: Route::AdminBlogEdit { id: id() }, class: "text-indigo-600 hover:underline", "← Back to editor" }
+                h1 { class: "text-3xl font-bold", "History" }
+            }
+            ClientOnly { RevisionHistory { key: "{id}", id } }
+        }
+    }
+}
+
+#[component]
+fn RevisionHistory(id: ReadOnlySignal<i32>) -> Element {
+    let mut revisions = use_resource(move || list_revisions(access_token().unwrap_or_default(), id()));
+    // Compare the two newest versions until the author picks others.
+    let mut from = use_signal::<Option<i32>>(|| None);
+    let mut to = use_signal::<Option<i32>>(|| None);
+    let mut pending_restore = use_signal::<Option<RevisionSummary>>(|| None);
+    let mut message = use_signal::<Option<Result<String, String>>>(|| None);
+
+    let confirm_restore = move |_| {
+        let Some(revision) = pending_restore() else { return };
+        spawn(async move {
+            match restore_revision(access_token().unwrap_or_default(), revision.id).await {
+                Ok(_) => {
+                    message.set(Some(Ok(format!("Restored revision #{}", revision.id))));
+                    from.set(None);
+                    to.set(None);
+                    revisions.restart();
+                }
+                Err(e) => message.set(Some(Err(format!("Failed to restore revision #{}: {}", revision.id, e)))),
+            }
+            pending_restore.set(None);
+        });
+    };
+
+    let list = match &*revisions.read() {
+        None => return rsx! { p { class: "text-gray-500", "Loading history..." } },
+        Some(Err(e)) => return error_banner(format!("Failed to load history: {}", e)),
+        Some(Ok(list)) => list.clone(),
+    };
+    let newest = list.first().map(|revision| revision.id);
+    let selected_to = to().or(newest);
+    let selected_from = from().or_else(|| list.get(1).map(|revision| revision.id));
+
+    rsx! {
+        match message() {
+            Some(Ok(text)) => rsx! { p { class: "p-4 mb-6 bg-green-50 text-green-800 rounded-lg", "{text}" } },
+            Some(Err(text)) => error_banner(text),
+            None => rsx! {},
+        }
+        if list.is_empty() {
+            p { class: "text-gray-500 dark:text-gray-400 py-12 text-center", "No revisions yet." }
+        } else {
+            table { class: "w-full text-left text-sm mb-10",
+                thead { class: "border-b border-gray-300 dark:border-gray-700 text-gray-500",
+                    tr {
+                        th { class: "py-2", "From" }
+                        th { class: "py-2", "To" }
+                        th { class: "py-2", "Revision" }
+                        th { class: "py-2", "Saved" }
+                        th { class: "py-2", "By" }
+                        th { class: "py-2", "Note" }
+                        th { class: "py-2 text-right", "" }
+                    }
+                }
+                tbody {
+                    for revision in list {
+                        tr { key: "{revision.id}", class: "border-b border-gray-200 dark:border-gray-800",
+                            td { class: "py-3 pr-2",
+                                input {
+                                    r#type: "radio",
+                                    name: "diff-from",
+                                    aria_label: "Compare from revision {revision.id}",
+                                    checked: selected_from == Some(revision.id),
+                                    onchange: move |_| from.set(Some(revision.id)),
+                                }
+                            }
+                            td { class: "py-3 pr-4",
+                                input {
+                                    r#type: "radio",
+                                    name: "diff-to",
+                                    aria_label: "Compare to revision {revision.id}",
+                                    checked: selected_to == Some(revision.id),
+                                    onchange: move |_| to.set(Some(revision.id)),
+                                }
+                            }
+                            td { class: "py-3 pr-4 font-medium",
+                                "#{revision.id}"
+                                if Some(revision.id) == newest {
+                                    span { class: "ml-2 px-2 py-1 text-xs rounded-full bg-green-100 text-green-800", "Current" }
+                                }
+                            }
+                            td { class: "py-3 pr-4 text-gray-500 whitespace-nowrap",
+                                "{revision.created_at.chars().take(16).collect::<String>().replace('T', \" \")}"
+                            }
+                            td { class: "py-3 pr-4 text-gray-500", "{revision.author.clone().unwrap_or_default()}" }
+                            td { class: "py-3 pr-4", "{revision.change_note.clone().unwrap_or_default()}" }
+                            td { class: "py-3 text-right",
+                                if Some(revision.id) != newest {
+                                    Button {
+                                        text: "Restore",
+                                        button_scheme: Some(ButtonScheme::Outline),
+                                        button_size: Some(ButtonSize::ExtraSmall),
+                                        on_click: {
+                                            let revision = revision.clone();
+                                            move |_| pending_restore.set(Some(revision.clone()))
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if let (Some(from), Some(to)) = (selected_from, selected_to) {
+                h2 { class: "text-xl font-bold mb-4", "Changes from #{from} to #{to}" }
+                RevisionDiff { from, to }
+            }
+        }
+        Modal {
+            title: "Restore revision?",
+            is_open: pending_restore.read().is_some(),
+            on_close: move |_| pending_restore.set(None),
+            body_children: rsx! {
+                p {
+                    "The post's text goes back to revision #{pending_restore.read().as_ref().map(|revision| revision.id).unwrap_or_default()}. "
+                    "Its publishing state stays as it is, and the current text remains in the history."
+                }
+            },
+            footer_children: rsx! {
+                Button { text: "Restore", on_click: confirm_restore }
+                Button {
+                    text: "Cancel",
+                    button_scheme: Some(ButtonScheme::Outline),
+                    on_click: move |_| pending_restore.set(None),
+                }
+            },
+        }
+    }
+}
+
+#[component]
+fn RevisionDiff(from: ReadOnlySignal<i32>, to: ReadOnlySignal<i32>) -> Element {
+    let diff = use_resource(move || diff_revisions(access_token().unwrap_or_default(), from(), to()));
+
+    let lines = match &*diff.read() {
+        None => return rsx! { p { class: "text-gray-500", "Comparing..." } },
+        Some(Err(e)) => return error_banner(format!("Failed to compare revisions: {}", e)),
+        Some(Ok(lines)) => lines.clone(),
+    };
+    if lines.iter().all(|line| line.kind == DiffKind::Unchanged) {
+        return rsx! { p { class: "text-gray-500 py-6", "No differences." } };
+    }
+
+    rsx! {
+        div { class: "font-mono text-sm rounded-lg border border-gray-200 dark:border-gray-700 overflow-x-auto mb-10",
+            for row in fold_unchanged(lines) {
+                match row {
+                    DiffRow::Folded(count) => rsx! {
+                        div { class: "px-4 py-1 bg-gray-100 dark:bg-gray-800 text-gray-500 italic", "⋯ {count} unchanged lines" }
+                    },
+                    DiffRow::Line(line) => {
+                        let (sign, class) = match line.kind {
+                            DiffKind::Added => ("+", "bg-green-50 text-green-900 dark:bg-green-900/30 dark:text-green-200"),
+                            DiffKind::Removed => ("-", "bg-red-50 text-red-900 dark:bg-red-900/30 dark:text-red-200"),
+                            DiffKind::Unchanged => (" ", ""),
+                        };
+                        rsx! {
+                            div { class: "px-4 whitespace-pre-wrap {class}", "{sign} {line.text}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
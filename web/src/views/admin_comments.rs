@@ -0,0 +1,179 @@
+use dioxus::prelude::*;
+use crate::api::auth::access_token;
+use crate::api::comments::{admin_list_comments, delete_comment, set_comment_status, AdminComment, CommentStatus};
+use crate::components::{Button, ButtonScheme, ButtonSize, Modal};
+use crate::content::render_comment;
+use crate::views::routes::protected;
+use crate::Route;
+
+use super::admin_blog::{error_banner, ClientOnly};
+
+/// The queues, in the order of their tabs.
+const QUEUES: [(CommentStatus, &str); 3] = [
+    (CommentStatus::Pending, "Awaiting moderation"),
+    (CommentStatus::Approved, "Approved"),
+    (CommentStatus::Spam, "Spam"),
+];
+
+/// `/admin/comments`: approve new comments, or file them as spam.
+#[component]
+pub fn AdminComments() -> Element {
+    use_effect(|| protected(Route::Login {}, Route::AdminComments {}));
+
+    rsx! {
+        div { class: "max-w-6xl mx-auto",
+            div { class: "flex items-center gap-4 mb-8",
+                Link { to: Route::AdminBlog {}, class: "text-indigo-600 hover:underline", "← All posts" }
+                h1 { class: "text-3xl font-bold", "Comments" }
+            }
+            ClientOnly { CommentQueue {} }
+        }
+    }
+}
+
+#[component]
+fn CommentQueue() -> Element {
+    let mut queue = use_signal(|| CommentStatus::Pending);
+    let mut comments = use_resource(move || admin_list_comments(access_token().unwrap_or_default(), queue()));
+    let mut pending_delete = use_signal::<Option<AdminComment>>(|| None);
+    let mut action_error = use_signal::<Option<String>>(|| None);
+
+    let moderate = move |id: i32, status: CommentStatus| {
+        spawn(async move {
+            match set_comment_status(access_token().unwrap_or_default(), id, status).await {
+                Ok(()) => {
+                    action_error.set(None);
+                    comments.restart();
+                }
+                Err(e) => action_error.set(Some(format!("Failed to update comment: {}", e))),
+            }
+        });
+    };
+
+    let confirm_delete = move |_| {
+        let Some(comment) = pending_delete.take() else { return };
+        spawn(async move {
+            match delete_comment(access_token().unwrap_or_default(), comment.id).await {
+                Ok(()) => {
+                    action_error.set(None);
+                    comments.restart();
+                }
+                Err(e) => action_error.set(Some(format!("Failed to delete comment: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "flex gap-2 mb-6 border-b border-gray-200 dark:border-gray-700",
+            for (status, label) in QUEUES {
+                button {
+                    r#type: "button",
+                    class: if queue() == status {
+                        "px-4 py-2 -mb-px border-b-2 border-indigo-600 text-indigo-600 font-medium"
+                    } else {
+                        "px-4 py-2 -mb-px border-b-2 border-transparent text-gray-500 hover:text-gray-700 dark:hover:text-gray-300"
+                    },
+                    onclick: move |_| queue.set(status),
+                    "{label}"
+                }
+            }
+        }
+        if let Some(message) = action_error() {
+            {error_banner(message)}
+        }
+        match &*comments.read() {
+            None => rsx! { p { class: "text-gray-500", "Loading comments..." } },
+            Some(Err(e)) => error_banner(format!("Failed to load comments: {}", e)),
+            Some(Ok(list)) if list.is_empty() => rsx! { p { class: "text-gray-500", "Nothing here." } },
+            Some(Ok(list)) => rsx! {
+                ul { class: "space-y-4",
+                    for comment in list.clone() {
+                        li {
+                            key: "{comment.id}",
+                            class: "p-4 bg-white dark:bg-gray-800 rounded-lg shadow",
+                            div { class: "flex flex-wrap items-baseline justify-between gap-2 text-sm",
+                                div { class: "flex flex-wrap items-baseline gap-2",
+                                    span { class: "font-semibold", "{comment.author_name}" }
+                                    if comment.parent_id.is_some() {
+                                        span { class: "text-gray-500", "replied" }
+                                    }
+                                    if let Some(post) = &comment.post {
+                                        span { class: "text-gray-500", "on" }
+                                        Link {
+                                            to: Route::BlogPostDetail { slug: post.slug.clone() },
+                                            class: "text-indigo-600 hover:underline",
+                                            "{post.title}"
+                                        }
+                                    }
+                                }
+                                span { class: "text-gray-500",
+                                    "{comment.created_at.get(..16).unwrap_or(&comment.created_at).replace('T', \" \")}"
+                                }
+                            }
+                            div {
+                                class: "prose prose-sm dark:prose-invert max-w-none mt-2",
+                                dangerous_inner_html: "{render_comment(&comment.body)}",
+                            }
+                            div { class: "flex gap-2 mt-3",
+                                if comment.status != CommentStatus::Approved {
+                                    Button {
+                                        text: "Approve",
+                                        button_size: Some(ButtonSize::ExtraSmall),
+                                        on_click: move |_| moderate(comment.id, CommentStatus::Approved),
+                                    }
+                                }
+                                if comment.status != CommentStatus::Spam {
+                                    Button {
+                                        text: "Spam",
+                                        button_scheme: Some(ButtonScheme::Outline),
+                                        button_size: Some(ButtonSize::ExtraSmall),
+                                        on_click: move |_| moderate(comment.id, CommentStatus::Spam),
+                                    }
+                                }
+                                if comment.status == CommentStatus::Approved {
+                                    Button {
+                                        text: "Unapprove",
+                                        button_scheme: Some(ButtonScheme::Outline),
+                                        button_size: Some(ButtonSize::ExtraSmall),
+                                        on_click: move |_| moderate(comment.id, CommentStatus::Pending),
+                                    }
+                                }
+                                Button {
+                                    text: "Delete",
+                                    button_scheme: Some(ButtonScheme::Danger),
+                                    button_size: Some(ButtonSize::ExtraSmall),
+                                    on_click: {
+                                        let comment = comment.clone();
+                                        move |_| pending_delete.set(Some(comment.clone()))
+                                    },
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        }
+        Modal {
+            title: "Delete comment?",
+            is_open: pending_delete.read().is_some(),
+            on_close: move |_| pending_delete.set(None),
+            body_children: rsx! {
+                p {
+                    "The comment by {pending_delete.read().as_ref().map(|comment| comment.author_name.clone()).unwrap_or_default()} and any replies to it will be removed permanently."
+                }
+            },
+            footer_children: rsx! {
+                Button {
+                    text: "Delete",
+                    button_scheme: Some(ButtonScheme::Danger),
+                    on_click: confirm_delete,
+                }
+                Button {
+                    text: "Cancel",
+                    button_scheme: Some(ButtonScheme::Outline),
+                    on_click: move |_| pending_delete.set(None),
+                }
+            },
+        }
+    }
+}
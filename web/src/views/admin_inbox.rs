@@ -0,0 +1,496 @@
+use dioxus::prelude::*;
+use crate::api::auth::access_token;
+use crate::api::inbox::{
+    get_contact_thread, list_contact_messages, send_contact_reply, set_contact_status, ContactStatus, InboxFilter,
+    InboxMessage, InboxReply, ReplyDirection, MAX_REPLY_CHARS,
+};
+use crate::components::{Button, ButtonScheme, ButtonSize};
+use crate::views::routes::protected;
+use crate::Route;
+
+use super::admin_blog::{error_banner, ClientOnly};
+
+const INPUT_CLASS: &str = "px-3 py-1.5 rounded-md border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-indigo-500";
+
+/// Bumped whenever the inbox changes a message's status, so the navbar's
+/// unread count follows.
+pub static INBOX_CHANGED: GlobalSignal<u32> = Signal::global(|| 0);
+
+fn status_badge(status: ContactStatus) -> Element {
+    let class = match status {
+        ContactStatus::Unread => "bg-indigo-100 text-indigo-800",
+        ContactStatus::Read => "bg-gray-200 text-gray-700",
+        ContactStatus::Replied => "bg-green-100 text-green-800",
+        ContactStatus::Archived => "bg-yellow-100 text-yellow-800",
+        ContactStatus::Spam => "bg-red-100 text-red-800",
+    };
+    rsx! {
+        span { class: "px-2 py-0.5 text-xs rounded-full {class}", "{status.label()}" }
+    }
+}
+
+fn received(message: &InboxMessage) -> String {
+    message.created_at.get(..16).unwrap_or(&message.created_at).replace('T', " ")
+}
+
+/// `/admin/inbox`: messages sent through the contact form.
+#[component]
+pub fn AdminInbox() -> Element {
+    use_effect(|| protected(Route::Login {}, Route::AdminInbox {}));
+
+    rsx! {
+        div { class: "max-w-6xl mx-auto",
+            div { class: "flex items-center gap-4 mb-8",
+                Link { to: Route::AdminBlog {}, class: "text-indigo-600 hover:underline", "← All posts" }
+                h1 { class: "text-3xl font-bold", "Inbox" }
+            }
+            ClientOnly { Inbox {} }
+        }
+    }
+}
+
+#[component]
+fn Inbox() -> Element {
+    let mut filter = use_signal(|| InboxFilter { page: 1, ..Default::default() });
+    let mut email_search = use_signal(String::new);
+    let mut page = use_resource(move || list_contact_messages(access_token().unwrap_or_default(), filter()));
+    let mut selected = use_signal(Vec::<String>::new);
+    let mut open = use_signal::<Option<InboxMessage>>(|| None);
+    let mut action_error = use_signal::<Option<String>>(|| None);
+    let mut notice = use_signal::<Option<String>>(|| None);
+
+    // Every filter but the page starts again from the first page.
+    let mut refilter = move |change: &dyn Fn(&mut InboxFilter)| {
+        let mut next = filter();
+        change(&mut next);
+        next.page = 1;
+        selected.set(Vec::new());
+        filter.set(next);
+    };
+
+    let move_messages = move |ids: Vec<String>, status: ContactStatus| {
+        spawn(async move {
+            match set_contact_status(access_token().unwrap_or_default(), ids.clone(), status).await {
+                Ok(moved) => {
+                    action_error.set(None);
+                    let skipped = ids.len() - moved;
+                    notice.set((skipped > 0).then(|| match skipped {
+                        1 => format!("1 message can't be marked {}.", status.label().to_lowercase()),
+                        n => format!("{} messages can't be marked {}.", n, status.label().to_lowercase()),
+                    }));
+                    if let Some(message) = open.write().as_mut() {
+                        if ids.contains(&message.id) && message.status.next().contains(&status) {
+                            message.status = status;
+                        }
+                    }
+                    selected.set(Vec::new());
+                    *INBOX_CHANGED.write() += 1;
+                    page.restart();
+                }
+                Err(e) => action_error.set(Some(format!("Failed to update messages: {}", e))),
+            }
+        });
+    };
+
+    let mut open_message = move |message: InboxMessage| {
+        let unread = message.status == ContactStatus::Unread;
+        let id = message.id.clone();
+        open.set(Some(message));
+        if unread {
+            move_messages(vec![id], ContactStatus::Read);
+        }
+    };
+
+    // The server marks a message replied when a reply goes out.
+    let replied = move |id: String| {
+        if let Some(message) = open.write().as_mut().filter(|message| message.id == id) {
+            message.status = ContactStatus::Replied;
+        }
+        *INBOX_CHANGED.write() += 1;
+        page.restart();
+    };
+
+    let current = filter();
+    let messages = match &*page.read() {
+        Some(Ok(page)) => page.messages.clone(),
+        _ => Vec::new(),
+    };
+    let page_ids: Vec<String> = messages.iter().map(|message| message.id.clone()).collect();
+    let all_selected = !page_ids.is_empty() && page_ids.iter().all(|id| selected.read().contains(id));
+
+    rsx! {
+        div { class: "flex flex-wrap gap-2 mb-4 border-b border-gray-200 dark:border-gray-700",
+            for status in std::iter::once(None).chain(ContactStatus::ALL.into_iter().map(Some)) {
+                button {
+                    r#type: "button",
+                    class: if current.status == status {
+                        "px-4 py-2 -mb-px border-b-2 border-indigo-600 text-indigo-600 font-medium"
+                    } else {
+                        "px-4 py-2 -mb-px border-b-2 border-transparent text-gray-500 hover:text-gray-700 dark:hover:text-gray-300"
+                    },
+                    onclick: move |_| refilter(&|filter| filter.status = status),
+                    {status.map(ContactStatus::label).unwrap_or("All")}
+                }
+            }
+        }
+        div { class: "flex flex-wrap items-end gap-4 mb-6 text-sm",
+            label { class: "flex flex-col gap-1",
+                span { class: "text-gray-500", "From" }
+                input {
+                    r#type: "date",
+                    class: INPUT_CLASS,
+                    value: "{current.from}",
+                    onchange: move |evt| refilter(&|filter| filter.from = evt.value()),
+                }
+            }
+            label { class: "flex flex-col gap-1",
+                span { class: "text-gray-500", "To" }
+                input {
+                    r#type: "date",
+                    class: INPUT_CLASS,
+                    value: "{current.to}",
+                    onchange: move |evt| refilter(&|filter| filter.to = evt.value()),
+                }
+            }
+            form {
+                class: "flex flex-col gap-1",
+                onsubmit: move |evt: FormEvent| {
+                    evt.prevent_default();
+                    let email = email_search();
+                    refilter(&|filter| filter.email = email.clone());
+                },
+                label { r#for: "inbox-email", class: "text-gray-500", "Email" }
+                input {
+                    id: "inbox-email",
+                    r#type: "search",
+                    class: INPUT_CLASS,
+                    placeholder: "Part of an address, then Enter",
+                    value: "{email_search}",
+                    oninput: move |evt| email_search.set(evt.value()),
+                }
+            }
+            if !(current.from.is_empty() && current.to.is_empty() && current.email.is_empty()) {
+                button {
+                    r#type: "button",
+                    class: "py-1.5 text-indigo-600 hover:underline",
+                    onclick: move |_| {
+                        email_search.set(String::new());
+                        refilter(&|filter| {
+                            filter.from.clear();
+                            filter.to.clear();
+                            filter.email.clear();
+                        });
+                    },
+                    "Clear filters"
+                }
+            }
+        }
+        if let Some(message) = action_error() {
+            {error_banner(message)}
+        }
+        if let Some(message) = notice() {
+            div { class: "p-4 mb-6 bg-amber-50 dark:bg-amber-900/20 border border-amber-200 dark:border-amber-800 rounded-lg",
+                "{message}"
+            }
+        }
+        div { class: "flex flex-wrap items-center gap-2 mb-4 min-h-10",
+            label { class: "flex items-center gap-2 mr-4 text-sm",
+                input {
+                    r#type: "checkbox",
+                    checked: all_selected,
+                    disabled: page_ids.is_empty(),
+                    onchange: {
+                        let page_ids = page_ids.clone();
+                        move |evt: FormEvent| selected.set(if evt.checked() { page_ids.clone() } else { Vec::new() })
+                    },
+                }
+                if selected.read().is_empty() {
+                    "Select all"
+                } else {
+                    "{selected.read().len()} selected"
+                }
+            }
+            if !selected.read().is_empty() {
+                for status in ContactStatus::ALL {
+                    Button {
+                        text: status.action(),
+                        button_scheme: Some(if status == ContactStatus::Spam { ButtonScheme::Danger } else { ButtonScheme::Outline }),
+                        button_size: Some(ButtonSize::ExtraSmall),
+                        on_click: move |_| move_messages(selected(), status),
+                    }
+                }
+            }
+        }
+        div { class: "grid gap-6 md:grid-cols-5",
+            div { class: "md:col-span-2",
+                match &*page.read() {
+                    None => rsx! { p { class: "text-gray-500", "Loading messages..." } },
+                    Some(Err(e)) => error_banner(format!("Failed to load messages: {}", e)),
+                    Some(Ok(found)) if found.messages.is_empty() => rsx! { p { class: "text-gray-500", "No messages." } },
+                    Some(Ok(found)) => rsx! {
+                        ul { class: "divide-y divide-gray-200 dark:divide-gray-700 bg-white dark:bg-gray-800 rounded-lg shadow",
+                            for message in found.messages.clone() {
+                                li {
+                                    key: "{message.id}",
+                                    class: if open.read().as_ref().is_some_and(|open| open.id == message.id) {
+                                        "flex gap-3 p-3 bg-indigo-50 dark:bg-indigo-900/20"
+                                    } else {
+                                        "flex gap-3 p-3"
+                                    },
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "mt-1",
+                                        aria_label: "Select the message from {message.name}",
+                                        checked: selected.read().contains(&message.id),
+                                        onchange: {
+                                            let id = message.id.clone();
+                                            move |evt: FormEvent| {
+                                                let mut selected = selected.write();
+                                                selected.retain(|selected| *selected != id);
+                                                if evt.checked() {
+                                                    selected.push(id.clone());
+                                                }
+                                            }
+                                        },
+                                    }
+                                    button {
+                                        r#type: "button",
+                                        class: "flex-1 min-w-0 text-left",
+                                        onclick: {
+                                            let message = message.clone();
+                                            move |_| open_message(message.clone())
+                                        },
+                                        div { class: "flex items-baseline justify-between gap-2 text-sm",
+                                            span {
+                                                class: if message.status == ContactStatus::Unread { "truncate font-semibold" } else { "truncate" },
+                                                "{message.name}"
+                                            }
+                                            span { class: "shrink-0 text-xs text-gray-500", "{received(&message)}" }
+                                        }
+                                        div { class: "flex items-center justify-between gap-2 mt-1",
+                                            span { class: "truncate text-sm text-gray-500",
+                                                {message.subject.clone().unwrap_or_else(|| message.message.chars().take(80).collect())}
+                                            }
+                                            {status_badge(message.status)}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        div { class: "flex items-center justify-between mt-4 text-sm",
+                            Button {
+                                text: "Previous",
+                                button_scheme: Some(ButtonScheme::Outline),
+                                button_size: Some(ButtonSize::ExtraSmall),
+                                disabled: current.page <= 1,
+                                on_click: move |_| {
+                                    selected.set(Vec::new());
+                                    filter.write().page -= 1;
+                                },
+                            }
+                            span { class: "text-gray-500",
+                                "Page {current.page} of {found.total_pages.max(1)} · {found.total} messages"
+                            }
+                            Button {
+                                text: "Next",
+                                button_scheme: Some(ButtonScheme::Outline),
+                                button_size: Some(ButtonSize::ExtraSmall),
+                                disabled: current.page >= found.total_pages,
+                                on_click: move |_| {
+                                    selected.set(Vec::new());
+                                    filter.write().page += 1;
+                                },
+                            }
+                        }
+                    },
+                }
+            }
+            div { class: "md:col-span-3",
+                match open() {
+                    None => rsx! {
+                        p { class: "p-6 text-gray-500 bg-white dark:bg-gray-800 rounded-lg shadow", "Pick a message to read it." }
+                    },
+                    Some(message) => rsx! {
+                        MessageDetail {
+                            message,
+                            on_move: move |(id, status): (String, ContactStatus)| move_messages(vec![id], status),
+                            on_replied: replied,
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// The open message, with where it came from and what can be done with it.
+#[component]
+fn MessageDetail(
+    message: InboxMessage,
+    on_move: EventHandler<(String, ContactStatus)>,
+    on_replied: EventHandler<String>,
+) -> Element {
+    let spam = message.metadata.as_ref().and_then(|metadata| metadata.get("spam"));
+    let spam_score = spam.and_then(|spam| spam["score"].as_u64());
+    let spam_signals: Vec<&str> = spam
+        .and_then(|spam| spam["signals"].as_array())
+        .map(|signals| signals.iter().filter_map(|signal| signal.as_str()).collect())
+        .unwrap_or_default();
+    let spam_signals = spam_signals.join(", ");
+    let metadata = message
+        .metadata
+        .as_ref()
+        .and_then(|metadata| serde_json::to_string_pretty(metadata).ok())
+        .unwrap_or_default();
+
+    rsx! {
+        article { class: "p-6 bg-white dark:bg-gray-800 rounded-lg shadow",
+            div { class: "flex items-start justify-between gap-4",
+                h2 { class: "text-xl font-semibold", {message.subject.clone().unwrap_or_else(|| "(no subject)".to_string())} }
+                {status_badge(message.status)}
+            }
+            p { class: "mt-1 text-sm",
+                "{message.name} "
+                a { href: "mailto:{message.email}", class: "text-indigo-600 hover:underline", "{message.email}" }
+            }
+            p { class: "text-sm text-gray-500", "{received(&message)} UTC" }
+            div { class: "mt-4 whitespace-pre-wrap break-words", "{message.message}" }
+            div { class: "flex flex-wrap gap-2 mt-6",
+                for status in message.status.next().iter().copied() {
+                    Button {
+                        text: status.action(),
+                        button_scheme: Some(if status == ContactStatus::Spam { ButtonScheme::Danger } else { ButtonScheme::Outline }),
+                        button_size: Some(ButtonSize::ExtraSmall),
+                        on_click: {
+                            let id = message.id.clone();
+                            move |_| on_move.call((id.clone(), status))
+                        },
+                    }
+                }
+            }
+            dl { class: "grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 mt-6 pt-4 border-t border-gray-200 dark:border-gray-700 text-sm",
+                dt { class: "text-gray-500", "IP address" }
+                dd { {message.ip_address.clone().unwrap_or_else(|| "unknown".to_string())} }
+                dt { class: "text-gray-500", "User agent" }
+                dd { class: "break-all", {message.user_agent.clone().unwrap_or_else(|| "unknown".to_string())} }
+                if let Some(score) = spam_score {
+                    dt { class: "text-gray-500", "Spam score" }
+                    dd {
+                        "{score}"
+                        if !spam_signals.is_empty() {
+                            " ({spam_signals})"
+                        }
+                    }
+                }
+            }
+            if !metadata.is_empty() {
+                details { class: "mt-4 text-sm",
+                    summary { class: "cursor-pointer text-gray-500", "Metadata" }
+                    pre { class: "mt-2 p-3 overflow-x-auto bg-gray-100 dark:bg-gray-900 rounded text-xs", "{metadata}" }
+                }
+            }
+        }
+        Conversation { key: "{message.id}", message: message.clone(), on_replied }
+    }
+}
+
+fn delivery_badge(reply: &InboxReply) -> Element {
+    let (label, class) = match reply.mail.as_ref().map(|mail| mail.status.as_str()) {
+        Some("sent") => ("Sent", "bg-green-100 text-green-800"),
+        Some("failed") => ("Not delivered", "bg-red-100 text-red-800"),
+        Some(_) => ("Sending", "bg-yellow-100 text-yellow-800"),
+        None => return rsx! {},
+    };
+    let error = reply.mail.as_ref().and_then(|mail| mail.last_error.clone());
+    rsx! {
+        span { class: "px-2 py-0.5 text-xs rounded-full {class}", title: error, "{label}" }
+    }
+}
+
+/// The replies to the open message, and a form to send another.
+#[component]
+fn Conversation(message: InboxMessage, on_replied: EventHandler<String>) -> Element {
+    let id = message.id.clone();
+    let mut replies = use_resource(move || get_contact_thread(access_token().unwrap_or_default(), id.clone()));
+    let mut draft = use_signal(String::new);
+    let mut sending = use_signal(|| false);
+    let mut send_error = use_signal::<Option<String>>(|| None);
+
+    let send = move |_| {
+        let id = message.id.clone();
+        spawn(async move {
+            sending.set(true);
+            match send_contact_reply(access_token().unwrap_or_default(), id.clone(), draft()).await {
+                Ok(_) => {
+                    draft.set(String::new());
+                    send_error.set(None);
+                    replies.restart();
+                    on_replied.call(id);
+                }
+                Err(e) => send_error.set(Some(format!("Failed to send the reply: {}", e))),
+            }
+            sending.set(false);
+        });
+    };
+
+    rsx! {
+        section { class: "mt-6",
+            h3 { class: "text-lg font-semibold mb-3", "Conversation" }
+            match &*replies.read() {
+                None => rsx! { p { class: "text-gray-500", "Loading replies..." } },
+                Some(Err(e)) => error_banner(format!("Failed to load replies: {}", e)),
+                Some(Ok(list)) if list.is_empty() => rsx! { p { class: "text-sm text-gray-500", "No replies yet." } },
+                Some(Ok(list)) => rsx! {
+                    ol { class: "space-y-3",
+                        for reply in list.clone() {
+                            li {
+                                key: "{reply.id}",
+                                class: if reply.direction == ReplyDirection::Outgoing {
+                                    "p-4 ml-8 bg-indigo-50 dark:bg-indigo-900/20 rounded-lg"
+                                } else {
+                                    "p-4 mr-8 bg-white dark:bg-gray-800 rounded-lg shadow"
+                                },
+                                div { class: "flex flex-wrap items-baseline justify-between gap-2 text-sm",
+                                    span { class: "font-medium",
+                                        match reply.direction {
+                                            ReplyDirection::Outgoing => reply.sent_by.clone().unwrap_or_else(|| reply.from_address.clone()),
+                                            ReplyDirection::Incoming => reply.from_address.clone(),
+                                        }
+                                    }
+                                    span { class: "flex items-center gap-2 text-gray-500",
+                                        {reply.created_at.get(..16).unwrap_or(&reply.created_at).replace('T', " ")}
+                                        {delivery_badge(&reply)}
+                                    }
+                                }
+                                div { class: "mt-2 text-sm whitespace-pre-wrap break-words", "{reply.body}" }
+                            }
+                        }
+                    }
+                },
+            }
+            form {
+                class: "mt-4",
+                onsubmit: move |evt: FormEvent| evt.prevent_default(),
+                label { r#for: "inbox-reply", class: "block text-sm font-medium mb-1", "Reply to {message.email}" }
+                textarea {
+                    id: "inbox-reply",
+                    class: "w-full h-40 px-3 py-2 rounded-md border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                    maxlength: MAX_REPLY_CHARS as i64,
+                    value: "{draft}",
+                    oninput: move |evt| draft.set(evt.value()),
+                }
+                p { class: "text-xs text-gray-500 mb-2",
+                    "{message.name}'s message is quoted under your reply. Their answer comes to your own address."
+                }
+                if let Some(message) = send_error() {
+                    {error_banner(message)}
+                }
+                Button {
+                    text: if sending() { "Sending..." } else { "Send reply" },
+                    disabled: sending() || draft.read().trim().is_empty(),
+                    on_click: send,
+                }
+            }
+        }
+    }
+}
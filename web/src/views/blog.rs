@@ -1,97 +1,48 @@
 use dioxus::prelude::*;
-use crate::api::get_blog;
+use crate::components::{ResponsiveImage, Seo};
+use crate::api::env::absolute_url;
+use serde_json::json;
+use crate::api::{get_blog_page, get_blog_page_after, tag_slug, BlogPage, BlogPostSummary};
+use crate::views::routes::canonical_path;
 use crate::Route;
-use crate::api::blog::BlogPost;
+
+/// Observes the sentinel below the post grid and pings the component when it
+/// scrolls into view. Each message from Rust re-arms the observer, so a
+/// sentinel that is still visible after a page loads fires again.
+const INFINITE_SCROLL_SCRIPT: &str = r#"
+const sentinel = document.getElementById("blog-load-more");
+if (sentinel) {
+    const observer = new IntersectionObserver((entries) => {
+        if (entries.some((entry) => entry.isIntersecting)) dioxus.send(true);
+    }, { rootMargin: "400px" });
+    observer.observe(sentinel);
+    while (true) {
+        await dioxus.recv();
+        observer.unobserve(sentinel);
+        observer.observe(sentinel);
+    }
+}
+"#;
 
 /// The Blog page component
 #[component]
-pub fn Blog() -> Element {
-    let mut posts = use_signal::<Vec<BlogPost>>(|| vec![]);
-    let loading = use_signal(|| false); // Start as false to show content immediately
-    let mut error = use_signal::<Option<String>>(|| None);
-
-    // Set up mock data immediately
-    use_effect(move || {
-        // Try to fetch from Supabase in a separate future
-        spawn(async move {
-            #[cfg(target_arch = "wasm32")]
-            gloo::console::log!("Starting blog post fetch...");
-            
-            match get_blog().await {
-                Ok(fetched_posts) => {
-                    #[cfg(target_arch = "wasm32")]
-                    gloo::console::log!("Fetch successful! Posts count:", fetched_posts.len());
-                    
-                    if !fetched_posts.is_empty() {
-                        #[cfg(target_arch = "wasm32")]
-                        gloo::console::log!("Successfully fetched", fetched_posts.len(), "blog posts from Supabase");
-                        posts.set(fetched_posts);
-                    } else {
-                        #[cfg(target_arch = "wasm32")]
-                        {
-                            gloo::console::log!("No posts found in Supabase - this likely means:");
-                            gloo::console::log!("1. No data in your Supabase blog_posts table");
-                            gloo::console::log!("2. Environment variables not set correctly");
-                            gloo::console::log!("3. Table doesn't exist or RLS policies blocking access");
-                        }
-                        
-                        // Set some temporary mock data since Supabase is empty
-                        let mock_posts = vec![
-                            BlogPost {
-                                id: 1,
-                                slug: "supabase-connection-test".to_string(),
-                                excerpt: "This is a test post showing that Supabase connection works but no data exists...".to_string(),
-                                title: "Supabase Connected (No Data)".to_string(),
-                                author: "System".to_string(),
-                                content: "The Supabase connection is working, but no blog posts were found in the database. This could mean the table is empty or the environment variables need to be configured.".to_string(),
-                                published_at: "2024-01-01".to_string(),
-                                updated_at: None,
-                                created_at: None,
-                                tags: vec!["Debug".to_string(), "Supabase".to_string()],
-                                cover_image: None,
-                            },
-                        ];
-                        posts.set(mock_posts);
-                    }
-                }
-                Err(e) => {
-                    #[cfg(target_arch = "wasm32")]
-                    {
-                        gloo::console::error!("Failed to fetch from Supabase:", e.to_string());
-                        gloo::console::log!("This likely means:");
-                        gloo::console::log!("1. Supabase URL/Key not set (check environment variables)");
-                        gloo::console::log!("2. Network connectivity issue");
-                        gloo::console::log!("3. CORS or authentication problems");
-                        gloo::console::log!("4. 406 error usually means missing Accept headers or wrong content type");
-                        gloo::console::log!("5. Table 'blog_posts' doesn't exist in your Supabase database");
-                    }
-                    
-                    // Set error message for user
-                    error.set(Some(format!("Failed to connect to Supabase: {}", e)));
-                    
-                    // Still provide some content so page isn't empty
-                    let debug_posts = vec![
-                        BlogPost {
-                            id: 1,
-                            slug: "connection-error".to_string(),
-                            excerpt: "Failed to connect to Supabase - check configuration...".to_string(),
-                            title: "Blog Connection Error".to_string(),
-                            author: "System".to_string(),
-                            content: "Unable to connect to Supabase. Please check your environment variables and database configuration. If you're seeing a 406 error, it usually means the blog_posts table doesn't exist or the request headers are incorrect.".to_string(),
-                            published_at: "2024-01-01".to_string(),
-                            updated_at: None,
-                            tags: vec!["Error".to_string(), "Configuration".to_string()],
-                            cover_image: None,
-                            created_at: None,
-                        },
-                    ];
-                    posts.set(debug_posts);
-                }
-            }
-        });
-    });
+pub fn Blog(page: ReadOnlySignal<u32>) -> Element {
+    let page_number = page().max(1);
+    let title = if page_number > 1 { format!("Blog - Page {}", page_number) } else { "Blog".to_string() };
 
     rsx! {
+        Seo {
+            key: "{page_number}",
+            title,
+            description: "Exploring the intersection of finance, technology, and innovation. Sharing insights from my journey as a developer, 3D printing enthusiast, and financial professional.",
+            path: canonical_path(&Route::Blog { page: page_number }),
+            json_ld: json!({
+                "@context": "https://schema.org",
+                "@type": "Blog",
+                "name": "Dylan Rayburn's Blog",
+                "url": absolute_url(&canonical_path(&Route::Blog { page: 1 })),
+            }),
+        }
         div { class: "max-w-6xl mx-auto",
             // Hero section
             div { class: "",
@@ -99,81 +50,215 @@ pub fn Blog() -> Element {
                 p { class: " text-lg leading-relaxed pb-2",
                     "Exploring the intersection of finance, technology, and innovation. Sharing insights from my journey as a developer, 3D printing enthusiast, and financial professional."
                 }
+                Link {
+                    to: Route::BlogTags {},
+                    class: "inline-block mb-8 text-indigo-600 dark:text-indigo-400 hover:underline",
+                    "Browse by tag →"
+                }
             }
-            // Loading state
-            if *loading.read() {
-                div { class: "flex justify-center py-20",
-                    div { class: "flex flex-col items-center",
-                        div { class: "animate-spin rounded-full h-16 w-16 border-t-4 border-b-4 border-indigo-600 dark:border-indigo-400" }
-                        p { class: "mt-4 text-gray-600 dark:text-gray-400", "Loading blog posts..." }
+            SuspenseBoundary {
+                fallback: |_| rsx! {
+                    div { class: "flex justify-center py-20",
+                        div { class: "flex flex-col items-center",
+                            div { class: "animate-spin rounded-full h-16 w-16 border-t-4 border-b-4 border-indigo-600 dark:border-indigo-400" }
+                            p { class: "mt-4 text-gray-600 dark:text-gray-400", "Loading blog posts..." }
+                        }
+                    }
+                },
+                // Remount per page so the infinite scroll state starts over.
+                BlogPostList { key: "{page_number}", page: page_number }
+            }
+        }
+    }
+}
+
+/// The post grid. Its data comes from a server future, so the server renders
+/// it with the posts already in place and the client hydrates from that.
+///
+/// With infinite scroll switched on, further pages are fetched by cursor and
+/// appended as the reader nears the bottom, instead of using the page links.
+#[component]
+fn BlogPostList(page: u32) -> Element {
+    let data = use_server_future(move || get_blog_page(page))?;
+
+    let (blog_page, error) = match &*data.read() {
+        Some(Ok(blog_page)) => (blog_page.clone(), None),
+        Some(Err(e)) => (BlogPage::default(), Some(format!("Failed to connect to Supabase: {}", e))),
+        None => (BlogPage::default(), None),
+    };
+
+    let mut infinite = use_signal(|| false);
+    let mut more_posts = use_signal(Vec::<BlogPostSummary>::new);
+    let mut next_cursor = use_signal(|| blog_page.next_cursor.clone());
+    let mut loading_more = use_signal(|| false);
+    let mut more_error = use_signal::<Option<String>>(|| None);
+    let mut scroll_task = use_signal::<Option<Task>>(|| None);
+
+    use_effect(move || {
+        let enabled = infinite();
+        // Only one watcher at a time: toggling stops the one already running.
+        if let Some(task) = scroll_task.write().take() {
+            task.cancel();
+        }
+        if !enabled {
+            return;
+        }
+        let task = spawn(async move {
+            let mut observer = document::eval(INFINITE_SCROLL_SCRIPT);
+            while observer.recv::<bool>().await.is_ok() {
+                let Some(cursor) = next_cursor() else { break };
+                loading_more.set(true);
+                match get_blog_page_after(cursor).await {
+                    Ok(next) => {
+                        more_posts.write().extend(next.posts);
+                        next_cursor.set(next.next_cursor);
+                    }
+                    Err(e) => {
+                        more_error.set(Some(format!("Failed to load more posts: {}", e)));
+                        loading_more.set(false);
+                        break;
                     }
                 }
+                loading_more.set(false);
+                if observer.send(true).is_err() {
+                    break;
+                }
             }
-            // Error state
-            if let Some(err) = error.read().as_ref() {
-                div { class: "p-6 mb-8 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-red-800 dark:text-red-200 rounded-lg",
-                    div { class: "flex items-center",
-                        svg {
-                            class: "w-5 h-5 mr-2",
-                            fill: "currentColor",
-                            view_box: "0 0 20 20",
-                            path {
-                                fill_rule: "evenodd",
-                                d: "M10 18a8 8 0 100-16 8 8 0 000 16zM8.707 7.293a1 1 0 00-1.414 1.414L8.586 10l-1.293 1.293a1 1 0 101.414 1.414L10 11.414l1.293 1.293a1 1 0 001.414-1.414L11.414 10l1.293-1.293a1 1 0 00-1.414-1.414L10 8.586 8.707 7.293z",
-                                clip_rule: "evenodd",
-                            }
+        });
+        scroll_task.set(Some(task));
+    });
+
+    let posts = blog_page.posts;
+
+    rsx! {
+        // Error state
+        if let Some(err) = error {
+            div { class: "p-6 mb-8 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-red-800 dark:text-red-200 rounded-lg",
+                div { class: "flex items-center",
+                    svg {
+                        class: "w-5 h-5 mr-2",
+                        fill: "currentColor",
+                        view_box: "0 0 20 20",
+                        path {
+                            fill_rule: "evenodd",
+                            d: "M10 18a8 8 0 100-16 8 8 0 000 16zM8.707 7.293a1 1 0 00-1.414 1.414L8.586 10l-1.293 1.293a1 1 0 101.414 1.414L10 11.414l1.293 1.293a1 1 0 001.414-1.414L11.414 10l1.293-1.293a1 1 0 00-1.414-1.414L10 8.586 8.707 7.293z",
+                            clip_rule: "evenodd",
                         }
-                        strong { "Error loading posts: " }
-                        "{err}"
                     }
+                    strong { "Error loading posts: " }
+                    "{err}"
                 }
             }
-            // Blog posts list
-            if posts.read().is_empty() && !*loading.read() {
-                div { class: "text-center py-20",
-                    div { class: "mb-4",
-                        svg {
-                            class: "mx-auto h-16 w-16 text-gray-400 dark:text-gray-600",
-                            fill: "none",
-                            stroke: "currentColor",
-                            view_box: "0 0 24 24",
-                            path {
-                                stroke_linecap: "round",
-                                stroke_linejoin: "round",
-                                stroke_width: "2",
-                                d: "M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z",
-                            }
+        }
+        if posts.is_empty() {
+            div { class: "text-center py-20",
+                div { class: "mb-4",
+                    svg {
+                        class: "mx-auto h-16 w-16 text-gray-400 dark:text-gray-600",
+                        fill: "none",
+                        stroke: "currentColor",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            stroke_width: "2",
+                            d: "M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z",
                         }
                     }
-                    h3 { class: "text-lg font-medium text-gray-900 dark:text-white mb-2",
-                        "No blog posts found"
+                }
+                h3 { class: "text-lg font-medium text-gray-900 dark:text-white mb-2",
+                    "No blog posts found"
+                }
+                p { class: "text-gray-500 dark:text-gray-400", "Check back soon for new content!" }
+            }
+        } else {
+            div { class: "grid gap-8 md:grid-cols-2 lg:grid-cols-3",
+                for post in posts.iter().chain(more_posts.read().iter()) {
+                    BlogPostCard { key: "{post.id}", post: post.clone() }
+                }
+            }
+
+            if infinite() {
+                div { id: "blog-load-more", class: "flex justify-center py-10 text-gray-500 dark:text-gray-400",
+                    if let Some(err) = more_error() {
+                        span { class: "text-red-600 dark:text-red-400", "{err}" }
+                    } else if loading_more() {
+                        div { class: "animate-spin rounded-full h-8 w-8 border-t-2 border-b-2 border-indigo-600 dark:border-indigo-400" }
+                    } else if next_cursor.read().is_none() {
+                        span { "You've reached the end." }
                     }
-                    p { class: "text-gray-500 dark:text-gray-400", "Check back soon for new content!" }
                 }
             } else {
-                div { class: "grid gap-8 md:grid-cols-2 lg:grid-cols-3",
-                    for post in posts.read().iter() {
-                        BlogPostCard { post: post.clone() }
-                    }
+                Pagination { page, total_pages: blog_page.total_pages }
+            }
+
+            div { class: "flex justify-center pb-4",
+                button {
+                    class: "text-sm text-indigo-600 dark:text-indigo-400 hover:underline cursor-pointer",
+                    onclick: move |_| infinite.toggle(),
+                    if infinite() { "Switch to page links" } else { "Load posts as I scroll" }
                 }
             }
         }
     }
+}
+
+/// The listing at `/blog/page/<n>`, which the static export writes pages
+/// 2..N to because static hosts ignore the query string.
+#[component]
+pub fn BlogPaged(page: ReadOnlySignal<u32>) -> Element {
+    rsx! {
+        Blog { page }
+    }
+}
+
+/// Previous / numbered / next links between listing pages.
+#[component]
+fn Pagination(page: u32, total_pages: u32) -> Element {
+    if total_pages <= 1 {
+        return rsx! {};
     }
 
+    let link_class = "px-3 py-2 rounded-md text-sm font-medium text-gray-700 dark:text-gray-300 hover:bg-indigo-50 dark:hover:bg-gray-700";
+    let current_class = "px-3 py-2 rounded-md text-sm font-medium bg-indigo-600 text-white";
+
+    rsx! {
+        nav { class: "flex justify-center items-center gap-2 py-10", aria_label: "Blog pages",
+            if page > 1 {
+                Link { to: Route::Blog { page: page - 1 }, class: link_class, rel: "prev", "← Newer" }
+            }
+            for number in 1..=total_pages {
+                if number == page {
+                    span { class: current_class, aria_current: "page", "{number}" }
+                } else {
+                    Link { to: Route::Blog { page: number }, class: link_class, "{number}" }
+                }
+            }
+            if page < total_pages {
+                Link { to: Route::Blog { page: page + 1 }, class: link_class, rel: "next", "Older →" }
+            }
+        }
+    }
+}
+
+/// Rendered width of a card's cover in the `md:grid-cols-2 lg:grid-cols-3`
+/// grids the cards sit in.
+const BLOG_CARD_SIZES: &str = "(min-width: 1024px) 33vw, (min-width: 768px) 50vw, 100vw";
 
 /// Individual blog post card component
 #[component]
-fn BlogPostCard(post: BlogPost) -> Element {
+pub(crate) fn BlogPostCard(post: BlogPostSummary) -> Element {
     rsx! {
         article { class: "bg-white dark:bg-gray-800 rounded-xl shadow-lg hover:shadow-xl transition-all duration-300 overflow-hidden group",
             // Cover image if available
             if let Some(image_url) = &post.cover_image {
                 div { class: "relative overflow-hidden h-48",
-                    img {
+                    ResponsiveImage {
                         class: "w-full h-full object-cover group-hover:scale-105 transition-transform duration-300",
-                        src: "{image_url}",
+                        src: image_url.clone(),
+                        variants: post.cover_image_variants.clone(),
                         alt: "Cover image for {post.title}",
+                        sizes: BLOG_CARD_SIZES,
                     }
                     div { class: "absolute inset-0 bg-black bg-opacity-0 group-hover:bg-opacity-10 transition-opacity duration-300" }
                 }
@@ -204,6 +289,21 @@ fn BlogPostCard(post: BlogPost) -> Element {
                     }
                     div { class: "text-sm text-gray-500 dark:text-gray-400", "By {post.author}" }
                 }
+                // Series badge
+                if let Some(series) = &post.series {
+                    Link {
+                        to: Route::BlogSeries { slug: series.slug.clone() },
+                        class: "inline-flex items-center gap-1 mb-2 px-2 py-1 text-xs font-semibold rounded bg-amber-100 dark:bg-amber-900 text-amber-800 dark:text-amber-200 hover:bg-amber-200 dark:hover:bg-amber-800",
+                        title: "Part of a series",
+                        svg {
+                            class: "w-3 h-3",
+                            fill: "currentColor",
+                            view_box: "0 0 20 20",
+                            path { d: "M4 3a2 2 0 00-2 2v1h16V5a2 2 0 00-2-2H4zM2 9h16v2H2V9zm0 5h16v1a2 2 0 01-2 2H4a2 2 0 01-2-2v-1z" }
+                        }
+                        "Series: {series.title}"
+                    }
+                }
                 // Title
                 h2 { class: "text-xl font-bold text-gray-900 dark:text-white mb-3 group-hover:text-indigo-600 dark:group-hover:text-indigo-400 transition-colors duration-200",
                     Link {
@@ -216,13 +316,15 @@ fn BlogPostCard(post: BlogPost) -> Element {
                 }
                 // Excerpt
                 p { class: "text-gray-600 dark:text-gray-300 mb-4 line-clamp-3",
-                    "{post.content.chars().take(120).collect::<String>()}..."
+                    "{post.excerpt}"
                 }
                 // Tags
                 if !post.tags.is_empty() {
                     div { class: "flex flex-wrap gap-2 mb-4",
                         for tag in &post.tags {
-                            span { class: "px-2 py-1 text-xs font-medium bg-indigo-100 dark:bg-indigo-900 text-indigo-800 dark:text-indigo-200 rounded-full",
+                            Link {
+                                to: Route::BlogTag { tag: tag_slug(tag) },
+                                class: "px-2 py-1 text-xs font-medium bg-indigo-100 dark:bg-indigo-900 text-indigo-800 dark:text-indigo-200 hover:bg-indigo-200 dark:hover:bg-indigo-800 rounded-full",
                                 "{tag}"
                             }
                         }
@@ -248,7 +350,7 @@ fn BlogPostCard(post: BlogPost) -> Element {
                         }
                     }
                     div { class: "text-sm text-gray-500 dark:text-gray-400",
-                        "{post.content.chars().count() / 250} min read"
+                        "{post.reading_time} min read"
                     }
                 }
             }
@@ -1,195 +1,476 @@
 use dioxus::prelude::*;
-use crate::api::{get_blog_with_slug, BlogPost};
+use crate::api::{get_blog_with_slug, get_post_links, tag_slug, BlogPost, BlogPostSummary, PostLinks, PostSeries};
+use crate::api::preview::{get_blog_preview, preview_token};
+use crate::api::env::absolute_url;
+use crate::components::{scroll_to, use_scroll_spy, ResponsiveImage, ScrollSpy, ScrollTarget, Seo};
+use crate::content::{outline, render_markdown, Heading};
+use crate::views::blog::BlogPostCard;
+use crate::views::blog_comments::Comments;
+use crate::views::blog_webmentions::Webmentions;
 use crate::Route;
+use serde_json::{json, Value};
 
-// 2. Blog Post Detail Component
-#[component]
-pub fn BlogPostDetail(slug: String) -> Element {
-    let post = use_signal::<Option<BlogPost>>(|| None);
-    let loading = use_signal(|| true);
-    let error = use_signal::<Option<String>>(|| None);
-
-    // Fetch post from Supabase when component mounts
-    use_effect(move || {
-        let mut post = post.clone();
-        let mut loading = loading.clone();
-        let mut error = error.clone();
-        let slug = slug.clone();
-        
-        #[cfg(target_arch = "wasm32")]
-        {
-            wasm_bindgen_futures::spawn_local(async move {
-                loading.set(true);
-                error.set(None);
-                
-                match get_blog_with_slug(slug).await {
-                    Ok(blog_post) => {
-                        post.set(Some(blog_post));
-                    }
-                    Err(err) => {
-                        error.set(Some(format!("Failed to load blog post: {}", err)));
-                    }
-                }
-                
-                loading.set(false);
+/// Click handler for the copy buttons emitted by the server-side highlighter
+/// and the anchor links on headings. They arrive inside
+/// `dangerous_inner_html`, so they can't carry Dioxus event handlers; one
+/// delegated listener on the document covers all of them.
+const COPY_SCRIPT: &str = r#"
+if (!window.__copyInstalled) {
+    window.__copyInstalled = true;
+    document.addEventListener("click", (event) => {
+        const button = event.target.closest(".code-copy");
+        if (button) {
+            const code = button.closest(".code-block").querySelector("code");
+            navigator.clipboard.writeText(code.innerText).then(() => {
+                button.textContent = "Copied";
+                setTimeout(() => { button.textContent = "Copy"; }, 2000);
             });
+            return;
         }
-        
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            // For non-WASM builds, just set an error message
-            error.set(Some("Blog detail view not available in non-WASM builds".to_string()));
-            loading.set(false);
+        const anchor = event.target.closest(".heading-anchor");
+        if (anchor) {
+            event.preventDefault();
+            history.replaceState(history.state, "", anchor.getAttribute("href"));
+            navigator.clipboard.writeText(location.href).then(() => {
+                anchor.dataset.copied = "";
+                setTimeout(() => { delete anchor.dataset.copied; }, 2000);
+            });
         }
     });
+}
+"#;
+
+/// ID of the element wrapping the post body, which the reading progress and
+/// the table of contents follow.
+const POST_BODY_ID: &str = "post-body";
+
+/// Fewest headings worth a table of contents.
+const MIN_TOC_HEADINGS: usize = 2;
+
+/// Prefer the server-rendered body; fall back to rendering the Markdown here
+/// (without highlighting) if the post came from somewhere that didn't fill it.
+fn post_body_html(post: &BlogPost) -> String {
+    if post.content_html.is_empty() {
+        render_markdown(&post.content)
+    } else {
+        post.content_html.clone()
+    }
+}
+
+/// `BlogPosting` structured data for a post.
+fn post_json_ld(post: &BlogPost) -> Value {
+    let url = absolute_url(&Route::BlogPostDetail { slug: post.slug.clone() }.to_string());
+    let mut data = json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": post.title,
+        "description": post.excerpt,
+        "url": url,
+        "mainEntityOfPage": url,
+        "datePublished": post.published_at,
+        "dateModified": post.updated_at.as_deref().unwrap_or(&post.published_at),
+        "author": { "@type": "Person", "name": post.author },
+        "keywords": post.tags.join(", "),
+        "wordCount": post.word_count,
+        "timeRequired": format!("PT{}M", post.reading_time),
+    });
+    if let Some(image) = post.cover_image.as_deref().filter(|image| !image.is_empty()) {
+        data["image"] = json!(absolute_url(image));
+    }
+    if let Some(series) = &post.series {
+        data["isPartOf"] = json!({
+            "@type": "CreativeWorkSeries",
+            "name": series.title,
+            "url": absolute_url(&Route::BlogSeries { slug: series.slug.clone() }.to_string()),
+        });
+    }
+    data
+}
+
+// 2. Blog Post Detail Component
+#[component]
+pub fn BlogPostDetail(slug: ReadOnlySignal<String>) -> Element {
+    use_effect(|| {
+        document::eval(COPY_SCRIPT);
+    });
 
     rsx! {
         div { class: "max-w-6xl mx-auto py-8",
-            // Loading state
-            if *loading.read() {
-                div { class: "flex justify-center py-12",
-                    div {
-                        class: "animate-spin rounded-full h-12 w-12 border-t-2 border-b-2 border-blue-500",
-                        aria_label: "Loading...",
+            SuspenseBoundary {
+                fallback: |_| rsx! {
+                    div { class: "flex justify-center py-12",
+                        div {
+                            class: "animate-spin rounded-full h-12 w-12 border-t-2 border-b-2 border-blue-500",
+                            aria_label: "Loading...",
+                        }
                     }
-                }
+                },
+                BlogPostArticle { slug }
+            }
+        }
+    }
+}
+
+/// The post itself and the links under it, fetched through a server future
+/// so the server renders the article in full and the client hydrates from
+/// the same data.
+///
+/// A `?preview=` token in the address shows the post even if it is a draft
+/// or scheduled. The router doesn't see it: declaring it on the route would
+/// add `?preview=` to every post URL.
+#[component]
+fn BlogPostArticle(slug: ReadOnlySignal<String>) -> Element {
+    // One future for both: a second one would resolve in a later streaming
+    // frame, too late for the head tags. Drafts have no place among the
+    // public posts yet.
+    let page = use_server_future(move || {
+        let slug = slug();
+        let preview = preview_token(&history().current_route());
+        async move {
+            match preview {
+                Some(token) => (get_blog_preview(slug, token).await, Ok(PostLinks::default())),
+                None => tokio::join!(get_blog_with_slug(slug.clone()), get_post_links(slug)),
             }
-            // Error state
-            if let Some(err) = error.read().as_ref() {
+        }
+    })?;
+    let spy = use_scroll_spy(POST_BODY_ID);
+    let previewing = preview_token(&history().current_route()).is_some();
+
+    let (post, links) = match &*page.read() {
+        Some((Ok(post), links)) => (post.clone(), links.clone()),
+        Some((Err(err), _)) => {
+            return rsx! {
                 div {
                     class: "bg-red-100 border-l-4 border-red-500 text-red-700 p-4 mb-6",
                     role: "alert",
                     p { class: "font-bold", "Error" }
-                    p { "{err}" }
+                    p { "Failed to load blog post: {err}" }
                 }
+            };
+        }
+        None => return rsx! {},
+    };
+    let headings = outline(&post.content);
+    let has_toc = headings.len() >= MIN_TOC_HEADINGS;
+
+    rsx! {
+        ReadingProgress { spy }
+        Seo {
+            // Head tags are fixed once mounted; remount when the post changes.
+            key: "{post.slug}",
+            title: post.title.clone(),
+            description: post.excerpt.clone(),
+            path: Route::BlogPostDetail { slug: post.slug.clone() }.to_string(),
+            image: post.cover_image.clone(),
+            og_type: "article",
+            json_ld: post_json_ld(&post),
+        }
+        if previewing {
+            document::Meta { name: "robots", content: "noindex" }
+            div {
+                class: "bg-yellow-100 border-l-4 border-yellow-500 text-yellow-800 p-4 mb-6",
+                role: "status",
+                p { class: "font-bold", "Preview" }
+                p { "This post may not be published yet. Please don't share this link further." }
             }
-            // Blog post content
-            if let Some(post) = post.read().as_ref() {
-                article {
-                    // Back button
-                    Link {
-                        to: Route::Blog {},
-                        class: "inline-flex items-center text-CustomHover dark:text-blue-400 hover:underline mb-8",
-                        svg {
-                            class: "w-5 h-5 mr-2",
-                            fill: "none",
-                            stroke: "currentColor",
-                            view_box: "0 0 24 24",
-                            path {
-                                stroke_linecap: "round",
-                                stroke_linejoin: "round",
-                                stroke_width: "2",
-                                d: "M10 19l-7-7m0 0l7-7m-7 7h18",
+        }
+        div { class: "lg:flex lg:gap-10",
+            article { class: "flex-1 min-w-0",
+                // Back button
+                Link {
+                    to: Route::Blog { page: 1 },
+                    class: "inline-flex items-center text-CustomHover dark:text-blue-400 hover:underline mb-8",
+                    svg {
+                        class: "w-5 h-5 mr-2",
+                        fill: "none",
+                        stroke: "currentColor",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            stroke_width: "2",
+                            d: "M10 19l-7-7m0 0l7-7m-7 7h18",
+                        }
+                    }
+                    "Back to Blog"
+                }
+                // Header
+                header { class: "mb-8",
+                    // Tags
+                    div { class: "flex flex-wrap gap-2 mb-4",
+                        for tag in &post.tags {
+                            Link {
+                                to: Route::BlogTag { tag: tag_slug(tag) },
+                                class: "px-3 py-1 bg-blue-100 dark:bg-blue-900 text-blue-800 dark:text-blue-200 hover:bg-blue-200 dark:hover:bg-blue-800 rounded-full text-sm font-medium",
+                                "{tag}"
                             }
                         }
-                        "Back to Blog"
                     }
-                    // Header
-                    header { class: "mb-8",
-                        // Tags
-                        div { class: "flex flex-wrap gap-2 mb-4",
-                            for tag in &post.tags {
-                                span { class: "px-3 py-1 bg-blue-100 dark:bg-blue-900 text-blue-800 dark:text-blue-200 rounded-full text-sm font-medium",
-                                    "{tag}"
+                    // Title
+                    h1 { class: "text-3xl md:text-4xl font-bold text-CustomAccent dark:text-white mb-4",
+                        "{post.title}"
+                    }
+                    // Meta
+                    div { class: "flex items-center text-sm text-gray-300 dark:text-gray-400",
+                        // Author
+                        span { class: "flex items-center mr-6",
+                            svg {
+                                class: "w-4 h-4 mr-1",
+                                fill: "none",
+                                stroke: "currentColor",
+                                view_box: "0 0 24 24",
+                                path {
+                                    stroke_linecap: "round",
+                                    stroke_linejoin: "round",
+                                    stroke_width: "2",
+                                    d: "M16 7a4 4 0 11-8 0 4 4 0 018 0zM12 14a7 7 0 00-7 7h14a7 7 0 00-7-7z",
                                 }
                             }
+                            "{post.author}"
                         }
-                        // Title
-                        h1 { class: "text-3xl md:text-4xl font-bold text-CustomAccent dark:text-white mb-4",
-                            "{post.title}"
-                        }
-                        // Meta
-                        div { class: "flex items-center text-sm text-gray-300 dark:text-gray-400",
-                            // Author
-                            span { class: "flex items-center mr-6",
-                                svg {
-                                    class: "w-4 h-4 mr-1",
-                                    fill: "none",
-                                    stroke: "currentColor",
-                                    view_box: "0 0 24 24",
-                                    path {
-                                        stroke_linecap: "round",
-                                        stroke_linejoin: "round",
-                                        stroke_width: "2",
-                                        d: "M16 7a4 4 0 11-8 0 4 4 0 018 0zM12 14a7 7 0 00-7 7h14a7 7 0 00-7-7z",
-                                    }
+                        // Date
+                        span { class: "flex items-center",
+                            svg {
+                                class: "w-4 h-4 mr-1",
+                                fill: "none",
+                                stroke: "currentColor",
+                                view_box: "0 0 24 24",
+                                path {
+                                    stroke_linecap: "round",
+                                    stroke_linejoin: "round",
+                                    stroke_width: "2",
+                                    d: "M8 7V3m8 4V3m-9 8h10M5 21h14a2 2 0 002-2V7a2 2 0 00-2-2H5a2 2 0 00-2 2v12a2 2 0 002 2z",
                                 }
-                                "{post.author}"
                             }
-                            // Date
-                            span { class: "flex items-center",
-                                svg {
-                                    class: "w-4 h-4 mr-1",
-                                    fill: "none",
-                                    stroke: "currentColor",
-                                    view_box: "0 0 24 24",
-                                    path {
-                                        stroke_linecap: "round",
-                                        stroke_linejoin: "round",
-                                        stroke_width: "2",
-                                        d: "M8 7V3m8 4V3m-9 8h10M5 21h14a2 2 0 002-2V7a2 2 0 00-2-2H5a2 2 0 00-2 2v12a2 2 0 002 2z",
-                                    }
-                                }
-                                "{post.published_at}"
-                                if let Some(updated) = &post.updated_at {
-                                    span { class: "ml-2 italic", "(updated {updated})" }
-                                }
+                            "{post.published_at}"
+                            if let Some(updated) = &post.updated_at {
+                                span { class: "ml-2 italic", "(updated {updated})" }
                             }
                         }
-                    }
-                    // Cover Image
-                    if let Some(image_url) = &post.cover_image {
-                        div { class: "mb-8 rounded-lg overflow-hidden",
-                            img {
-                                class: "w-full h-auto object-cover",
-                                src: "{image_url}",
-                                alt: "Cover image for {post.title}",
+                        // Reading time
+                        span { class: "flex items-center ml-6", title: "{post.word_count} words",
+                            svg {
+                                class: "w-4 h-4 mr-1",
+                                fill: "none",
+                                stroke: "currentColor",
+                                view_box: "0 0 24 24",
+                                path {
+                                    stroke_linecap: "round",
+                                    stroke_linejoin: "round",
+                                    stroke_width: "2",
+                                    d: "M12 8v4l3 3m6-3a9 9 0 11-18 0 9 9 0 0118 0z",
+                                }
                             }
+                            "{post.reading_time} min read"
                         }
                     }
-                    // Content
-                    div { class: "prose dark:prose-invert prose-lg max-w-none",
-                        // In a real app, you would use a markdown renderer here
-                        // For this example, we'll just render paragraphs
-                        for paragraph in post.content.split("\n\n") {
-                            p { class: "mb-6", "{paragraph}" }
+                }
+                if let Some(series) = links.as_ref().ok().and_then(|links| links.series.clone()) {
+                    SeriesNavigator { series, slug: post.slug.clone() }
+                }
+                // Cover Image
+                if let Some(image_url) = &post.cover_image {
+                    div { class: "mb-8 rounded-lg overflow-hidden",
+                        ResponsiveImage {
+                            class: "w-full h-auto object-cover",
+                            src: image_url.clone(),
+                            variants: post.cover_image_variants.clone(),
+                            alt: "Cover image for {post.title}",
+                            sizes: if has_toc { "(min-width: 1152px) 856px, 100vw" } else { "(min-width: 1152px) 1152px, 100vw" },
                         }
                     }
-                    // Footer
-                    footer { class: "mt-12 pt-8 border-t border-gray-200 dark:border-gray-700",
-                        div { class: "flex justify-between items-center",
-                            // Share buttons
-                            div { class: "flex space-x-4" }
-                            // Back to top
-                            button {
-                                onclick: move |_| {
-                                    #[cfg(target_arch = "wasm32")]
-                                    {
-                                        if let Some(window) = web_sys::window() {
-                                            window.scroll_to_with_x_and_y(0.0, 0.0);
-                                        }
-                                    }
-                                },
-                                class: "text-CustomHover cursor-pointer dark:text-blue-400 hover:underline flex items-center",
-                                svg {
-                                    class: "w-4 h-4 mr-1",
-                                    fill: "none",
-                                    stroke: "currentColor",
-                                    view_box: "0 0 24 24",
-                                    path {
-                                        stroke_linecap: "round",
-                                        stroke_linejoin: "round",
-                                        stroke_width: "2",
-                                        d: "M5 10l7-7m0 0l7 7m-7-7v18",
-                                    }
+                }
+                // Content
+                div {
+                    id: POST_BODY_ID,
+                    class: "prose dark:prose-invert prose-lg max-w-none",
+                    dangerous_inner_html: post_body_html(&post),
+                }
+                // Footer
+                footer { class: "mt-12 pt-8 border-t border-gray-200 dark:border-gray-700",
+                    div { class: "flex justify-end items-center",
+                        // Back to top
+                        button {
+                            onclick: move |_| scroll_to(ScrollTarget::Top),
+                            class: "text-CustomHover cursor-pointer dark:text-blue-400 hover:underline flex items-center",
+                            svg {
+                                class: "w-4 h-4 mr-1",
+                                fill: "none",
+                                stroke: "currentColor",
+                                view_box: "0 0 24 24",
+                                path {
+                                    stroke_linecap: "round",
+                                    stroke_linejoin: "round",
+                                    stroke_width: "2",
+                                    d: "M5 10l7-7m0 0l7 7m-7-7v18",
                                 }
-                                "Back to top"
+                            }
+                            "Back to top"
+                        }
+                    }
+                }
+            }
+            if has_toc {
+                aside { class: "hidden lg:block w-64 shrink-0",
+                    TableOfContents { headings, section: spy.section }
+                }
+            }
+        }
+        // Nowhere to go is better than an error under a post that loaded fine.
+        if let Ok(links) = links {
+            MorePosts { links }
+        }
+        if !previewing {
+            Webmentions { key: "mentions-{post.id}", post_id: post.id }
+            Comments { key: "comments-{post.id}", post_id: post.id }
+        }
+    }
+}
+
+/// The posts before and after this one, and the ones most like it.
+#[component]
+fn MorePosts(links: PostLinks) -> Element {
+    rsx! {
+        if links.previous.is_some() || links.next.is_some() {
+            nav { class: "grid gap-4 sm:grid-cols-2 mt-12", aria_label: "More posts",
+                if let Some(post) = links.previous {
+                    NeighbourLink { post, label: "Previous post", newer: false }
+                }
+                if let Some(post) = links.next {
+                    NeighbourLink { post, label: "Next post", newer: true }
+                }
+            }
+        }
+        if !links.related.is_empty() {
+            section { class: "mt-16",
+                h2 { class: "text-2xl font-bold text-gray-900 dark:text-white mb-6", "Related posts" }
+                div { class: "grid gap-8 md:grid-cols-2 lg:grid-cols-3",
+                    for post in links.related {
+                        BlogPostCard { key: "{post.id}", post }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// "Part 2 of 5": where this post sits in its series, with every part listed.
+#[component]
+fn SeriesNavigator(series: PostSeries, slug: String) -> Element {
+    let part = series.part_number(&slug);
+    let next = part.and_then(|part| series.parts.get(part));
+
+    rsx! {
+        nav {
+            class: "mb-8 rounded-lg border border-amber-200 dark:border-amber-800 bg-amber-50 dark:bg-amber-900/20 p-5",
+            aria_label: "Series",
+            if let Some(part) = part {
+                p { class: "text-sm font-semibold text-amber-800 dark:text-amber-200",
+                    "Part {part} of {series.parts.len()}"
+                }
+            }
+            Link {
+                to: Route::BlogSeries { slug: series.series.slug.clone() },
+                class: "text-lg font-bold text-gray-900 dark:text-white hover:underline",
+                "{series.series.title}"
+            }
+            ol { class: "mt-3 space-y-1 text-sm list-decimal list-inside text-gray-700 dark:text-gray-300",
+                for entry in series.parts.iter() {
+                    li { key: "{entry.slug}",
+                        if entry.slug == slug {
+                            span { class: "font-semibold text-gray-900 dark:text-white", aria_current: "page", "{entry.title}" }
+                        } else {
+                            Link {
+                                to: Route::BlogPostDetail { slug: entry.slug.clone() },
+                                class: "text-indigo-600 dark:text-indigo-400 hover:underline",
+                                "{entry.title}"
                             }
                         }
                     }
                 }
             }
+            if let Some(next) = next {
+                Link {
+                    to: Route::BlogPostDetail { slug: next.slug.clone() },
+                    class: "inline-block mt-3 text-sm font-medium text-indigo-600 dark:text-indigo-400 hover:underline",
+                    "Next in this series: {next.title} →"
+                }
+            }
+        }
+    }
+}
+
+/// A link to the post published just before (`newer: false`) or after this
+/// one, on the matching side.
+#[component]
+fn NeighbourLink(post: BlogPostSummary, label: &'static str, newer: bool) -> Element {
+    rsx! {
+        Link {
+            to: Route::BlogPostDetail { slug: post.slug.clone() },
+            class: if newer {
+                "block sm:col-start-2 text-right rounded-lg border border-gray-200 dark:border-gray-700 p-4 hover:border-indigo-500 dark:hover:border-indigo-400"
+            } else {
+                "block rounded-lg border border-gray-200 dark:border-gray-700 p-4 hover:border-indigo-500 dark:hover:border-indigo-400"
+            },
+            rel: if newer { "next" } else { "prev" },
+            span { class: "block text-sm text-gray-500 dark:text-gray-400 mb-1",
+                if newer { "{label} →" } else { "← {label}" }
+            }
+            span { class: "block font-semibold text-gray-900 dark:text-white", "{post.title}" }
         }
     }
 }
 
+/// A thin bar along the top of the window that fills as the post is read.
+#[component]
+fn ReadingProgress(spy: ScrollSpy) -> Element {
+    let percent = (spy.progress)() * 100.0;
+
+    rsx! {
+        div {
+            class: "fixed top-0 left-0 z-[60] h-1 bg-indigo-600 transition-[width] duration-100",
+            style: "width: {percent}%",
+            role: "progressbar",
+            aria_label: "Reading progress",
+            aria_valuemin: "0",
+            aria_valuemax: "100",
+            aria_valuenow: "{percent.round()}",
+        }
+    }
+}
+
+/// The post's sections, following the reader down the page with the current
+/// one highlighted.
+#[component]
+fn TableOfContents(headings: Vec<Heading>, section: ReadOnlySignal<Option<String>>) -> Element {
+    rsx! {
+        nav { class: "sticky top-24 max-h-[calc(100vh-8rem)] overflow-y-auto", aria_label: "Table of contents",
+            h2 { class: "text-sm font-semibold uppercase tracking-wide text-gray-500 dark:text-gray-400 mb-3", "On this page" }
+            ul { class: "space-y-1 text-sm border-l border-gray-200 dark:border-gray-700",
+                for heading in headings {
+                    li { key: "{heading.id}",
+                        a {
+                            href: "#{heading.id}",
+                            class: if section().as_deref() == Some(heading.id.as_str()) {
+                                "block -ml-px border-l-2 border-indigo-600 py-1 text-indigo-600 dark:text-indigo-400 font-medium"
+                            } else {
+                                "block -ml-px border-l-2 border-transparent py-1 text-gray-600 dark:text-gray-400 hover:text-gray-900 dark:hover:text-white"
+                            },
+                            // Indent subsections under their section.
+                            style: if heading.level > 2 { "padding-left: 1.75rem" } else { "padding-left: 0.75rem" },
+                            aria_current: if section().as_deref() == Some(heading.id.as_str()) { "location" } else { "false" },
+                            onclick: {
+                                let id = heading.id.clone();
+                                move |evt: MouseEvent| {
+                                    evt.prevent_default();
+                                    scroll_to(ScrollTarget::Element(id.clone()));
+                                }
+                            },
+                            "{heading.text}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use dioxus::prelude::*;
+use crate::api::auth::access_token;
+use crate::api::comments::{get_comments, submit_comment, Comment, CommentStatus, NewComment, MAX_COMMENT_CHARS};
+use crate::content::render_comment;
+
+/// Replies deeper than this are shown at this depth, so long threads don't
+/// squeeze into a narrow column.
+const MAX_INDENT: usize = 3;
+
+const INPUT_CLASS: &str = "w-full px-3 py-2 rounded-md border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-800 text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-indigo-500";
+
+/// Comments under a post, threaded, with a form for new ones. They load once
+/// the page is in the browser, so the post itself never waits on them.
+#[component]
+pub fn Comments(post_id: i32) -> Element {
+    let mut comments = use_resource(move || get_comments(post_id));
+    // The comment whose reply form is open.
+    let replying_to = use_signal::<Option<i32>>(|| None);
+    let on_posted = move |status: CommentStatus| {
+        if status == CommentStatus::Approved {
+            comments.restart();
+        }
+    };
+
+    let count = match &*comments.read() {
+        Some(Ok(list)) => list.len(),
+        _ => 0,
+    };
+
+    rsx! {
+        section { id: "comments", class: "mt-12 pt-8 border-t border-gray-200 dark:border-gray-700",
+            h2 { class: "text-2xl font-bold mb-6",
+                if count > 0 { "Comments ({count})" } else { "Comments" }
+            }
+            match &*comments.read() {
+                None => rsx! { p { class: "text-gray-500", "Loading comments..." } },
+                Some(Err(_)) => rsx! { p { class: "text-gray-500", "Comments couldn't be loaded right now." } },
+                Some(Ok(list)) if list.is_empty() => rsx! {
+                    p { class: "text-gray-500 mb-6", "No comments yet. Be the first to share your thoughts." }
+                },
+                Some(Ok(list)) => rsx! {
+                    CommentThread { comments: list.clone(), parent: None, depth: 0, post_id, replying_to, on_posted }
+                },
+            }
+            div { class: "mt-8",
+                h3 { class: "text-lg font-semibold mb-3", "Leave a comment" }
+                CommentForm { post_id, parent_id: None, on_posted }
+            }
+        }
+    }
+}
+
+/// The replies to `parent`, or the top-level comments when it is `None`,
+/// each followed by its own replies.
+#[component]
+fn CommentThread(
+    comments: Vec<Comment>,
+    parent: Option<i32>,
+    depth: usize,
+    post_id: i32,
+    replying_to: Signal<Option<i32>>,
+    on_posted: EventHandler<CommentStatus>,
+) -> Element {
+    let thread: Vec<Comment> = comments.iter().filter(|comment| comment.parent_id == parent).cloned().collect();
+    if thread.is_empty() {
+        return rsx! {};
+    }
+    let indent = if depth > 0 && depth <= MAX_INDENT { "ml-4 sm:ml-6 pl-4 border-l border-gray-200 dark:border-gray-700" } else { "" };
+
+    rsx! {
+        ol { class: "space-y-6 {indent}",
+            for comment in thread {
+                li { key: "{comment.id}", id: "comment-{comment.id}",
+                    article {
+                        header { class: "flex items-baseline gap-2 text-sm",
+                            span { class: "font-semibold text-gray-900 dark:text-white", "{comment.author_name}" }
+                            time { class: "text-gray-500", datetime: "{comment.created_at}",
+                                "{comment.created_at.get(..10).unwrap_or(&comment.created_at)}"
+                            }
+                        }
+                        div {
+                            class: "prose prose-sm dark:prose-invert max-w-none mt-1",
+                            dangerous_inner_html: "{render_comment(&comment.body)}",
+                        }
+                        button {
+                            class: "mt-1 text-sm text-indigo-600 hover:underline",
+                            r#type: "button",
+                            onclick: move |_| {
+                                let open = replying_to() == Some(comment.id);
+                                replying_to.set(if open { None } else { Some(comment.id) });
+                            },
+                            if replying_to() == Some(comment.id) { "Cancel reply" } else { "Reply" }
+                        }
+                        if replying_to() == Some(comment.id) {
+                            div { class: "mt-3",
+                                CommentForm {
+                                    post_id,
+                                    parent_id: Some(comment.id),
+                                    on_posted: move |status| {
+                                        replying_to.set(None);
+                                        on_posted.call(status);
+                                    },
+                                }
+                            }
+                        }
+                    }
+                    div { class: "mt-4",
+                        CommentThread {
+                            comments: comments.clone(),
+                            parent: Some(comment.id),
+                            depth: depth + 1,
+                            post_id,
+                            replying_to,
+                            on_posted,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A new comment, or a reply to `parent_id`.
+#[component]
+fn CommentForm(post_id: i32, parent_id: Option<i32>, on_posted: EventHandler<CommentStatus>) -> Element {
+    let mut name = use_signal(String::new);
+    let mut body = use_signal(String::new);
+    let mut sending = use_signal(|| false);
+    let mut status = use_signal::<Option<Result<String, String>>>(|| None);
+    let field_id = |field: &str| format!("comment-{}-{}", field, parent_id.map_or("new".to_string(), |id| id.to_string()));
+
+    let send = move |_| {
+        let comment = NewComment { post_id, parent_id, author_name: name(), body: body() };
+        if let Err(e) = comment.validate() {
+            status.set(Some(Err(e)));
+            return;
+        }
+
+        sending.set(true);
+        spawn(async move {
+            match submit_comment(access_token().unwrap_or_default(), comment).await {
+                Ok(saved) => {
+                    body.set(String::new());
+                    let message = match saved {
+                        CommentStatus::Approved => "Your comment is posted.",
+                        _ => "Thanks! Your comment will appear once it has been approved.",
+                    };
+                    status.set(Some(Ok(message.to_string())));
+                    on_posted.call(saved);
+                }
+                Err(e) => status.set(Some(Err(e.to_string()))),
+            }
+            sending.set(false);
+        });
+    };
+
+    rsx! {
+        form {
+            class: "space-y-3",
+            onsubmit: move |evt| evt.prevent_default(),
+            div {
+                label { class: "block text-sm font-medium mb-1", r#for: "{field_id(\"name\")}", "Name" }
+                input {
+                    id: "{field_id(\"name\")}",
+                    class: INPUT_CLASS,
+                    autocomplete: "name",
+                    maxlength: 80,
+                    value: "{name}",
+                    oninput: move |evt| name.set(evt.value()),
+                }
+            }
+            div {
+                label { class: "block text-sm font-medium mb-1", r#for: "{field_id(\"body\")}", "Comment" }
+                textarea {
+                    id: "{field_id(\"body\")}",
+                    class: INPUT_CLASS,
+                    rows: 4,
+                    maxlength: MAX_COMMENT_CHARS as i64,
+                    value: "{body}",
+                    oninput: move |evt| body.set(evt.value()),
+                }
+                p { class: "mt-1 text-xs text-gray-500",
+                    "**bold**, *italic*, `code`, > quotes, lists and links work. Comments are moderated."
+                }
+            }
+            div { class: "flex items-center gap-4",
+                button {
+                    class: "rounded-md bg-indigo-600 px-4 py-2 text-sm font-medium text-white hover:bg-indigo-700 disabled:opacity-50",
+                    r#type: "submit",
+                    disabled: sending(),
+                    onclick: send,
+                    if sending() { "Sending..." } else if parent_id.is_some() { "Post reply" } else { "Post comment" }
+                }
+                match status() {
+                    Some(Ok(message)) => rsx! { span { class: "text-sm text-green-600", "{message}" } },
+                    Some(Err(message)) => rsx! { span { class: "text-sm text-red-600", "{message}" } },
+                    None => rsx! {},
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use dioxus::prelude::*;
+use crate::api::get_series_archive;
+use crate::api::env::absolute_url;
+use crate::components::Seo;
+use crate::views::blog::BlogPostCard;
+use crate::views::blog_tags::spinner;
+use crate::Route;
+use serde_json::json;
+
+/// `/blog/series/:slug`: a series and its posts in reading order.
+#[component]
+pub fn BlogSeries(slug: ReadOnlySignal<String>) -> Element {
+    rsx! {
+        div { class: "max-w-6xl mx-auto",
+            Link {
+                to: Route::Blog { page: 1 },
+                class: "inline-flex items-center text-indigo-600 dark:text-indigo-400 hover:underline mb-8",
+                "← All posts"
+            }
+            SuspenseBoundary {
+                fallback: |_| spinner("Loading series..."),
+                SeriesPostList { key: "{slug}", slug }
+            }
+        }
+    }
+}
+
+#[component]
+fn SeriesPostList(slug: ReadOnlySignal<String>) -> Element {
+    let archive = use_server_future(move || get_series_archive(slug()))?;
+
+    let archive = match &*archive.read() {
+        Some(Ok(archive)) => archive.clone(),
+        Some(Err(e)) => {
+            return rsx! {
+                div { class: "p-6 mb-8 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-red-800 dark:text-red-200 rounded-lg",
+                    strong { "Error loading series: " }
+                    "{e}"
+                }
+            };
+        }
+        None => return rsx! {},
+    };
+    let series = archive.series;
+    let path = Route::BlogSeries { slug: series.slug.clone() }.to_string();
+    let description = if series.description.is_empty() {
+        format!("Every part of the {} series.", series.title)
+    } else {
+        series.description.clone()
+    };
+
+    rsx! {
+        Seo {
+            key: "{series.slug}",
+            title: series.title.clone(),
+            description: description.clone(),
+            path: path.clone(),
+            json_ld: json!({
+                "@context": "https://schema.org",
+                "@type": "CreativeWorkSeries",
+                "name": series.title,
+                "description": description,
+                "url": absolute_url(&path),
+                "hasPart": archive.posts.iter().map(|post| json!({
+                    "@type": "BlogPosting",
+                    "headline": post.title,
+                    "url": absolute_url(&Route::BlogPostDetail { slug: post.slug.clone() }.to_string()),
+                })).collect::<Vec<_>>(),
+            }),
+        }
+        p { class: "text-sm font-semibold uppercase tracking-wide text-indigo-600 dark:text-indigo-400 mb-2", "Series" }
+        h1 { class: "text-3xl sm:text-4xl font-bold mb-4", "{series.title}" }
+        if !series.description.is_empty() {
+            p { class: "text-lg text-gray-600 dark:text-gray-300 mb-4 max-w-3xl", "{series.description}" }
+        }
+        p { class: "text-gray-600 dark:text-gray-300 mb-8",
+            if archive.posts.len() == 1 {
+                "1 part"
+            } else {
+                "{archive.posts.len()} parts"
+            }
+        }
+        if archive.posts.is_empty() {
+            p { class: "text-gray-500 dark:text-gray-400 py-20 text-center", "No posts in this series yet." }
+        } else {
+            div { class: "grid gap-8 md:grid-cols-2 lg:grid-cols-3 pb-10",
+                for post in archive.posts {
+                    BlogPostCard { key: "{post.id}", post }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use dioxus::prelude::*;
+use crate::api::{get_tag_archive, get_tags, TagCount};
+use crate::api::env::absolute_url;
+use crate::components::Seo;
+use crate::views::blog::BlogPostCard;
+use crate::Route;
+use serde_json::json;
+
+/// Text size for a tag in the cloud, scaled by how many posts use it
+/// relative to the most used tag.
+fn cloud_size(count: u32, max: u32) -> &'static str {
+    match count * 4 / max.max(1) {
+        0 => "text-sm",
+        1 => "text-base",
+        2 => "text-lg",
+        3 => "text-xl",
+        _ => "text-2xl",
+    }
+}
+
+pub(super) fn spinner(label: &'static str) -> Element {
+    rsx! {
+        div { class: "flex justify-center py-20",
+            div { class: "flex flex-col items-center",
+                div { class: "animate-spin rounded-full h-16 w-16 border-t-4 border-b-4 border-indigo-600 dark:border-indigo-400" }
+                p { class: "mt-4 text-gray-600 dark:text-gray-400", "{label}" }
+            }
+        }
+    }
+}
+
+/// `/blog/tags`: every tag with its post count.
+#[component]
+pub fn BlogTags() -> Element {
+    rsx! {
+        Seo {
+            title: "Blog Tags",
+            description: "Browse blog posts by topic.",
+            path: Route::BlogTags {}.to_string(),
+            json_ld: json!({
+                "@context": "https://schema.org",
+                "@type": "CollectionPage",
+                "name": "Blog Tags",
+                "url": absolute_url(&Route::BlogTags {}.to_string()),
+            }),
+        }
+        div { class: "max-w-6xl mx-auto",
+            h1 { class: "text-3xl sm:text-4xl font-bold mb-8", "Tags" }
+            SuspenseBoundary {
+                fallback: |_| spinner("Loading tags..."),
+                TagCloud {}
+            }
+        }
+    }
+}
+
+#[component]
+fn TagCloud() -> Element {
+    let tags = use_server_future(get_tags)?;
+
+    let tags: Vec<TagCount> = match &*tags.read() {
+        Some(Ok(tags)) => tags.clone(),
+        Some(Err(e)) => {
+            return rsx! {
+                div { class: "p-6 mb-8 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-red-800 dark:text-red-200 rounded-lg",
+                    strong { "Error loading tags: " }
+                    "{e}"
+                }
+            };
+        }
+        None => Vec::new(),
+    };
+    let max = tags.iter().map(|tag| tag.count).max().unwrap_or(1);
+
+    rsx! {
+        if tags.is_empty() {
+            p { class: "text-gray-500 dark:text-gray-400 py-20 text-center", "No tags yet." }
+        } else {
+            ul { class: "flex flex-wrap items-baseline gap-x-6 gap-y-4",
+                for tag in tags {
+                    li { key: "{tag.slug}",
+                        Link {
+                            to: Route::BlogTag { tag: tag.slug.clone() },
+                            class: "{cloud_size(tag.count, max)} font-medium text-indigo-600 dark:text-indigo-400 hover:underline",
+                            "{tag.name}"
+                        }
+                        span { class: "ml-1 text-sm text-gray-500 dark:text-gray-400", "({tag.count})" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `/blog/tags/:tag`: the posts carrying one tag. Any spelling of the tag
+/// works in the URL; the canonical URL uses its slug.
+#[component]
+pub fn BlogTag(tag: ReadOnlySignal<String>) -> Element {
+    rsx! {
+        div { class: "max-w-6xl mx-auto",
+            Link {
+                to: Route::BlogTags {},
+                class: "inline-flex items-center text-indigo-600 dark:text-indigo-400 hover:underline mb-8",
+                "← All tags"
+            }
+            SuspenseBoundary {
+                fallback: |_| spinner("Loading blog posts..."),
+                TagArchiveList { key: "{tag}", tag }
+            }
+        }
+    }
+}
+
+#[component]
+fn TagArchiveList(tag: ReadOnlySignal<String>) -> Element {
+    let archive = use_server_future(move || get_tag_archive(tag()))?;
+
+    let archive = match &*archive.read() {
+        Some(Ok(archive)) => archive.clone(),
+        Some(Err(e)) => {
+            return rsx! {
+                h1 { class: "text-3xl sm:text-4xl font-bold mb-8", "{tag}" }
+                div { class: "p-6 mb-8 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-red-800 dark:text-red-200 rounded-lg",
+                    strong { "Error loading posts: " }
+                    "{e}"
+                }
+            };
+        }
+        None => return rsx! {},
+    };
+    let path = Route::BlogTag { tag: archive.slug.clone() }.to_string();
+
+    rsx! {
+        Seo {
+            key: "{archive.slug}",
+            title: format!("Posts tagged \"{}\"", archive.name),
+            description: format!("Blog posts about {}.", archive.name),
+            path: path.clone(),
+            json_ld: json!({
+                "@context": "https://schema.org",
+                "@type": "CollectionPage",
+                "name": format!("Posts tagged \"{}\"", archive.name),
+                "url": absolute_url(&path),
+            }),
+        }
+        div { class: "flex flex-wrap items-baseline justify-between gap-4 mb-8",
+            h1 { class: "text-3xl sm:text-4xl font-bold", "{archive.name}" }
+            a {
+                href: "/tags/{archive.slug}/feed.xml",
+                class: "text-sm text-indigo-600 dark:text-indigo-400 hover:underline",
+                "RSS feed"
+            }
+        }
+        p { class: "text-gray-600 dark:text-gray-300 mb-8",
+            if archive.posts.len() == 1 {
+                "1 post"
+            } else {
+                "{archive.posts.len()} posts"
+            }
+        }
+        div { class: "grid gap-8 md:grid-cols-2 lg:grid-cols-3 pb-10",
+            for post in archive.posts {
+                BlogPostCard { key: "{post.id}", post }
+            }
+        }
+    }
+}
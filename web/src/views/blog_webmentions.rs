@@ -0,0 +1,104 @@
+use dioxus::prelude::*;
+use crate::api::webmentions::{get_webmentions, MentionKind, Webmention};
+
+/// Likes, reposts, replies and mentions of a post from other sites. Like
+/// the comments, they load once the page is in the browser, and nothing is
+/// shown until there is at least one.
+#[component]
+pub fn Webmentions(post_id: i32) -> Element {
+    let mentions = use_resource(move || get_webmentions(post_id));
+
+    let mentions = match &*mentions.read() {
+        Some(Ok(mentions)) if !mentions.is_empty() => mentions.clone(),
+        _ => return rsx! {},
+    };
+    let of_kind = |kind: MentionKind| mentions.iter().filter(|mention| mention.kind == kind).cloned().collect::<Vec<_>>();
+    let likes = of_kind(MentionKind::Like);
+    let reposts = of_kind(MentionKind::Repost);
+    let replies: Vec<Webmention> =
+        mentions.iter().filter(|mention| matches!(mention.kind, MentionKind::Reply | MentionKind::Mention)).cloned().collect();
+
+    rsx! {
+        section { id: "webmentions", class: "mt-12 pt-8 border-t border-gray-200 dark:border-gray-700",
+            h2 { class: "text-2xl font-bold mb-6", "Around the web" }
+            if !likes.is_empty() {
+                Faces { label: if likes.len() == 1 { "1 like".to_string() } else { format!("{} likes", likes.len()) }, mentions: likes }
+            }
+            if !reposts.is_empty() {
+                Faces { label: if reposts.len() == 1 { "1 repost".to_string() } else { format!("{} reposts", reposts.len()) }, mentions: reposts }
+            }
+            if !replies.is_empty() {
+                ol { class: "space-y-6 mt-6",
+                    for mention in replies {
+                        li { key: "{mention.id}",
+                            div { class: "flex items-baseline gap-2 text-sm",
+                                Author { mention: mention.clone() }
+                                span { class: "text-gray-500",
+                                    if mention.kind == MentionKind::Reply { "replied" } else { "mentioned this" }
+                                }
+                                a {
+                                    class: "text-gray-500 hover:underline",
+                                    href: "{mention.link()}",
+                                    rel: "nofollow ugc",
+                                    if let Some(published) = &mention.published_at {
+                                        time { datetime: "{published}", "{published.get(..10).unwrap_or(published)}" }
+                                    } else {
+                                        "View"
+                                    }
+                                }
+                            }
+                            if let Some(content) = &mention.content {
+                                p { class: "mt-1 text-gray-700 dark:text-gray-300", "{content}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A row of author photos, or names for authors without one.
+#[component]
+fn Faces(label: String, mentions: Vec<Webmention>) -> Element {
+    rsx! {
+        div { class: "flex flex-wrap items-center gap-2 mb-3",
+            span { class: "text-sm font-medium mr-2", "{label}" }
+            for mention in mentions {
+                a {
+                    key: "{mention.id}",
+                    href: "{mention.link()}",
+                    rel: "nofollow ugc",
+                    title: "{mention.author()}",
+                    if let Some(photo) = &mention.author_photo {
+                        img {
+                            class: "w-8 h-8 rounded-full object-cover",
+                            src: "{photo}",
+                            alt: "{mention.author()}",
+                            loading: "lazy",
+                        }
+                    } else {
+                        span { class: "text-sm text-indigo-600 hover:underline", "{mention.author()}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The author's name, linked to their site when the mention names one.
+#[component]
+fn Author(mention: Webmention) -> Element {
+    let name = mention.author();
+    rsx! {
+        if let Some(photo) = &mention.author_photo {
+            img { class: "w-6 h-6 rounded-full object-cover self-center", src: "{photo}", alt: "", loading: "lazy" }
+        }
+        match &mention.author_url {
+            Some(url) => rsx! {
+                a { class: "font-semibold text-gray-900 dark:text-white hover:underline", href: "{url}", rel: "nofollow ugc", "{name}" }
+            },
+            None => rsx! { span { class: "font-semibold text-gray-900 dark:text-white", "{name}" } },
+        }
+    }
+}
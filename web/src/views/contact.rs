@@ -1,17 +1,34 @@
 use dioxus::prelude::*;
-use serde_json::json;
-use crate::api::contact::{NewContactSubmission, submit_contact_form};
+use crate::components::Seo;
+use crate::Route;
+use crate::api::contact::{
+    get_contact_challenge, submit_contact_form, ContactErrors, ContactForm, ContactReply, MAX_EMAIL_CHARS, MAX_MESSAGE_CHARS, MAX_NAME_CHARS,
+    MAX_SUBJECT_CHARS,
+};
 
 #[component]
 pub fn Contact() -> Element {
-    let mut name = use_signal(|| String::new());
-    let mut email = use_signal(|| String::new());
-    let mut message = use_signal(|| String::new());
+    let mut name = use_signal(String::new);
+    let mut email = use_signal(String::new);
+    let mut subject = use_signal(String::new);
+    let mut message = use_signal(String::new);
+    let mut website = use_signal(String::new);
+    let mut errors = use_signal(ContactErrors::default);
     let mut is_submitting = use_signal(|| false);
     let mut submit_status = use_signal(|| Option::<String>::None);
     let mut status_type = use_signal(|| String::from("info")); // "success", "error", "info"
+    // The form token and its proof of work, worked out while the visitor types.
+    let mut proof = use_resource(move || async move {
+        let challenge = get_contact_challenge().await.ok()?;
+        Some(challenge.solve())
+    });
 
     rsx! {
+        Seo {
+            title: "Contact",
+            description: "Questions about mortgages and finance, or a coding or 3D printing project in mind? Get in touch with Dylan Rayburn.",
+            path: Route::Contact {}.to_string(),
+        }
         div { class: "max-w-6xl mx-auto dark:text-gray-200",
             h1 { class: "text-3xl sm:text-4xl font-bold mb-8", "Get in Touch" }
             p { class: "mb-8 text-lg",
@@ -19,53 +36,92 @@ pub fn Contact() -> Element {
             }
 
             // Contact Form
-            form { 
-                class: "space-y-6 mb-12",
+            form { class: "space-y-6 mb-12 relative", novalidate: true,
 
-                // Name Field
-                div {
-                    label { class: "block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300",
-                        "Name *"
+                // Honeypot: hidden from people, so only bots fill it in.
+                div { class: "absolute -left-[9999px] w-px h-px overflow-hidden", aria_hidden: "true",
+                    label { r#for: "contact-website", "Leave this field empty" }
+                    input {
+                        id: "contact-website",
+                        r#type: "text",
+                        name: "website",
+                        tabindex: "-1",
+                        autocomplete: "off",
+                        value: website.read().clone(),
+                        oninput: move |e| website.set(e.value()),
                     }
+                }
+
+                Field { id: "contact-name", label: "Name *", error: errors.read().name.clone(),
                     input {
-                        class: "w-full px-4 py-3 rounded-lg border border-gray-300 dark:border-gray-600 dark:bg-gray-800 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent transition-all duration-200",
+                        class: input_class(errors.read().name.is_some()),
+                        id: "contact-name",
                         r#type: "text",
                         name: "name",
+                        autocomplete: "name",
                         placeholder: "Your full name",
+                        maxlength: MAX_NAME_CHARS as i64,
                         value: name.read().clone(),
-                        oninput: move |e| name.set(e.value()),
-                        required: true,
+                        oninput: move |e| {
+                            name.set(e.value());
+                            errors.write().name = None;
+                        },
+                        aria_invalid: errors.read().name.is_some(),
+                        aria_describedby: "contact-name-error",
                     }
                 }
 
-                // Email Field
-                div {
-                    label { class: "block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300",
-                        "Email *"
-                    }
+                Field { id: "contact-email", label: "Email *", error: errors.read().email.clone(),
                     input {
-                        class: "w-full px-4 py-3 rounded-lg border border-gray-300 dark:border-gray-600 dark:bg-gray-800 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent transition-all duration-200",
+                        class: input_class(errors.read().email.is_some()),
+                        id: "contact-email",
                         r#type: "email",
                         name: "email",
+                        autocomplete: "email",
                         placeholder: "your.email@example.com",
+                        maxlength: MAX_EMAIL_CHARS as i64,
                         value: email.read().clone(),
-                        oninput: move |e| email.set(e.value()),
-                        required: true,
+                        oninput: move |e| {
+                            email.set(e.value());
+                            errors.write().email = None;
+                        },
+                        aria_invalid: errors.read().email.is_some(),
+                        aria_describedby: "contact-email-error",
                     }
                 }
 
-                // Message Field
-                div {
-                    label { class: "block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300",
-                        "Message *"
+                Field { id: "contact-subject", label: "Subject", error: errors.read().subject.clone(),
+                    input {
+                        class: input_class(errors.read().subject.is_some()),
+                        id: "contact-subject",
+                        r#type: "text",
+                        name: "subject",
+                        placeholder: "What's it about? (optional)",
+                        maxlength: MAX_SUBJECT_CHARS as i64,
+                        value: subject.read().clone(),
+                        oninput: move |e| {
+                            subject.set(e.value());
+                            errors.write().subject = None;
+                        },
+                        aria_invalid: errors.read().subject.is_some(),
+                        aria_describedby: "contact-subject-error",
                     }
+                }
+
+                Field { id: "contact-message", label: "Message *", error: errors.read().message.clone(),
                     textarea {
-                        class: "w-full px-4 py-3 rounded-lg border border-gray-300 dark:border-gray-600 dark:bg-gray-800 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent transition-all duration-200 min-h-[120px] resize-y",
+                        class: "{input_class(errors.read().message.is_some())} min-h-[120px] resize-y",
+                        id: "contact-message",
                         name: "message",
                         placeholder: "Tell us about your project, question, or how we can help you...",
+                        maxlength: MAX_MESSAGE_CHARS as i64,
                         value: message.read().clone(),
-                        oninput: move |e| message.set(e.value()),
-                        required: true,
+                        oninput: move |e| {
+                            message.set(e.value());
+                            errors.write().message = None;
+                        },
+                        aria_invalid: errors.read().message.is_some(),
+                        aria_describedby: "contact-message-error",
                     }
                 }
 
@@ -75,59 +131,41 @@ pub fn Contact() -> Element {
                     r#type: "button",
                     disabled: *is_submitting.read(),
                     onclick: move |_| {
-                        let name_val = name.read().clone();
-                        let email_val = email.read().clone();
-                        let message_val = message.read().clone();
-                        
-                        // Basic validation
-                        if name_val.trim().is_empty() || email_val.trim().is_empty() || message_val.trim().is_empty() {
-                            status_type.set("error".to_string());
-                            submit_status.set(Some("❌ Please fill in all required fields.".to_string()));
-                            return;
-                        }
-                        
-                        // Basic email validation
-                        if !email_val.contains('@') || !email_val.contains('.') {
-                            status_type.set("error".to_string());
-                            submit_status.set(Some("❌ Please enter a valid email address.".to_string()));
+                        let form = ContactForm {
+                            name: name.read().clone(),
+                            email: email.read().clone(),
+                            subject: subject.read().clone(),
+                            message: message.read().clone(),
+                            website: website.read().clone(),
+                        };
+
+                        // The server checks again; this just saves a round trip.
+                        if let Err(invalid) = form.validate() {
+                            errors.set(invalid);
+                            submit_status.set(None);
                             return;
                         }
-                        
-                        // Trigger the same logic as form submission
+
                         is_submitting.set(true);
                         submit_status.set(None);
-                        
+
+                        // Without a solved challenge the message still goes,
+                        // but will be filed as spam.
+                        let proof_sent = proof.read().clone().flatten().unwrap_or_default();
                         spawn(async move {
-                            #[cfg(target_arch = "wasm32")]
-                            let user_agent = {
-                                web_sys::window().and_then(|w| w.navigator().user_agent().ok())
-                            };
-                            #[cfg(not(target_arch = "wasm32"))]
-                            let user_agent = None;
-                            
-                            let submission = NewContactSubmission {
-                                name: name_val,
-                                email: email_val,
-                                message: message_val,
-                                ip_address: None,
-                                user_agent,
-                                subject: Some("Website Contact Form".to_string()),
-                                metadata: Some(json!({
-                                    "source": "website",
-                                    "timestamp": chrono::Utc::now().to_rfc3339()
-                                })),
-                            };
-                            
-                            match submit_contact_form(submission).await {
-                                Ok(_) => {
+                            match submit_contact_form(form, proof_sent).await {
+                                Ok(ContactReply::Sent) => {
                                     status_type.set("success".to_string());
                                     submit_status.set(Some("🎉 Success! Your message has been sent successfully. We'll get back to you soon!".to_string()));
                                     name.set(String::new());
                                     email.set(String::new());
+                                    subject.set(String::new());
                                     message.set(String::new());
-                                    
+                                    errors.set(ContactErrors::default());
+                                    proof.restart();
+
                                     // Auto-dismiss success message after 5 seconds
-                                    let mut submit_status_clone = submit_status.clone();
+                                    let mut submit_status_clone = submit_status;
                                     spawn(async move {
                                         #[cfg(target_arch = "wasm32")]
                                         {
@@ -142,23 +180,22 @@ pub fn Contact() -> Element {
                                         }
                                     });
                                 }
+                                Ok(ContactReply::Invalid(invalid)) => {
+                                    errors.set(invalid);
+                                }
+                                Ok(ContactReply::TooMany) => {
+                                    status_type.set("error".to_string());
+                                    submit_status.set(Some("⏳ You've sent several messages recently. Please wait an hour and try again, or email me directly.".to_string()));
+                                }
                                 Err(error) => {
+                                    tracing::error!("Contact form failed: {}", error);
+                                    // The token may have been used up.
+                                    proof.restart();
                                     status_type.set("error".to_string());
-                                    let error_msg = if error.contains("HTTP 400") {
-                                        "❌ Please check your input and try again."
-                                    } else if error.contains("HTTP 401") || error.contains("HTTP 403") {
-                                        "🔐 Authentication error. Please try again later."
-                                    } else if error.contains("HTTP 500") {
-                                        "🔧 Server error. Please try again in a few moments."
-                                    } else if error.contains("Failed to fetch") || error.contains("network") {
-                                        "🌐 Network error. Please check your connection and try again."
-                                    } else {
-                                        "⚠️ An unexpected error occurred. Please try again."
-                                    };
-                                    submit_status.set(Some(format!("{} Error details: {}", error_msg, error)));
+                                    submit_status.set(Some("⚠️ Your message couldn't be sent. Please try again in a few moments, or email me directly.".to_string()));
                                 }
                             }
-                            
+
                             is_submitting.set(false);
                         });
                     },
@@ -238,4 +275,26 @@ pub fn Contact() -> Element {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// A labelled form field with its error, if any, underneath.
+#[component]
+fn Field(id: String, label: String, error: Option<String>, children: Element) -> Element {
+    rsx! {
+        div {
+            label { class: "block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300", r#for: "{id}", "{label}" }
+            {children}
+            if let Some(error) = error {
+                p { id: "{id}-error", class: "mt-1 text-sm text-red-600 dark:text-red-400", role: "alert", "{error}" }
+            }
+        }
+    }
+}
+
+fn input_class(invalid: bool) -> &'static str {
+    if invalid {
+        "w-full px-4 py-3 rounded-lg border border-red-500 dark:border-red-400 dark:bg-gray-800 focus:outline-none focus:ring-2 focus:ring-red-500 focus:border-transparent transition-all duration-200"
+    } else {
+        "w-full px-4 py-3 rounded-lg border border-gray-300 dark:border-gray-600 dark:bg-gray-800 focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-transparent transition-all duration-200"
+    }
+}
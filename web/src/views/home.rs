@@ -1,4 +1,5 @@
 use dioxus::prelude::*;
+use crate::components::{seo::SITE_NAME, Seo};
 use crate::Route;
 
 
@@ -10,6 +11,11 @@ pub fn Home() -> Element {
 
 
     rsx! {
+        Seo {
+            title: SITE_NAME,
+            description: "Software developer and mortgage professional writing about finance, technology and 3D printing.",
+            path: Route::Home {}.to_string(),
+        }
         div { class: "flex flex-col",
             // Hero Section
             div { class: "flex-1 flex flex-col justify-center items-center text-center py-6",
@@ -2,11 +2,32 @@ mod home;
 pub use home::Home;
 
 mod blog;
-pub use blog::Blog;
+pub use blog::{Blog, BlogPaged};
+
+mod admin_blog;
+pub use admin_blog::{AdminBlog, AdminBlogEdit, AdminBlogNew};
+
+mod admin_blog_history;
+pub use admin_blog_history::AdminBlogHistory;
+
+mod admin_comments;
+pub use admin_comments::AdminComments;
+
+mod admin_inbox;
+pub use admin_inbox::AdminInbox;
+
+mod blog_tags;
+pub use blog_tags::{BlogTag, BlogTags};
+
+mod blog_series;
+pub use blog_series::BlogSeries;
 
 mod blog_by_id;
 pub use blog_by_id::BlogPostDetail;
 
+mod blog_comments;
+mod blog_webmentions;
+
 mod navbar;
 pub use navbar::Navbar;
 
@@ -22,6 +43,9 @@ pub use projects::Projects;
 mod resume;
 pub use resume::Resume;
 
+mod search;
+pub use search::Search;
+
 mod layout;
 pub use layout::AppLayout;
 mod login;
@@ -30,4 +54,4 @@ pub mod protected;
 pub use protected::Protected;
 pub mod callback;
 pub use callback::Callback;
-mod routes;
\ No newline at end of file
+pub mod routes;
\ No newline at end of file
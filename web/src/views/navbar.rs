@@ -1,14 +1,27 @@
+use crate::api::auth::access_token;
+use crate::api::inbox::unread_contact_count;
 use crate::Route;
 use dioxus::prelude::*;
 use dioxus_router::prelude::use_route;
 
+use super::admin_inbox::INBOX_CHANGED;
+
 #[component]
 pub fn Navbar(children: Element) -> Element {
     let current_route = use_route::<Route>();
+    let mut search_query = use_signal(String::new);
+    // Unread contact messages, for authors only: anyone else gets an error
+    // and no inbox link. Asked again after every navigation, so the link
+    // turns up on signing in, and whenever the inbox changes a message.
+    let unread = use_resource(use_reactive((&current_route,), |(_route,)| {
+        INBOX_CHANGED();
+        async move { unread_contact_count(access_token()?).await.ok() }
+    }));
 
-    // Helper function to determine active class
+    // Helper function to determine active class. Compares variants only, so
+    // e.g. every page of the blog listing marks "Blog" as active.
     fn active_class(route: &Route, current_route: &Route, class: &str) -> String {
-        if route == current_route {
+        if std::mem::discriminant(route) == std::mem::discriminant(current_route) {
             format!("{} text-CustomHover font-medium border-b-2 border-CustomHover", class)
         } else {
             class.to_string()
@@ -52,9 +65,9 @@ pub fn Navbar(children: Element) -> Element {
                         }
 
                         Link {
-                            to: Route::Blog {},
+                            to: Route::Blog { page: 1 },
                             class: active_class(
-                                &Route::Blog {},
+                                &Route::Blog { page: 1 },
                                 &current_route,
                                 "text-CustomAccent hover:text-CustomAccentDarker px-1 py-2 text-sm font-medium transition-colors",
                             ),
@@ -71,7 +84,42 @@ pub fn Navbar(children: Element) -> Element {
                         }
                     }
 
-                    // Right side (CTA/resume)
+                    // Right side (search + CTA)
+                    form {
+                        role: "search",
+                        class: "hidden md:block ml-auto",
+                        onsubmit: move |evt: FormEvent| {
+                            evt.prevent_default();
+                            let q = search_query();
+                            if !q.trim().is_empty() {
+                                search_query.set(String::new());
+                                navigator().push(Route::Search { q });
+                            }
+                        },
+                        input {
+                            r#type: "search",
+                            name: "q",
+                            value: "{search_query}",
+                            placeholder: "Search...",
+                            aria_label: "Search the site",
+                            class: "w-40 lg:w-56 rounded-md border border-gray-300 bg-white/80 px-3 py-1.5 text-sm text-gray-900 focus:outline-none focus:ring-2 focus:ring-CustomHover",
+                            oninput: move |evt| search_query.set(evt.value()),
+                        }
+                    }
+
+                    if let Some(Some(unread)) = *unread.read() {
+                        Link {
+                            to: Route::AdminInbox {},
+                            class: "ml-4 inline-flex items-center gap-1.5 text-sm font-medium text-CustomAccent hover:text-CustomAccentDarker",
+                            aria_label: if unread == 1 { "Inbox, 1 unread message".to_string() } else { format!("Inbox, {} unread messages", unread) },
+                            "Inbox"
+                            if unread > 0 {
+                                span { class: "min-w-5 rounded-full bg-red-600 px-1.5 text-center text-xs leading-5 text-white",
+                                    "{unread}"
+                                }
+                            }
+                        }
+                    }
 
                     Link {
                         to: Route::Contact {},
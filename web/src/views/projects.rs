@@ -1,38 +1,16 @@
 use dioxus::prelude::*;
+use crate::components::{ResponsiveImage, Seo};
+use crate::Route;
 use crate::api::projects::{get_projects, Project};
 
-
-
-
-
 #[component]
 pub fn Projects() -> Element {
-    let mut projects = use_signal(|| Vec::<Project>::new());
-    let mut loading = use_signal(|| true);
-    let mut error = use_signal(|| Option::<String>::None);
-
-    // Load projects when component mounts
-    use_effect(move || {
-        spawn(async move {
-            loading.set(true);
-            match get_projects().await {
-                Ok(fetched_projects) => {
-                    projects.set(fetched_projects);
-                    loading.set(false);
-                }
-                Err(e) => {
-                    error.set(Some(format!("Failed to load projects: {}", e)));
-                    loading.set(false);
-                }
-            }
-        });
-    });
-
-    // Separate projects by category
-    let web_projects = projects().into_iter().filter(|p| p.category == "web").collect::<Vec<_>>();
-    let printing_projects = projects().into_iter().filter(|p| p.category == "3d-printing").collect::<Vec<_>>();
-
     rsx! {
+        Seo {
+            title: "Projects",
+            description: "A collection of web development, financial tools and 3D printing projects by Dylan Rayburn.",
+            path: Route::Projects {}.to_string(),
+        }
         div { class: "max-w-6xl mx-auto",
             h1 { class: "text-3xl sm:text-4xl font-bold mb-8", "My Projects" }
             // Introduction
@@ -40,57 +18,77 @@ pub fn Projects() -> Element {
                 "Here's a collection of my work spanning web development, financial tools, and 3D printing projects. Each represents my passion for combining technology with practical solutions."
             }
 
-            // Loading state
-            if loading() {
-                div { class: "flex justify-center items-center py-12",
-                    div { class: "animate-spin rounded-full h-12 w-12 border-b-2 border-blue-600" }
-                    span { class: "ml-4 text-lg", "Loading projects..." }
-                }
+            SuspenseBoundary {
+                fallback: |_| rsx! {
+                    div { class: "flex justify-center items-center py-12",
+                        div { class: "animate-spin rounded-full h-12 w-12 border-b-2 border-blue-600" }
+                        span { class: "ml-4 text-lg", "Loading projects..." }
+                    }
+                },
+                ProjectList {}
             }
+        }
+    }
+}
 
-            // Error state
-            if let Some(error_msg) = error() {
-                div { class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-8",
-                    span { class: "font-bold", "Error: " }
-                    span { "{error_msg}" }
-                }
+/// Projects grouped by category, resolved on the server before rendering.
+#[component]
+fn ProjectList() -> Element {
+    let projects = use_server_future(get_projects)?;
+
+    let (projects, error) = match &*projects.read() {
+        Some(Ok(projects)) => (projects.clone(), None),
+        Some(Err(e)) => (Vec::new(), Some(format!("Failed to load projects: {}", e))),
+        None => (Vec::new(), None),
+    };
+
+    // Separate projects by category
+    let web_projects = projects.iter().filter(|p| p.category == "web").cloned().collect::<Vec<_>>();
+    let printing_projects = projects.iter().filter(|p| p.category == "3d-printing").cloned().collect::<Vec<_>>();
+
+    rsx! {
+        // Error state
+        if let Some(error_msg) = error.clone() {
+            div { class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded mb-8",
+                span { class: "font-bold", "Error: " }
+                span { "{error_msg}" }
             }
+        }
 
-            // Web Development Projects
-            if !loading() && !web_projects.is_empty() {
-                section { class: "mb-16",
-                    h2 { class: "text-2xl sm:text-3xl font-bold mb-8 pb-2 border-b border-gray-300 dark:border-gray-600",
-                        "Web Development"
-                    }
-                    div { class: "grid grid-cols-1 md:grid-cols-2 gap-8",
-                        for project in web_projects.iter() {
-                            ProjectCard { project: project.clone() }
-                        }
+        // Web Development Projects
+        if !web_projects.is_empty() {
+            section { class: "mb-16",
+                h2 { class: "text-2xl sm:text-3xl font-bold mb-8 pb-2 border-b border-gray-300 dark:border-gray-600",
+                    "Web Development"
+                }
+                div { class: "grid grid-cols-1 md:grid-cols-2 gap-8",
+                    for project in web_projects.iter() {
+                        ProjectCard { project: project.clone() }
                     }
                 }
             }
+        }
 
-            // 3D Printing Projects
-            if !loading() && !printing_projects.is_empty() {
-                section { class: "mb-16",
-                    h2 { class: "text-2xl sm:text-3xl font-bold mb-8 pb-2 border-b border-gray-300 dark:border-gray-600",
-                        "3D Printing & CAD Designs"
-                    }
-                    div { class: "grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8",
-                        for project in printing_projects.iter() {
-                            ProjectCard3D { project: project.clone() }
-                        }
+        // 3D Printing Projects
+        if !printing_projects.is_empty() {
+            section { class: "mb-16",
+                h2 { class: "text-2xl sm:text-3xl font-bold mb-8 pb-2 border-b border-gray-300 dark:border-gray-600",
+                    "3D Printing & CAD Designs"
+                }
+                div { class: "grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-8",
+                    for project in printing_projects.iter() {
+                        ProjectCard3D { project: project.clone() }
                     }
                 }
             }
+        }
 
-            // Fallback content if no projects loaded but no error
-            if !loading() && projects().is_empty() && error().is_none() {
-                div { class: "text-center py-12",
-                    h3 { class: "text-xl font-semibold mb-4", "No projects found" }
-                    p { class: "text-gray-600",
-                        "Projects will appear here once they're added to the database."
-                    }
+        // Fallback content if no projects loaded but no error
+        if projects.is_empty() && error.is_none() {
+            div { class: "text-center py-12",
+                h3 { class: "text-xl font-semibold mb-4", "No projects found" }
+                p { class: "text-gray-600",
+                    "Projects will appear here once they're added to the database."
                 }
             }
         }
@@ -141,10 +139,12 @@ fn ProjectCard3D(project: Project) -> Element {
     
     rsx! {
         div { class: "border border-gray-200 bg-CustomAccent dark:border-gray-700 rounded-lg overflow-hidden hover:shadow-lg transition-shadow",
-            img {
+            ResponsiveImage {
                 class: "w-full h-48 object-cover",
-                src: "{project.image_url.as_deref().unwrap_or(\"/placeholder.png\")}",
+                src: project.image_url.clone().unwrap_or_else(|| "/placeholder.png".to_string()),
+                variants: project.image_variants.clone(),
                 alt: "{project.title}",
+                sizes: "(min-width: 1024px) 33vw, (min-width: 768px) 50vw, 100vw",
             }
             div { class: "p-6",
                 h3 { class: "text-xl text-CustomBackground font-semibold mb-2", "{project.title}" }
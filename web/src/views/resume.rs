@@ -1,8 +1,15 @@
 use dioxus::prelude::*;
+use crate::components::Seo;
+use crate::Route;
 
 #[component]
 pub fn Resume() -> Element {
     rsx! {
+        Seo {
+            title: "Resume",
+            description: "Experience, education and skills of Dylan Rayburn, software developer and mortgage professional.",
+            path: Route::Resume {}.to_string(),
+        }
         div { class: "max-w-6xl mx-auto",
             // Experience Section
             section { class: "mb-12",
@@ -3,14 +3,77 @@ use dioxus::prelude::{Router as DRouter, *};
 use crate::api::auth::get_user;
 
 
-/// Register the protected state of routes here
-fn is_guarded(current: Route) -> bool {
-    match current {
-        Route::Protected {} => true,
-        _ => false,
+/// Who a route is for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Anyone, crawlers included.
+    Public,
+    /// Signed-in users; the guard sends everyone else to sign in.
+    Guarded,
+    /// Where the guard sends visitors to sign in, and where they come back
+    /// to; only useful in the middle of signing in.
+    SignIn,
+}
+
+impl Access {
+    /// Register the protected state of routes here. Every route is listed, so
+    /// a new one has to say who it is for.
+    pub fn of(current: &Route) -> Access {
+        match current {
+            Route::Home {}
+            | Route::Blog { .. }
+            | Route::BlogPaged { .. }
+            | Route::BlogTags {}
+            | Route::BlogTag { .. }
+            | Route::BlogSeries { .. }
+            | Route::BlogPostDetail { .. }
+            | Route::About {}
+            | Route::Contact {}
+            | Route::Projects {}
+            | Route::Resume {}
+            | Route::Search { .. } => Access::Public,
+            Route::Protected {}
+            | Route::AdminBlog {}
+            | Route::AdminBlogNew {}
+            | Route::AdminBlogEdit { .. }
+            | Route::AdminBlogHistory { .. }
+            | Route::AdminComments {}
+            | Route::AdminInbox {} => Access::Guarded,
+            Route::Login {} | Route::Callback {} => Access::SignIn,
+        }
+    }
+}
+
+pub fn is_guarded(current: Route) -> bool {
+    Access::of(&current) == Access::Guarded
+}
+
+/// Whether a route may be advertised to crawlers (sitemap, robots.txt):
+/// neither guarded nor part of signing in.
+pub fn is_public(current: &Route) -> bool {
+    !is_guarded(current.clone()) && Access::of(current) != Access::SignIn
+}
+
+/// Where `route` lives, as links to it should be written: the first page of
+/// the blog is plain `/blog` rather than `/blog?page=1`, the static export's
+/// `/blog/page/2` is `/blog?page=2`, and search without a query plain
+/// `/search`.
+pub fn canonical_path(route: &Route) -> String {
+    match route {
+        Route::Blog { page } if *page <= 1 => "/blog".to_string(),
+        Route::BlogPaged { page } => canonical_path(&Route::Blog { page: *page }),
+        Route::Search { q } if q.is_empty() => "/search".to_string(),
+        route => route.to_string(),
     }
 }
 
+/// Every page crawlers should know about: the public routes without path
+/// arguments. Query arguments take their defaults, so the blog listing is
+/// its first page.
+pub fn public_routes() -> Vec<Route> {
+    Route::static_routes().into_iter().filter(is_public).collect()
+}
+
 #[component]
 pub fn Router() -> Element {
     rsx! {
@@ -78,4 +141,23 @@ pub fn protected(redirect: Route, next: Route) {
 }
 
 #[cfg(target_arch = "wasm32")]
-use instant as _;
\ No newline at end of file
+use instant as _;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_routes_are_canonical_and_leave_out_sign_in() {
+        let paths: Vec<String> = public_routes().iter().map(canonical_path).collect();
+        assert_eq!(paths, ["/", "/blog", "/blog/tags", "/about", "/contact", "/projects", "/resume", "/search"]);
+    }
+
+    #[test]
+    fn every_route_that_is_not_public_is_kept_out() {
+        for route in [Route::Login {}, Route::Callback {}, Route::Protected {}, Route::AdminInbox {}] {
+            assert!(!is_public(&route), "{}", route);
+        }
+        assert!(is_guarded(Route::AdminBlogEdit { id: 1 }));
+        assert!(!is_guarded(Route::Login {}));
+    }
+}
@@ -0,0 +1,132 @@
+use dioxus::prelude::*;
+use crate::api::{search, SearchHit, SearchKind};
+use crate::components::Seo;
+use crate::Route;
+
+/// How long typing has to pause before the results update.
+const SEARCH_DEBOUNCE_MS: u32 = 250;
+
+/// `/search?q=`: results update as you type. The query lives in the URL, so
+/// results pages can be linked and are rendered on the server.
+#[component]
+pub fn Search(q: ReadOnlySignal<String>) -> Element {
+    let mut draft = use_signal(|| q.cloned());
+
+    // Follow the URL when a search starts elsewhere, e.g. the navbar box.
+    use_effect(move || draft.set(q()));
+
+    let on_input = move |evt: FormEvent| {
+        let value = evt.value();
+        draft.set(value.clone());
+        spawn(async move {
+            #[cfg(target_arch = "wasm32")]
+            {
+                use gloo_timers::future::TimeoutFuture;
+                TimeoutFuture::new(SEARCH_DEBOUNCE_MS).await;
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                tokio::time::sleep(std::time::Duration::from_millis(SEARCH_DEBOUNCE_MS as u64)).await;
+            }
+            if *draft.peek() == value {
+                navigator().replace(Route::Search { q: value });
+            }
+        });
+    };
+
+    rsx! {
+        Seo {
+            title: "Search",
+            description: "Search blog posts and projects.",
+            path: Route::Search { q: String::new() }.to_string(),
+        }
+        div { class: "max-w-4xl mx-auto",
+            h1 { class: "text-3xl sm:text-4xl font-bold mb-8", "Search" }
+            form {
+                role: "search",
+                class: "mb-10",
+                onsubmit: move |evt: FormEvent| {
+                    evt.prevent_default();
+                    navigator().replace(Route::Search { q: draft() });
+                },
+                input {
+                    r#type: "search",
+                    name: "q",
+                    value: "{draft}",
+                    autofocus: true,
+                    placeholder: "Search posts and projects...",
+                    aria_label: "Search posts and projects",
+                    class: "w-full px-4 py-3 text-lg rounded-lg border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-800 focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                    oninput: on_input,
+                }
+            }
+            if !q().trim().is_empty() {
+                SuspenseBoundary {
+                    fallback: |_| rsx! {
+                        div { class: "flex justify-center py-12",
+                            div { class: "animate-spin rounded-full h-12 w-12 border-t-2 border-b-2 border-indigo-600 dark:border-indigo-400" }
+                        }
+                    },
+                    SearchResults { q }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SearchResults(q: ReadOnlySignal<String>) -> Element {
+    let hits = use_server_future(move || search(q()))?;
+
+    let hits: Vec<SearchHit> = match &*hits.read() {
+        Some(Ok(hits)) => hits.clone(),
+        Some(Err(e)) => {
+            return rsx! {
+                div { class: "p-6 mb-8 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-red-800 dark:text-red-200 rounded-lg",
+                    strong { "Search failed: " }
+                    "{e}"
+                }
+            };
+        }
+        None => Vec::new(),
+    };
+
+    rsx! {
+        if hits.is_empty() {
+            p { class: "text-gray-500 dark:text-gray-400 text-center py-12", "No results for \"{q}\"." }
+        } else {
+            ul { class: "space-y-6 pb-10",
+                for hit in hits {
+                    SearchResult { key: "{hit.kind:?}-{hit.id}", hit }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SearchResult(hit: SearchHit) -> Element {
+    let (label, to) = match (hit.kind, hit.slug.clone()) {
+        (SearchKind::Post, Some(slug)) => ("Blog post", Route::BlogPostDetail { slug }),
+        (SearchKind::Post, None) => ("Blog post", Route::Blog { page: 1 }),
+        (SearchKind::Project, _) => ("Project", Route::Projects {}),
+    };
+
+    rsx! {
+        li { class: "p-6 bg-white dark:bg-gray-800 rounded-xl shadow",
+            span { class: "text-xs font-medium uppercase tracking-wide text-indigo-600 dark:text-indigo-400", "{label}" }
+            h2 { class: "text-xl font-bold text-gray-900 dark:text-white mt-1 mb-2",
+                Link { to, class: "hover:text-indigo-600 dark:hover:text-indigo-400", "{hit.title}" }
+            }
+            p { class: "text-gray-600 dark:text-gray-300",
+                for part in hit.snippet {
+                    if part.highlight {
+                        mark { class: "bg-yellow-200 dark:bg-yellow-700 dark:text-white rounded-sm px-0.5", "{part.text}" }
+                    } else {
+                        "{part.text}"
+                    }
+                }
+            }
+        }
+    }
+}